@@ -1,29 +1,117 @@
 use std::any::Any;
+use std::borrow::Cow;
+use std::str::FromStr;
 
-use alloy_dyn_abi::JsonAbiExt;
-use alloy_json_abi::{Function, JsonAbi};
-use alloy_primitives::hex;
+use alloy_dyn_abi::{DecodedEvent, DynSolType, DynSolValue, EventExt, FunctionExt, JsonAbiExt, Specifier};
+use alloy_json_abi::{AbiItem, Error as AbiError, Event, Function, JsonAbi, Param, StateMutability};
+use alloy_primitives::{hex, B256};
 
-use model::Error;
+use model::receipt::Receipt;
+use model::{Error, HexString};
 
+use crate::decode::{check_bounds, split_selector};
 use crate::encode::convert_arguments;
 
 pub struct Abi<'a> {
     abi: &'a str,
 }
 
+/// 持有abi字符串的`Abi`，例如由多个abi片段合并而来
+#[derive(Debug, Clone)]
+pub struct OwnedAbi {
+    abi: String,
+}
+
+impl OwnedAbi {
+    pub fn abi(&self) -> Abi<'_> {
+        Abi::new(&self.abi)
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.abi
+    }
+}
+
 impl<'a> Abi<'a> {
     pub fn new(abi: &'a str) -> Self {
         Abi { abi }
     }
 
+    /// # 合并多个abi片段，例如基础合约和扩展合约的abi，适用于diamond/proxy等接口为多个合约并集的场景
+    ///
+    /// 签名相同且完全一致的条目只保留一个
+    ///
+    /// ## 入参
+    /// + `fragments: &[&str]`: abi片段
+    ///
+    /// ## 出参
+    /// + `Result<OwnedAbi, Error>`: 片段不合法，或者存在签名相同但返回值、状态可变性、indexed不同的方法和事件，或者选择器冲突时返回`Err`
+    pub fn merge(fragments: &[&str]) -> Result<OwnedAbi, Error> {
+        let mut merged = JsonAbi::new();
+        for fragment in fragments {
+            let abi = Abi::new(fragment).try_parse()?;
+            merge_optional(&mut merged.constructor, abi.constructor, "constructor")?;
+            merge_optional(&mut merged.fallback, abi.fallback, "fallback")?;
+            merge_optional(&mut merged.receive, abi.receive, "receive")?;
+            for function in abi.functions.into_values().flatten() {
+                let existing = merged.functions.values().flatten().find(|f| f.selector() == function.selector());
+                match existing {
+                    Some(f) if f.signature() != function.signature() => {
+                        return Err(Error::new(&format!("function selector of {} conflicts with {}", function.signature(), f.signature())));
+                    }
+                    Some(f) if f.signature_with_outputs() != function.signature_with_outputs() || f.state_mutability != function.state_mutability => {
+                        return Err(Error::new(&format!("conflicting definitions of function {}: {} and {}", function.signature(), f.full_signature(), function.full_signature())));
+                    }
+                    Some(_) => {}
+                    None => merged.functions.entry(function.name.clone()).or_default().push(function),
+                }
+            }
+            for event in abi.events.into_values().flatten() {
+                let existing = merged.events.values().flatten().find(|e| e.signature() == event.signature());
+                match existing {
+                    Some(e) if e.full_signature() != event.full_signature() || e.anonymous != event.anonymous => {
+                        return Err(Error::new(&format!("conflicting definitions of event {}: {} and {}", event.signature(), e.full_signature(), event.full_signature())));
+                    }
+                    Some(_) => {}
+                    None => merged.events.entry(event.name.clone()).or_default().push(event),
+                }
+            }
+            for error in abi.errors.into_values().flatten() {
+                let existing = merged.errors.values().flatten().find(|e| e.selector() == error.selector());
+                match existing {
+                    Some(e) if e.signature() != error.signature() => {
+                        return Err(Error::new(&format!("error selector of {} conflicts with {}", error.signature(), e.signature())));
+                    }
+                    Some(_) => {}
+                    None => merged.errors.entry(error.name.clone()).or_default().push(error),
+                }
+            }
+        }
+        let abi = serde_json::to_string(&merged)?;
+        Ok(OwnedAbi { abi })
+    }
+
     pub fn parse(&self) -> JsonAbi {
-        let abi: JsonAbi = serde_json::from_str(&self.abi).unwrap();
-        abi
+        self.try_parse().unwrap()
+    }
+
+    /// # 解析abi
+    ///
+    /// ## 出参
+    /// + `Result<JsonAbi, Error>`: abi不合法时返回`Err`，`source`为serde的解析错误
+    pub fn try_parse(&self) -> Result<JsonAbi, Error> {
+        serde_json::from_str(self.abi)
+            .map_err(|e| Error::new(&format!("invalid abi: {}", e)).with_source(e))
     }
 
+    /// # 按方法名查找方法，方法名包含`(`时按完整签名查找，见`function_by_signature`
+    ///
+    /// 按方法名查找重载的方法时返回abi中的第一个
     pub fn function(&self, function_name: String) -> Result<Function, Error> {
-        let abi = self.parse();
+        if function_name.contains('(') {
+            return self.function_by_signature(&function_name);
+        }
+        let abi = self.try_parse()?;
         let functions = abi.functions;
         if !functions.contains_key(&function_name) {
             return Err(Error::new(&format!("function {} not found in abi", function_name)));
@@ -32,19 +120,402 @@ impl<'a> Abi<'a> {
         Ok(function)
     }
 
+    /// # 按完整签名查找方法，用于区分重载的方法
+    ///
+    /// ## 入参
+    /// + `signature: &str`: 方法签名，例如`transfer(address,uint256)`，忽略空白字符
+    ///
+    /// ## 出参
+    /// + `Result<Function, Error>`: 签名不在abi中时返回`Err`
+    pub fn function_by_signature(&self, signature: &str) -> Result<Function, Error> {
+        let signature: String = signature.chars().filter(|c| !c.is_whitespace()).collect();
+        let name = signature.split('(').next().unwrap_or_default();
+        let abi = self.try_parse()?;
+        abi.functions
+            .get(name)
+            .and_then(|functions| functions.iter().find(|function| function.signature() == signature))
+            .cloned()
+            .ok_or_else(|| Error::new(&format!("function {} not found in abi", signature)))
+    }
+
+    pub fn event(&self, event_name: &str) -> Result<Event, Error> {
+        let abi = self.try_parse()?;
+        abi.events
+            .get(event_name)
+            .and_then(|events| events.first())
+            .cloned()
+            .ok_or_else(|| Error::new(&format!("event {} not found in abi", event_name)))
+    }
+
     pub fn encode(&self, function_name: &str, args: Vec<Box<dyn Any>>) -> String {
-        let function = &self.function(function_name.to_string()).unwrap();
-        let args = convert_arguments(function.inputs.clone(), args).unwrap();
-        let data_bytes = function.abi_encode_input(args.as_slice()).unwrap();
-        format!("0x{}", hex::encode(data_bytes))
+        self.try_encode_hex(function_name, args).unwrap()
+    }
+
+    /// # 编码合约方法的调用
+    ///
+    /// ## 入参
+    /// + `function_name: &str`: 方法名，重载的方法使用完整签名，例如`foo(uint256,string)`
+    /// + `args: Vec<Box<dyn Any>>`: 实参
+    ///
+    /// ## 出参
+    /// + `Result<Vec<u8>, Error>`: 方法不存在、实参不匹配或编码失败时返回`Err`
+    pub fn try_encode(&self, function_name: &str, args: Vec<Box<dyn Any>>) -> Result<Vec<u8>, Error> {
+        let function = &self.function(function_name.to_string())?;
+        let args = convert_arguments(function.inputs.clone(), args)?;
+        function.abi_encode_input(args.as_slice()).map_err(|e| Error::new(&e.to_string()).with_source(e))
+    }
+
+    /// # 编码合约方法的调用，返回`0x`开头的十六进制字符串
+    ///
+    /// ## 入参
+    /// + `function_name: &str`: 方法名
+    /// + `args: Vec<Box<dyn Any>>`: 实参
+    ///
+    /// ## 出参
+    /// + `Result<String, Error>`
+    pub fn try_encode_hex(&self, function_name: &str, args: Vec<Box<dyn Any>>) -> Result<String, Error> {
+        let data_bytes = self.try_encode(function_name, args)?;
+        Ok(HexString::from(&data_bytes).hex_string)
+    }
+
+    /// # 编码合约构造函数的参数，部署合约时拼接在字节码之后
+    ///
+    /// ## 入参
+    /// + `args: Vec<Box<dyn Any>>`: 构造函数的实参
+    ///
+    /// ## 出参
+    /// + `Result<String, Error>`: 不带`0x`前缀的十六进制字符串，可直接拼接在字节码之后；abi中没有构造函数时只接受空的实参并返回空字符串，实参不匹配或编码失败时返回`Err`
+    pub fn encode_constructor(&self, args: Vec<Box<dyn Any>>) -> Result<String, Error> {
+        let constructor = match self.try_parse()?.constructor {
+            Some(constructor) => constructor,
+            None if args.is_empty() => return Ok(String::new()),
+            None => return Err(Error::new(&format!("constructor not found in abi, but got {} args", args.len()))),
+        };
+        let args = convert_arguments(constructor.inputs.clone(), args)?;
+        let data_bytes = constructor.abi_encode_input(args.as_slice()).map_err(|e| Error::new(&e.to_string()).with_source(e))?;
+        Ok(hex::encode(data_bytes))
+    }
+
+    /// # 编码合约方法的调用，并校验附带的转账金额是否符合方法的状态可变性
+    ///
+    /// ## 入参
+    /// + `function_name: &str`: 方法名
+    /// + `args: Vec<Box<dyn Any>>`: 实参
+    /// + `amount: Option<u128>`: 附带的转账金额，只有`payable`的方法可以附带非零的金额
+    ///
+    /// ## 出参
+    /// + `Result<String, Error>`
+    pub fn encode_with_value(&self, function_name: &str, args: Vec<Box<dyn Any>>, amount: Option<u128>) -> Result<String, Error> {
+        let function = &self.function(function_name.to_string())?;
+        let amount = amount.unwrap_or(0);
+        if amount > 0 && function.state_mutability != StateMutability::Payable {
+            return Err(Error::new(&format!(
+                "function {} is {}, can not receive amount {}",
+                function_name,
+                function.state_mutability.as_str().unwrap_or("nonpayable"),
+                amount
+            )));
+        }
+        let args = convert_arguments(function.inputs.clone(), args)?;
+        let data_bytes = function.abi_encode_input(args.as_slice()).map_err(|e| Error::new(&e.to_string()).with_source(e))?;
+        Ok(HexString::from(&data_bytes).hex_string)
+    }
+
+    /// # 解码合约方法的返回值，解码前会校验数据中声明的偏移量和长度不超出实际数据
+    ///
+    /// ## 入参
+    /// + `function_name: &str`: 方法名
+    /// + `data: &[u8]`: 合约的返回数据，即回执中的`contractRet`
+    ///
+    /// ## 出参
+    /// + `Result<Vec<DynSolValue>, Error>`: 方法不存在、数据不一致或解码失败时返回`Err`
+    pub fn decode_output(&self, function_name: &str, data: &[u8]) -> Result<Vec<DynSolValue>, Error> {
+        let function = self.function(function_name.to_string())?;
+        let types = function.outputs.iter()
+            .map(|param| param.resolve())
+            .collect::<Result<Vec<DynSolType>, _>>()
+            .map_err(|e| Error::new(&e.to_string()).with_source(e))?;
+        check_bounds(&types, data)?;
+        function.abi_decode_output(data, true).map_err(|e| Error::new(&e.to_string()).with_source(e))
+    }
+
+    /// # 解码合约方法的返回值为json，每个返回值对应数组中的一个元素
+    ///
+    /// 整数转换为十进制字符串，字节和地址转换为`0x`开头的十六进制字符串，元组转换为以成员名为键的对象，成员名为空时以下标为键
+    ///
+    /// ## 入参
+    /// + `function_name: &str`: 方法名
+    /// + `data: &[u8]`: 合约的返回数据，即回执中的`contractRet`
+    ///
+    /// ## 出参
+    /// + `Result<serde_json::Value, Error>`: 方法不存在、数据不一致或解码失败时返回`Err`
+    pub fn decode_output_json(&self, function_name: &str, data: &[u8]) -> Result<serde_json::Value, Error> {
+        let function = self.function(function_name.to_string())?;
+        let values = self.decode_output(function_name, data)?;
+        let json = function.outputs.iter()
+            .zip(values.iter())
+            .map(|(param, value)| value_to_json(value, &param.components))
+            .collect();
+        Ok(serde_json::Value::Array(json))
+    }
+
+    /// # 解码合约方法的返回值，并将每个返回值与abi中的名称配对
+    ///
+    /// 元组会展开为成员，名称以`.`连接，例如`user.name`，未命名元组的成员不加前缀；名称为空的返回值以下标为名称
+    ///
+    /// ## 入参
+    /// + `function_name: &str`: 方法名
+    /// + `data: &[u8]`: 合约的返回数据，即回执中的`contractRet`
+    ///
+    /// ## 出参
+    /// + `Result<Vec<(String, DynSolValue)>, Error>`: 方法不存在、数据不一致或解码失败时返回`Err`
+    pub fn decode_output_named(&self, function_name: &str, data: &[u8]) -> Result<Vec<(String, DynSolValue)>, Error> {
+        let function = self.function(function_name.to_string())?;
+        let values = self.decode_output(function_name, data)?;
+        let mut named = Vec::with_capacity(values.len());
+        flatten_named(&function.outputs, values, "", &mut named);
+        Ok(named)
+    }
+
+    /// # 解码合约事件
+    ///
+    /// ## 入参
+    /// + `event_name: &str`: 事件名
+    /// + `topics: &[String]`: 事件的主题，非匿名事件的第一个主题为事件选择器
+    /// + `data: &[u8]`: 事件的数据
+    ///
+    /// ## 出参
+    /// + `Result<DecodedEvent, Error>`: 事件不存在、选择器不一致或解码失败时返回`Err`
+    pub fn decode_event(&self, event_name: &str, topics: &[String], data: &[u8]) -> Result<DecodedEvent, Error> {
+        let event = self.event(event_name)?;
+        let topics = topics.iter()
+            .map(|topic| B256::from_str(topic).map_err(|e| Error::new(&format!("invalid topic {}", topic)).with_source(e)))
+            .collect::<Result<Vec<B256>, Error>>()?;
+        event.decode_log_parts(topics, data, true).map_err(|e| Error::new(&e.to_string()).with_source(e))
+    }
+
+    /// # 解码合约事件为json对象，以参数名为键，参数名为空时以下标为键
+    ///
+    /// indexed参数从主题中解码，其余参数从数据中解码，值的格式与`decode_output_json`相同；indexed的动态类型参数在主题中只有哈希，解码为`0x`开头的32字节
+    ///
+    /// ## 入参
+    /// + `event_name: &str`: 事件名
+    /// + `topics: &[String]`: 事件的主题，即回执中事件的`topics`
+    /// + `data: &[u8]`: 事件的数据，即回执中事件的`data_hex`
+    ///
+    /// ## 出参
+    /// + `Result<serde_json::Value, Error>`: 事件不存在、选择器不一致或解码失败时返回`Err`
+    pub fn decode_event_json(&self, event_name: &str, topics: &[String], data: &[u8]) -> Result<serde_json::Value, Error> {
+        let event = self.event(event_name)?;
+        let decoded = self.decode_event(event_name, topics, data)?;
+        let mut indexed = decoded.indexed.iter();
+        let mut body = decoded.body.iter();
+        let mut object = serde_json::Map::new();
+        for (i, param) in event.inputs.iter().enumerate() {
+            let value = if param.indexed { indexed.next() } else { body.next() }
+                .ok_or_else(|| Error::new(&format!("missing value of event parameter {}", param.name)))?;
+            let key = if param.name.is_empty() { i.to_string() } else { param.name.clone() };
+            object.insert(key, value_to_json(value, &param.components));
+        }
+        Ok(serde_json::Value::Object(object))
+    }
+
+    /// # 解码合约回滚时返回的自定义错误，例如`error InsufficientBalance(uint256 available)`
+    ///
+    /// ## 入参
+    /// + `data: &[u8]`: 回滚数据，由4字节的错误选择器和abi编码的参数组成
+    ///
+    /// ## 出参
+    /// + `Result<(String, Vec<(String, DynSolValue)>), Error>`: 错误名和参数名、参数值，选择器不在abi中或解码失败时返回`Err`
+    pub fn decode_error(&self, data: &[u8]) -> Result<(String, Vec<(String, DynSolValue)>), Error> {
+        let (selector, args) = split_selector(data)
+            .ok_or_else(|| Error::new(&format!("revert data 0x{} is shorter than a selector", hex::encode(data))))?;
+        let abi = self.try_parse()?;
+        let error = abi.errors()
+            .find(|error| error.selector().as_slice() == selector)
+            .ok_or_else(|| Error::new(&format!("error selector 0x{} not found in abi", hex::encode(selector))))?;
+        let values = error.abi_decode_input(args, true).map_err(|e| Error::new(&e.to_string()).with_source(e))?;
+        let params = error.inputs.iter()
+            .map(|param| param.name.clone())
+            .zip(values)
+            .collect();
+        Ok((error.name.clone(), params))
+    }
+
+    /// # 交易的失败原因，返回数据为abi中的自定义错误时解码为`InsufficientBalance(available: 100)`的形式
+    ///
+    /// ## 入参
+    /// + `receipt: &Receipt`: 交易回执
+    ///
+    /// ## 出参
+    /// + `Option<String>`: 执行成功时为None；不是abi中的自定义错误时同`Receipt::revert_reason`
+    pub fn revert_reason(&self, receipt: &Receipt) -> Option<String> {
+        receipt.revert_reason_with(|data| {
+            let (name, params) = self.decode_error(data).ok()?;
+            let args: Vec<String> = params.iter()
+                .map(|(param, value)| {
+                    let value = match value_to_json(value, &[]) {
+                        serde_json::Value::String(s) => s,
+                        value => value.to_string(),
+                    };
+                    if param.is_empty() { value } else { format!("{}: {}", param, value) }
+                })
+                .collect();
+            Some(format!("{}({})", name, args.join(", ")))
+        })
+    }
+
+    /// # 规范化的abi json，方法、事件和错误按照签名排序，类型别名统一为规范的类型，例如`uint`为`uint256`
+    ///
+    /// 条目顺序不同但等价的abi得到相同的json，可用于计算abi的哈希或者按内容寻址存储
+    ///
+    /// ## 出参
+    /// + `Result<String, Error>`: abi不合法时返回`Err`
+    pub fn to_canonical_json(&self) -> Result<String, Error> {
+        let abi = self.try_parse()?;
+        let mut items = vec![];
+        if let Some(mut constructor) = abi.constructor {
+            normalize_params(&mut constructor.inputs);
+            items.push(AbiItem::Constructor(Cow::Owned(constructor)));
+        }
+        if let Some(fallback) = abi.fallback {
+            items.push(AbiItem::Fallback(Cow::Owned(fallback)));
+        }
+        if let Some(receive) = abi.receive {
+            items.push(AbiItem::Receive(Cow::Owned(receive)));
+        }
+
+        let mut functions: Vec<Function> = abi.functions.into_values().flatten().collect();
+        for function in functions.iter_mut() {
+            normalize_params(&mut function.inputs);
+            normalize_params(&mut function.outputs);
+        }
+        functions.sort_by_cached_key(|function| function.signature());
+        items.extend(functions.into_iter().map(|function| AbiItem::Function(Cow::Owned(function))));
+
+        let mut events: Vec<Event> = abi.events.into_values().flatten().collect();
+        for event in events.iter_mut() {
+            for param in event.inputs.iter_mut() {
+                param.ty = normalize_type(&param.ty);
+                normalize_params(&mut param.components);
+            }
+        }
+        events.sort_by_cached_key(|event| event.signature());
+        items.extend(events.into_iter().map(|event| AbiItem::Event(Cow::Owned(event))));
+
+        let mut errors: Vec<AbiError> = abi.errors.into_values().flatten().collect();
+        for error in errors.iter_mut() {
+            normalize_params(&mut error.inputs);
+        }
+        errors.sort_by_cached_key(|error| error.signature());
+        items.extend(errors.into_iter().map(|error| AbiItem::Error(Cow::Owned(error))));
+
+        Ok(serde_json::to_string(&items)?)
+    }
+}
+
+/// # 将解码后的值转换为json，`components`为元组或元组数组的成员
+fn value_to_json(value: &DynSolValue, components: &[Param]) -> serde_json::Value {
+    use serde_json::Value;
+
+    match value {
+        DynSolValue::Bool(b) => Value::Bool(*b),
+        DynSolValue::Int(i, _) => Value::String(i.to_string()),
+        DynSolValue::Uint(u, _) => Value::String(u.to_string()),
+        DynSolValue::FixedBytes(word, size) => Value::String(format!("0x{}", hex::encode(&word[..*size]))),
+        DynSolValue::Address(address) => Value::String(format!("0x{}", hex::encode(address))),
+        DynSolValue::Function(function) => Value::String(format!("0x{}", hex::encode(function))),
+        DynSolValue::Bytes(bytes) => Value::String(format!("0x{}", hex::encode(bytes))),
+        DynSolValue::String(s) => Value::String(s.clone()),
+        DynSolValue::Array(values) | DynSolValue::FixedArray(values) => {
+            Value::Array(values.iter().map(|value| value_to_json(value, components)).collect())
+        }
+        DynSolValue::Tuple(values) => {
+            let object = values.iter()
+                .enumerate()
+                .map(|(i, value)| {
+                    let component = components.get(i);
+                    let key = match component {
+                        Some(component) if !component.name.is_empty() => component.name.clone(),
+                        _ => i.to_string(),
+                    };
+                    let nested = component.map(|component| component.components.as_slice()).unwrap_or_default();
+                    (key, value_to_json(value, nested))
+                })
+                .collect();
+            Value::Object(object)
+        }
+    }
+}
+
+/// # 将解码后的值与参数名配对，元组递归展开为成员，`prefix`为外层元组的名称
+fn flatten_named(params: &[Param], values: Vec<DynSolValue>, prefix: &str, named: &mut Vec<(String, DynSolValue)>) {
+    for (i, value) in values.into_iter().enumerate() {
+        let param = params.get(i);
+        let name = param.map(|param| param.name.as_str()).unwrap_or_default();
+        match (param, value) {
+            (Some(param), DynSolValue::Tuple(members)) if !param.components.is_empty() => {
+                let prefix = match (prefix.is_empty(), name.is_empty()) {
+                    (_, true) => prefix.to_string(),
+                    (true, false) => name.to_string(),
+                    (false, false) => format!("{}.{}", prefix, name),
+                };
+                flatten_named(&param.components, members, &prefix, named);
+            }
+            (_, value) => {
+                let name = if name.is_empty() { i.to_string() } else { name.to_string() };
+                let key = if prefix.is_empty() { name } else { format!("{}.{}", prefix, name) };
+                named.push((key, value));
+            }
+        }
+    }
+}
+
+/// # 规范化参数及其元组成员的类型
+fn normalize_params(params: &mut [Param]) {
+    for param in params.iter_mut() {
+        param.ty = normalize_type(&param.ty);
+        normalize_params(&mut param.components);
+    }
+}
+
+/// # 将类型别名转换为规范的类型，保留数组后缀，例如`uint[2][]`为`uint256[2][]`
+fn normalize_type(ty: &str) -> String {
+    let (base, suffix) = ty.split_at(ty.find('[').unwrap_or(ty.len()));
+    let base = match base {
+        "uint" => "uint256",
+        "int" => "int256",
+        "byte" => "bytes1",
+        "fixed" => "fixed128x18",
+        "ufixed" => "ufixed128x18",
+        other => other,
+    };
+    format!("{}{}", base, suffix)
+}
+
+/// # 合并构造函数、fallback、receive，两个片段都定义时必须一致
+fn merge_optional<T: PartialEq>(merged: &mut Option<T>, item: Option<T>, kind: &str) -> Result<(), Error> {
+    match (merged.as_ref(), item) {
+        (Some(existing), Some(item)) if *existing != item => Err(Error::new(&format!("conflicting definitions of {}", kind))),
+        (None, Some(item)) => {
+            *merged = Some(item);
+            Ok(())
+        }
+        _ => Ok(()),
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use alloy_dyn_abi::DynSolValue;
+    use alloy_primitives::{hex, Address, B256, U256};
+
+    use model::receipt::Receipt;
 
     use crate::abi::Abi;
 
+    const EVENT_ABI: &str = r#"[{"anonymous": false, "inputs": [{"indexed": true, "internalType": "uint64", "name": "protocolUri", "type": "uint64"}, {"indexed": false, "internalType": "address", "name": "updater", "type": "address"}], "name": "ProtocolAdded", "type": "event"}]"#;
+
     const LEDGER_ABI: &str = r#"[
       {
         "inputs": [
@@ -269,4 +740,362 @@ mod tests {
         let expected = "0xef7e9858000000000000000000000000000000000000000000000000000000000000006400000000000000000000000000000000000000000000000000000000000000400000000000000000000000000000000000000000000000000000000000000001516482b2880721149f75c9aea3b6a6a700022c78561f6e22fbd0d4f73e5e7432";
         assert_eq!(expected, data);
     }
-}
\ No newline at end of file
+
+    const PAYABLE_ABI: &str = r#"[
+      {
+        "inputs": [],
+        "name": "deposit",
+        "outputs": [],
+        "stateMutability": "payable",
+        "type": "function"
+      },
+      {
+        "inputs": [],
+        "name": "balance",
+        "outputs": [
+          {
+            "internalType": "uint256",
+            "name": "",
+            "type": "uint256"
+          }
+        ],
+        "stateMutability": "view",
+        "type": "function"
+      }
+    ]"#;
+
+    #[test]
+    fn test_encode_with_value_payable() {
+        let abi = Abi::new(PAYABLE_ABI);
+        let data = abi.encode_with_value("deposit", vec![], Some(100)).unwrap();
+        assert_eq!("0xd0e30db0", data);
+    }
+
+    #[test]
+    fn test_encode_with_value_view() {
+        let abi = Abi::new(PAYABLE_ABI);
+        assert!(abi.encode_with_value("balance", vec![], Some(100)).is_err());
+        assert_eq!("0xb69ef8a8", abi.encode_with_value("balance", vec![], None).unwrap());
+        assert_eq!("0xb69ef8a8", abi.encode_with_value("balance", vec![], Some(0)).unwrap());
+    }
+
+    #[test]
+    fn test_try_encode_unknown_function() {
+        let abi = Abi::new(PAYABLE_ABI);
+        assert!(abi.try_encode("withdraw", vec![]).is_err());
+        assert!(abi.try_encode_hex("withdraw", vec![]).is_err());
+    }
+
+    #[test]
+    fn test_try_encode_bad_argument() {
+        let abi = Abi::new(LEDGER_ABI);
+        let err = abi.try_encode("addProtocol", vec![Box::new("not-a-number"), Box::new(vec!["0x516482b2880721149f75c9aea3b6a6a700022c78561f6e22fbd0d4f73e5e7432"])]).unwrap_err();
+        assert!(err.to_string().contains("invalid number value not-a-number"), "{}", err);
+        let err = abi.try_encode("addProtocol", vec![Box::new(100u64), Box::new(vec!["0xzz"])]).unwrap_err();
+        assert!(err.to_string().contains("invalid hex value"), "{}", err);
+        let err = abi.try_encode("addProtocol", vec![Box::new("100")]).unwrap_err();
+        assert!(err.to_string().contains("inputs len 2 not equals args len 1"), "{}", err);
+        assert_eq!(
+            abi.try_encode_hex("addProtocol", vec![Box::new("100"), Box::new(vec!["0x516482b2880721149f75c9aea3b6a6a700022c78561f6e22fbd0d4f73e5e7432"])]).unwrap(),
+            abi.encode("addProtocol", vec![Box::new("100"), Box::new(vec!["0x516482b2880721149f75c9aea3b6a6a700022c78561f6e22fbd0d4f73e5e7432"])])
+        );
+    }
+
+    const OVERLOAD_ABI: &str = r#"[
+      {"inputs": [{"internalType": "uint256", "name": "a", "type": "uint256"}], "name": "foo", "outputs": [], "stateMutability": "nonpayable", "type": "function"},
+      {"inputs": [{"internalType": "uint256", "name": "a", "type": "uint256"}, {"internalType": "string", "name": "b", "type": "string"}], "name": "foo", "outputs": [{"internalType": "bool", "name": "", "type": "bool"}], "stateMutability": "nonpayable", "type": "function"}
+    ]"#;
+
+    #[test]
+    fn test_function_by_signature() {
+        let abi = Abi::new(OVERLOAD_ABI);
+        assert_eq!(1, abi.function_by_signature("foo(uint256)").unwrap().inputs.len());
+        assert_eq!(2, abi.function_by_signature("foo(uint256, string)").unwrap().inputs.len());
+        assert!(abi.function_by_signature("foo(string)").is_err());
+        assert!(abi.function_by_signature("bar(uint256)").is_err());
+
+        let one = abi.try_encode("foo(uint256)", vec![Box::new("1")]).unwrap();
+        assert_eq!(abi.function_by_signature("foo(uint256)").unwrap().selector().as_slice(), &one[..4]);
+        let two = abi.try_encode("foo(uint256,string)", vec![Box::new("1"), Box::new("lattice")]).unwrap();
+        assert_eq!(abi.function_by_signature("foo(uint256,string)").unwrap().selector().as_slice(), &two[..4]);
+        assert!(abi.try_encode("foo(uint256)", vec![Box::new("1"), Box::new("lattice")]).is_err());
+
+        let data = DynSolValue::Bool(true).abi_encode_params();
+        assert_eq!(abi.decode_output("foo(uint256,string)", &data).unwrap(), vec![DynSolValue::Bool(true)]);
+    }
+
+    const CONSTRUCTOR_ABI: &str = r#"[{"inputs": [{"internalType": "uint256", "name": "supply", "type": "uint256"}, {"internalType": "string", "name": "name", "type": "string"}], "stateMutability": "nonpayable", "type": "constructor"}]"#;
+
+    #[test]
+    fn test_encode_constructor() {
+        let abi = Abi::new(CONSTRUCTOR_ABI);
+        let encoded = abi.encode_constructor(vec![Box::new("1000"), Box::new("lattice")]).unwrap();
+        let expected = DynSolValue::Tuple(vec![
+            DynSolValue::Uint(U256::from(1000), 256),
+            DynSolValue::String(String::from("lattice")),
+        ]).abi_encode_params();
+        assert_eq!(hex::encode(expected), encoded);
+
+        assert!(abi.encode_constructor(vec![Box::new("1000")]).is_err());
+        assert!(abi.encode_constructor(vec![Box::new("abc"), Box::new("lattice")]).is_err());
+        assert_eq!("", Abi::new(OUTPUT_ABI).encode_constructor(vec![]).unwrap());
+        assert!(Abi::new(OUTPUT_ABI).encode_constructor(vec![Box::new("1")]).is_err());
+    }
+
+    const ERROR_ABI: &str = r#"[
+      {
+        "inputs": [
+          {
+            "internalType": "uint256",
+            "name": "available",
+            "type": "uint256"
+          }
+        ],
+        "name": "InsufficientBalance",
+        "type": "error"
+      },
+      {
+        "inputs": [],
+        "name": "Unauthorized",
+        "type": "error"
+      }
+    ]"#;
+
+    #[test]
+    fn test_invalid_abi_error_source() {
+        use std::error::Error as _;
+
+        let abi = Abi::new(r#"[{"name": "transfer", "type": "function""#);
+        let err = abi.try_parse().unwrap_err();
+        assert!(err.source().unwrap().downcast_ref::<serde_json::Error>().is_some());
+
+        let err = abi.try_encode("transfer", vec![]).unwrap_err();
+        assert!(err.source().unwrap().is::<serde_json::Error>());
+    }
+
+    #[test]
+    fn test_decode_error() {
+        let abi = Abi::new(ERROR_ABI);
+        // InsufficientBalance(100)
+        let data = hex::decode("926653510000000000000000000000000000000000000000000000000000000000000064").unwrap();
+        let (name, params) = abi.decode_error(&data).unwrap();
+        assert_eq!(name, "InsufficientBalance");
+        assert_eq!(params, vec![("available".to_string(), DynSolValue::Uint(U256::from(100), 256))]);
+
+        let (name, params) = abi.decode_error(&hex::decode("82b42900").unwrap()).unwrap();
+        assert_eq!(name, "Unauthorized");
+        assert!(params.is_empty());
+
+        // Error(string)不在abi中
+        assert!(abi.decode_error(&hex::decode("08c379a0").unwrap()).is_err());
+        assert!(abi.decode_error(&[0x92, 0x66]).is_err());
+    }
+
+    #[test]
+    fn test_revert_reason() {
+        let receipt = |success: bool, contract_ret: &str| -> Receipt {
+            serde_json::from_value(serde_json::json!({
+                "contractAddress": "zltc_QLbz7JHiBTspUvTPzLHy5biDS9mu53mmv",
+                "contractRet": contract_ret,
+                "dblockHash": "0xe8df1f1e250cd0eac75eee3f8733e26e9422ef5ea88650ab54498cd8e4928144",
+                "dblockNumber": 1,
+                "jouleUsed": 0,
+                "receiptIndex": 0,
+                "success": success,
+                "tblockHash": "0x616bf03baa685df9fddeff4701f170b30176e54120df726142a534f8f2b51873",
+                "version": 3
+            })).unwrap()
+        };
+        let abi = Abi::new(ERROR_ABI);
+
+        let reverted = receipt(false, "0x926653510000000000000000000000000000000000000000000000000000000000000064");
+        assert_eq!(abi.revert_reason(&reverted).as_deref(), Some("InsufficientBalance(available: 100)"));
+        assert_eq!(abi.revert_reason(&receipt(false, "0x82b42900")).as_deref(), Some("Unauthorized()"));
+        // 不在abi中的错误按Error(string)解码，否则返回原始数据
+        let message = "0x08c379a0\
+            0000000000000000000000000000000000000000000000000000000000000020\
+            0000000000000000000000000000000000000000000000000000000000000002\
+            6e6f000000000000000000000000000000000000000000000000000000000000";
+        assert_eq!(abi.revert_reason(&receipt(false, message)).as_deref(), Some("no"));
+        assert_eq!(abi.revert_reason(&receipt(false, "0x12345678")).as_deref(), Some("0x12345678"));
+        assert!(abi.revert_reason(&receipt(true, "0x")).is_none());
+    }
+
+    const OUTPUT_ABI: &str = r#"[
+      {
+        "inputs": [],
+        "name": "name",
+        "outputs": [
+          {
+            "internalType": "string",
+            "name": "",
+            "type": "string"
+          }
+        ],
+        "stateMutability": "view",
+        "type": "function"
+      }
+    ]"#;
+
+    #[test]
+    fn test_decode_output() {
+        let abi = Abi::new(OUTPUT_ABI);
+        let data = DynSolValue::String(String::from("lattice")).abi_encode_params();
+        assert_eq!(abi.decode_output("name", &data).unwrap(), vec![DynSolValue::String(String::from("lattice"))]);
+        assert!(abi.decode_output("symbol", &data).is_err());
+    }
+
+    #[test]
+    fn test_decode_output_oversized_length() {
+        let abi = Abi::new(OUTPUT_ABI);
+        // offset为0x20，声明的长度为2^32，但之后只有32字节的数据
+        let data = hex::decode(concat!(
+            "0000000000000000000000000000000000000000000000000000000000000020",
+            "0000000000000000000000000000000000000000000000000000000100000000",
+            "6c61747469636500000000000000000000000000000000000000000000000000",
+        )).unwrap();
+        let err = abi.decode_output("name", &data).unwrap_err();
+        assert!(err.to_string().contains("exceeds remaining data length 32"), "{}", err);
+    }
+
+    #[test]
+    fn test_decode_output_json() {
+        let abi = Abi::new(LEDGER_ABI);
+        let data = DynSolValue::Uint(U256::from(12), 64).abi_encode_params();
+        assert_eq!(abi.decode_output_json("addProtocol", &data).unwrap(), serde_json::json!(["12"]));
+
+        let updater = Address::repeat_byte(0x11);
+        let evidence = DynSolValue::Tuple(vec![
+            DynSolValue::Uint(U256::from(1), 64),
+            DynSolValue::Uint(U256::from(2), 64),
+            DynSolValue::Address(updater),
+            DynSolValue::Array(vec![DynSolValue::FixedBytes(B256::repeat_byte(0xab), 32)]),
+        ]);
+        let data = DynSolValue::Tuple(vec![DynSolValue::Array(vec![evidence])]).abi_encode_params();
+        assert_eq!(abi.decode_output_json("getTraceability", &data).unwrap(), serde_json::json!([[{
+            "number": "1",
+            "protocol": "2",
+            "updater": format!("0x{}", "11".repeat(20)),
+            "data": [format!("0x{}", "ab".repeat(32))],
+        }]]));
+    }
+
+    const USER_ABI: &str = r#"[{"inputs":[],"name":"getUser","outputs":[{"components":[{"internalType":"uint256","name":"id","type":"uint256"},{"internalType":"string","name":"name","type":"string"},{"internalType":"bool","name":"isMan","type":"bool"},{"internalType":"string[]","name":"tags","type":"string[]"},{"internalType":"uint32[]","name":"levels","type":"uint32[]"}],"internalType":"struct Test.User","name":"","type":"tuple"}],"stateMutability":"view","type":"function"},{"inputs":[],"name":"getOwner","outputs":[{"components":[{"internalType":"string","name":"name","type":"string"}],"internalType":"struct Test.Owner","name":"owner","type":"tuple"},{"internalType":"uint64","name":"","type":"uint64"}],"stateMutability":"view","type":"function"}]"#;
+
+    #[test]
+    fn test_decode_output_named() {
+        let abi = Abi::new(USER_ABI);
+        let user = DynSolValue::Tuple(vec![
+            DynSolValue::Uint(U256::from(1), 256),
+            DynSolValue::String(String::from("Jack")),
+            DynSolValue::Bool(true),
+            DynSolValue::Array(vec![DynSolValue::String(String::from("admin"))]),
+            DynSolValue::Array(vec![DynSolValue::Uint(U256::from(3), 32)]),
+        ]);
+        let data = DynSolValue::Tuple(vec![user]).abi_encode_params();
+        let named = abi.decode_output_named("getUser", &data).unwrap();
+        let keys: Vec<&str> = named.iter().map(|(key, _)| key.as_str()).collect();
+        assert_eq!(keys, vec!["id", "name", "isMan", "tags", "levels"]);
+        assert!(named.contains(&(String::from("name"), DynSolValue::String(String::from("Jack")))));
+        assert!(named.contains(&(String::from("isMan"), DynSolValue::Bool(true))));
+
+        let owner = DynSolValue::Tuple(vec![DynSolValue::String(String::from("Rose"))]);
+        let data = DynSolValue::Tuple(vec![owner, DynSolValue::Uint(U256::from(7), 64)]).abi_encode_params();
+        let named = abi.decode_output_named("getOwner", &data).unwrap();
+        assert_eq!(named, vec![
+            (String::from("owner.name"), DynSolValue::String(String::from("Rose"))),
+            (String::from("1"), DynSolValue::Uint(U256::from(7), 64)),
+        ]);
+    }
+
+    const BASE_FRAGMENT: &str = r#"[
+      {"inputs": [], "name": "owner", "outputs": [{"internalType": "address", "name": "", "type": "address"}], "stateMutability": "view", "type": "function"},
+      {"anonymous": false, "inputs": [{"indexed": true, "internalType": "address", "name": "owner", "type": "address"}], "name": "OwnerChanged", "type": "event"}
+    ]"#;
+
+    const EXTENSION_FRAGMENT: &str = r#"[
+      {"inputs": [], "name": "owner", "outputs": [{"internalType": "address", "name": "", "type": "address"}], "stateMutability": "view", "type": "function"},
+      {"inputs": [{"internalType": "uint256", "name": "amount", "type": "uint256"}], "name": "mint", "outputs": [], "stateMutability": "nonpayable", "type": "function"}
+    ]"#;
+
+    #[test]
+    fn test_merge() {
+        let merged = Abi::merge(&[BASE_FRAGMENT, EXTENSION_FRAGMENT]).unwrap();
+        let abi = merged.abi().parse();
+        assert_eq!(abi.functions.values().flatten().count(), 2);
+        assert_eq!(abi.events.len(), 1);
+        assert_eq!(
+            merged.abi().try_encode_hex("mint", vec![Box::new("100")]).unwrap(),
+            "0xa0712d680000000000000000000000000000000000000000000000000000000000000064"
+        );
+    }
+
+    #[test]
+    fn test_merge_conflict() {
+        let conflicting = r#"[{"inputs": [], "name": "owner", "outputs": [{"internalType": "string", "name": "", "type": "string"}], "stateMutability": "view", "type": "function"}]"#;
+        assert!(Abi::merge(&[BASE_FRAGMENT, conflicting]).is_err());
+
+        let conflicting = r#"[{"anonymous": false, "inputs": [{"indexed": false, "internalType": "address", "name": "owner", "type": "address"}], "name": "OwnerChanged", "type": "event"}]"#;
+        assert!(Abi::merge(&[BASE_FRAGMENT, conflicting]).is_err());
+        assert!(Abi::merge(&[BASE_FRAGMENT, "{"]).is_err());
+    }
+
+    #[test]
+    fn test_to_canonical_json() {
+        let ordered = r#"[
+          {"inputs": [{"internalType": "uint256", "name": "amount", "type": "uint256"}], "name": "mint", "outputs": [], "stateMutability": "nonpayable", "type": "function"},
+          {"inputs": [], "name": "owner", "outputs": [{"internalType": "address", "name": "", "type": "address"}], "stateMutability": "view", "type": "function"},
+          {"anonymous": false, "inputs": [{"indexed": true, "internalType": "address", "name": "owner", "type": "address"}], "name": "OwnerChanged", "type": "event"}
+        ]"#;
+        let reordered = r#"[
+          {"anonymous": false, "inputs": [{"indexed": true, "internalType": "address", "name": "owner", "type": "address"}], "name": "OwnerChanged", "type": "event"},
+          {"type": "function", "stateMutability": "view", "outputs": [{"internalType": "address", "name": "", "type": "address"}], "name": "owner", "inputs": []},
+          {"inputs": [{"internalType": "uint256", "name": "amount", "type": "uint"}], "name": "mint", "outputs": [], "stateMutability": "nonpayable", "type": "function"}
+        ]"#;
+        let canonical = Abi::new(ordered).to_canonical_json().unwrap();
+        assert_eq!(canonical, Abi::new(reordered).to_canonical_json().unwrap());
+        assert!(!canonical.contains(r#""type":"uint""#));
+        assert_eq!(canonical, Abi::new(&canonical).to_canonical_json().unwrap());
+        assert!(Abi::new("{").to_canonical_json().is_err());
+    }
+
+    #[test]
+    fn test_decode_event() {
+        let abi = Abi::new(EVENT_ABI);
+        let selector = abi.event("ProtocolAdded").unwrap().selector();
+        let topics = vec![selector.to_string(), format!("0x{:064x}", 7)];
+        let data = hex::decode("0000000000000000000000005f2be9a02b43f748ee460bf36eed24fafa109920").unwrap();
+        let event = abi.decode_event("ProtocolAdded", &topics, &data).unwrap();
+        assert_eq!(event.indexed, vec![DynSolValue::Uint(U256::from(7), 64)]);
+        assert_eq!(event.body[0].as_address().unwrap().to_string().to_lowercase(), "0x5f2be9a02b43f748ee460bf36eed24fafa109920");
+
+        assert!(abi.decode_event("ProtocolAdded", &topics[1..], &data).is_err());
+        assert!(abi.decode_event("ProtocolRemoved", &topics, &data).is_err());
+    }
+
+    const TRANSFER_EVENT_ABI: &str = r#"[{"anonymous": false, "inputs": [{"indexed": true, "internalType": "address", "name": "from", "type": "address"}, {"indexed": true, "internalType": "address", "name": "to", "type": "address"}, {"indexed": false, "internalType": "uint256", "name": "value", "type": "uint256"}, {"indexed": false, "internalType": "string", "name": "memo", "type": "string"}], "name": "Transfer", "type": "event"}]"#;
+
+    #[test]
+    fn test_decode_event_json() {
+        let abi = Abi::new(TRANSFER_EVENT_ABI);
+        let selector = abi.event("Transfer").unwrap().selector();
+        let topics = vec![
+            selector.to_string(),
+            format!("0x{:0>64}", "11".repeat(20)),
+            format!("0x{:0>64}", "22".repeat(20)),
+        ];
+        let data = DynSolValue::Tuple(vec![
+            DynSolValue::Uint(U256::from(1000), 256),
+            DynSolValue::String(String::from("rent")),
+        ]).abi_encode_params();
+        let event = abi.decode_event_json("Transfer", &topics, &data).unwrap();
+        assert_eq!(event, serde_json::json!({
+            "from": format!("0x{}", "11".repeat(20)),
+            "to": format!("0x{}", "22".repeat(20)),
+            "value": "1000",
+            "memo": "rent",
+        }));
+
+        assert!(abi.decode_event_json("Transfer", &topics[..2], &data).is_err());
+    }
+}
@@ -0,0 +1,179 @@
+use alloy_dyn_abi::DynSolType;
+
+use model::Error;
+
+/// abi编码中一个字的字节长度
+const WORD_SIZE: usize = 32;
+/// 函数选择器的字节长度
+const SELECTOR_SIZE: usize = 4;
+
+/// # 拆分调用数据或回滚数据开头的4字节选择器和之后abi编码的参数，参数不复制
+///
+/// ## 入参
+/// + `data: &[u8]`: 调用数据或回滚数据
+///
+/// ## 出参
+/// + `Option<([u8; 4], &[u8])>`: 选择器和参数，数据不足4字节时返回None
+pub fn split_selector(data: &[u8]) -> Option<([u8; SELECTOR_SIZE], &[u8])> {
+    let selector = data.get(..SELECTOR_SIZE)?.try_into().ok()?;
+    Some((selector, &data[SELECTOR_SIZE..]))
+}
+
+/// # 校验abi编码数据中的偏移量和长度，避免按照伪造的头部分配过大的内存
+///
+/// ## 入参
+/// + `types: &[DynSolType]`: 数据对应的类型，按照元组编码
+/// + `data: &[u8]`: abi编码的数据
+///
+/// ## 出参
+/// + `Result<(), Error>`: 偏移量或声明的长度超出实际数据时返回`Err`
+pub fn check_bounds(types: &[DynSolType], data: &[u8]) -> Result<(), Error> {
+    check_tuple(types.iter(), data)
+}
+
+/// # 校验元组的编码，`data`从元组的起始位置开始
+fn check_tuple<'a>(types: impl Iterator<Item = &'a DynSolType>, data: &[u8]) -> Result<(), Error> {
+    let mut head = 0;
+    for ty in types {
+        if is_dynamic(ty) {
+            let offset = read_word(data, head)?;
+            if offset > data.len() {
+                return Err(Error::new(&format!("abi offset {} exceeds data length {}", offset, data.len())));
+            }
+            check_dynamic(ty, &data[offset..])?;
+            head += WORD_SIZE;
+        } else {
+            head = head
+                .checked_add(static_size(ty))
+                .filter(|end| *end <= data.len())
+                .ok_or_else(|| Error::new(&format!("abi data of {} is too short", ty)))?;
+        }
+    }
+    Ok(())
+}
+
+/// # 校验动态类型的编码，`data`从偏移量指向的位置开始
+fn check_dynamic(ty: &DynSolType, data: &[u8]) -> Result<(), Error> {
+    if let Some(types) = tuple_types(ty) {
+        return check_tuple(types.iter(), data);
+    }
+    match ty {
+        DynSolType::String | DynSolType::Bytes => {
+            let len = read_word(data, 0)?;
+            if len > data.len() - WORD_SIZE {
+                return Err(Error::new(&format!(
+                    "abi {} length {} exceeds remaining data length {}",
+                    ty,
+                    len,
+                    data.len() - WORD_SIZE
+                )));
+            }
+            Ok(())
+        }
+        DynSolType::Array(inner) => {
+            let len = read_word(data, 0)?;
+            let rest = &data[WORD_SIZE..];
+            let head_size = if is_dynamic(inner) { WORD_SIZE } else { static_size(inner) };
+            match len.checked_mul(head_size) {
+                Some(size) if size <= rest.len() => check_tuple(std::iter::repeat_n(inner.as_ref(), len), rest),
+                _ => Err(Error::new(&format!(
+                    "abi {} length {} exceeds remaining data length {}",
+                    ty,
+                    len,
+                    rest.len()
+                ))),
+            }
+        }
+        DynSolType::FixedArray(inner, len) => check_tuple(std::iter::repeat_n(inner.as_ref(), *len), data),
+        _ => Ok(()),
+    }
+}
+
+/// # 读取一个字并转换为`usize`
+fn read_word(data: &[u8], position: usize) -> Result<usize, Error> {
+    let word = data
+        .get(position..position + WORD_SIZE)
+        .ok_or_else(|| Error::new(&format!("abi data is too short to read a word at {}", position)))?;
+    let (high, low) = word.split_at(WORD_SIZE - 8);
+    if high.iter().any(|b| *b != 0) {
+        return Err(Error::new(&format!("abi word 0x{} is too large", alloy_primitives::hex::encode(word))));
+    }
+    usize::try_from(u64::from_be_bytes(low.try_into().unwrap()))
+        .map_err(|e| Error::new(&e.to_string()).with_source(e))
+}
+
+/// 元组或结构体的成员类型
+fn tuple_types(ty: &DynSolType) -> Option<&[DynSolType]> {
+    ty.as_tuple().or_else(|| ty.as_custom_struct().map(|(_, _, types)| types))
+}
+
+fn is_dynamic(ty: &DynSolType) -> bool {
+    if let Some(types) = tuple_types(ty) {
+        return types.iter().any(is_dynamic);
+    }
+    match ty {
+        DynSolType::String | DynSolType::Bytes | DynSolType::Array(_) => true,
+        DynSolType::FixedArray(inner, _) => is_dynamic(inner),
+        _ => false,
+    }
+}
+
+/// 静态类型在头部占用的字节长度
+fn static_size(ty: &DynSolType) -> usize {
+    if let Some(types) = tuple_types(ty) {
+        return types.iter().map(static_size).sum();
+    }
+    match ty {
+        DynSolType::FixedArray(inner, len) => static_size(inner) * len,
+        _ => WORD_SIZE,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy_dyn_abi::{DynSolType, DynSolValue};
+
+    use super::*;
+
+    #[test]
+    fn test_split_selector() {
+        // transfer(address,uint256)
+        let data = alloy_primitives::hex::decode("a9059cbb0000000000000000000000005f2be9a02b43f748ee460bf36eed24fafa1099200000000000000000000000000000000000000000000000000000000000000064").unwrap();
+        let (selector, args) = split_selector(&data).unwrap();
+        assert_eq!(selector, [0xa9, 0x05, 0x9c, 0xbb]);
+        assert_eq!(args.len(), 2 * WORD_SIZE);
+        assert_eq!(read_word(args, WORD_SIZE).unwrap(), 100);
+        assert_eq!(args.as_ptr(), data[SELECTOR_SIZE..].as_ptr());
+
+        assert_eq!(split_selector(&data[..4]), Some(([0xa9, 0x05, 0x9c, 0xbb], &[][..])));
+        assert_eq!(split_selector(&data[..3]), None);
+        assert_eq!(split_selector(&[]), None);
+    }
+
+    #[test]
+    fn test_check_bounds() {
+        let types = vec![DynSolType::String, DynSolType::Array(Box::new(DynSolType::Uint(256))), DynSolType::Bool];
+        let data = DynSolValue::Tuple(vec![
+            DynSolValue::String(String::from("lattice")),
+            DynSolValue::Array(vec![DynSolValue::from(alloy_primitives::U256::from(1)); 3]),
+            DynSolValue::Bool(true),
+        ])
+        .abi_encode_params();
+        assert!(check_bounds(&types, &data).is_ok());
+        assert!(check_bounds(&types, &data[..data.len() - 1]).is_err());
+    }
+
+    #[test]
+    fn test_check_bounds_oversized_length() {
+        let mut data = DynSolValue::String(String::from("lattice")).abi_encode_params();
+        // 声明的长度远大于剩余的数据
+        data[32..64].copy_from_slice(&[0xff; 32]);
+        assert!(check_bounds(&[DynSolType::String], &data).is_err());
+        data[32..56].copy_from_slice(&[0; 24]);
+        assert!(check_bounds(&[DynSolType::String], &data).is_err());
+
+        let mut data = DynSolValue::Array(vec![]).abi_encode_params();
+        data[56..64].copy_from_slice(&u64::MAX.to_be_bytes());
+        assert!(check_bounds(&[DynSolType::Array(Box::new(DynSolType::Uint(256)))], &data).is_err());
+    }
+}
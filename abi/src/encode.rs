@@ -1,14 +1,15 @@
 use std::any::Any;
+use std::collections::HashMap;
 use std::str::FromStr;
 use std::string::String;
 use std::string::ToString;
 
 use alloy_dyn_abi::DynSolValue;
 use alloy_json_abi::Param;
-use alloy_primitives::{Address as SolAddress, B256, I256, U256};
+use alloy_primitives::{hex, Address as SolAddress, B256, I256, U256};
 use regex::Regex;
 
-use model::{Error, HexString};
+use model::Error;
 use model::common::Address;
 
 const BOOL_TY: &str = "bool";
@@ -16,6 +17,34 @@ const ADDRESS_TY: &str = "address";
 const STRING_TY: &str = "string";
 const TUPLE_TY: &str = "tuple";
 
+/// 可以作为Tuple类型实参的结构体，按照字段名而不是字段顺序匹配abi中Tuple的子类型
+///
+/// ```ignore
+/// impl Tokenizable for User {
+///     fn into_named_fields(self) -> HashMap<String, Box<dyn Any>> {
+///         HashMap::from([
+///             ("id".to_string(), Box::new(self.id) as Box<dyn Any>),
+///             ("name".to_string(), Box::new(self.name)),
+///         ])
+///     }
+/// }
+///
+/// let args: Vec<Box<dyn Any>> = vec![Box::new(user.into_named_fields())];
+/// ```
+pub trait Tokenizable {
+    /// # 转换为以字段名为键的实参
+    fn into_named_fields(self) -> HashMap<String, Box<dyn Any>>;
+}
+
+/// Tuple类型实参的各个字段
+#[derive(Clone, Copy)]
+enum TupleFields<'a> {
+    /// 按照子类型的顺序排列的字段，`Vec<Box<dyn Any>>`
+    Positional(&'a [Box<dyn Any>]),
+    /// 以子类型的名称为键的字段，`HashMap<String, Box<dyn Any>>`
+    Named(&'a HashMap<String, Box<dyn Any>>),
+}
+
 /// # 转换参数为Rust abi中对应的类型数据
 /// ## 入参
 /// + `types: Vec<Param>`: abi中方法入参(行参)描述
@@ -66,15 +95,8 @@ pub fn convert_argument(ty: &str, components: Vec<Param>, arg: &Box<dyn Any>) ->
             let arg_str = arg.downcast_ref::<&str>();
             let arg_string = arg.downcast_ref::<String>();
             return match (arg_str, arg_string) {
-                (Some(v), _) => {
-                    let v = *v;
-                    let b: bool = v.to_lowercase().parse().unwrap();
-                    Ok(DynSolValue::Bool(b))
-                }
-                (_, Some(v)) => {
-                    let b: bool = v.to_lowercase().parse().unwrap();
-                    Ok(DynSolValue::Bool(b))
-                }
+                (Some(v), _) => parse_bool(v).map(DynSolValue::Bool),
+                (_, Some(v)) => parse_bool(v).map(DynSolValue::Bool),
                 _ => Err(Error::new(&format!("invalid arg type, {} expected input string value", ty))),
             };
         }
@@ -82,155 +104,335 @@ pub fn convert_argument(ty: &str, components: Vec<Param>, arg: &Box<dyn Any>) ->
             let arg_str = arg.downcast_ref::<&str>();
             let arg_string = arg.downcast_ref::<String>();
             return match (arg_str, arg_string) {
-                (Some(v), _) => {
-                    let addr = Address::new(*v);
-                    Ok(DynSolValue::Address(SolAddress::parse_checksummed(addr.to_ethereum_address(), None).expect("invalid address checksum")))
-                }
-                (_, Some(v)) => {
-                    let addr = Address::new(v);
-                    Ok(DynSolValue::Address(SolAddress::parse_checksummed(addr.to_ethereum_address(), None).expect("invalid address checksum")))
-                }
+                (Some(v), _) => parse_address(v).map(DynSolValue::Address),
+                (_, Some(v)) => parse_address(v).map(DynSolValue::Address),
                 _ => Err(Error::new(&format!("invalid arg type, {} expected input string value", ty))),
             };
         }
         TUPLE_TY => {
-            let arg = arg.downcast_ref::<Vec<Box<dyn Any>>>();
-            return match arg {
+            match tuple_fields(arg.as_ref()) {
                 None => Err(Error::new(&format!("unsupported arg type, {}", ty))),
-                Some(v) => {
-                    if v.len() != components.len() {
-                        return Err(Error::new(&format!("{} expected field count is {}, but actual field count is {}", ty, components.len(), v.len())));
-                    }
-                    let mut converted_arg_vec: Vec<DynSolValue> = Vec::new();
-                    for (i, elem) in v.iter().enumerate() {
-                        let param_type = components.get(i).unwrap().ty.clone();
-                        let converted = convert_argument(param_type.as_str(), vec![], elem).unwrap();
-                        converted_arg_vec.push(converted);
-                    }
-                    Ok(DynSolValue::Tuple(converted_arg_vec))
-                }
-            };
+                Some(fields) => convert_tuple(ty, &components, fields),
+            }
         }
         _ if is_bytes(ty) => {
             let (_, size) = parse_bytes(ty);
-            let arg_str = arg.downcast_ref::<&str>();
-            let arg_string = arg.downcast_ref::<String>();
-            return match (arg_str, arg_string) {
-                (Some(v), _) => {
-                    let bytes = HexString::new(v).decode();
-                    if size > 0 && bytes.len() != size {
-                        return Err(Error::new(&format!("{} expected length is {}, but actual length is {}", ty, size, bytes.len())));
-                    }
-                    if size > 0 {
-                        Ok(DynSolValue::FixedBytes(B256::from_slice(bytes.as_slice()), size))
-                    } else {
-                        Ok(DynSolValue::Bytes(bytes))
-                    }
-                }
-                (_, Some(v)) => {
-                    let bytes = HexString::new(v).decode();
-                    if size > 0 && bytes.len() != size {
-                        return Err(Error::new(&format!("{} expected length is {}, but actual length is {}", ty, size, bytes.len())));
-                    }
-                    if size > 0 {
-                        Ok(DynSolValue::FixedBytes(B256::from_slice(bytes.as_slice()), size))
-                    } else {
-                        Ok(DynSolValue::Bytes(bytes))
-                    }
+            if size > 32 {
+                return Err(Error::new(&format!("unsupported arg type, {}", ty)));
+            }
+            let bytes = bytes_arg(arg.as_ref())
+                .ok_or_else(|| Error::new(&format!("invalid arg type, {} expected input hex &str or Vec<u8> value", ty)))?;
+            let bytes = match bytes {
+                BytesArg::Hex(v) => hex::decode(v).map_err(|e| Error::new(&format!("invalid hex value {}", v)).with_source(e))?,
+                BytesArg::Raw(v) => v.to_vec(),
+                BytesArg::Utf8(_) if size > 0 => {
+                    return Err(Error::new(&format!("invalid arg type, {} does not accept Utf8Bytes", ty)));
                 }
-                _ => Err(Error::new(&format!("invalid arg type, {} expected input &str value", ty))),
+                BytesArg::Utf8(v) => v.as_bytes().to_vec(),
             };
+            bytes_value(ty, size, bytes)
+        }
+        _ if is_tuple_array(ty) => {
+            let size = parse_tuple_array(ty);
+            let elements = tuple_array_elements(arg)
+                .ok_or_else(|| Error::new(&format!("invalid arg type, {} expected input Vec<Vec<Box<dyn Any>>> or Vec<HashMap<String, Box<dyn Any>>> value", ty)))?;
+            if size > 0 && elements.len() != size {
+                return Err(Error::new(&format!("{} expected length is {}, but actual length is {}", ty, size, elements.len())));
+            }
+            let mut converted_arg_vec: Vec<DynSolValue> = Vec::new();
+            for elem in elements {
+                converted_arg_vec.push(convert_tuple(ty, &components, elem)?);
+            }
+            if size > 0 {
+                Ok(DynSolValue::FixedArray(converted_arg_vec))
+            } else {
+                Ok(DynSolValue::Array(converted_arg_vec))
+            }
         }
         _ if is_array(ty) => {
+            // 每次只展开最外层的维度，内层类型(可能仍是数组)递归转换
             let (child_ty, size) = parse_array(ty);
-            let arg_vec_str = arg.downcast_ref::<Vec<&str>>();
-            let arg_vec_string = arg.downcast_ref::<Vec<String>>();
-            return match (arg_vec_str, arg_vec_string) {
-                (Some(v), _) => {
-                    if size > 0 && v.len() != size {
-                        return Err(Error::new(&format!("{} expected length is {}, but actual length is {}", ty, size, v.len())));
-                    }
-                    let mut converted_arg_vec: Vec<DynSolValue> = Vec::new();
-                    for elem in v {
-                        let boxed_arg: Box<dyn Any> = Box::new(*elem);
-                        let converted = convert_argument(child_ty.as_str(), vec![], &boxed_arg).unwrap();
-                        converted_arg_vec.push(converted);
-                    }
-                    if size > 0 {
-                        Ok(DynSolValue::FixedArray(converted_arg_vec))
-                    } else {
-                        Ok(DynSolValue::Array(converted_arg_vec))
-                    }
-                }
-                (_, Some(v)) => {
-                    if size > 0 && v.len() != size {
-                        return Err(Error::new(&format!("{} expected length is {}, but actual length is {}", ty, size, v.len())));
-                    }
-                    let mut converted_arg_vec: Vec<DynSolValue> = Vec::new();
-                    for elem in v {
-                        let boxed_arg: Box<dyn Any> = Box::new(elem.clone());
-                        let converted = convert_argument(child_ty.as_str(), vec![], &boxed_arg).unwrap();
-                        converted_arg_vec.push(converted);
-                    }
-                    if size > 0 {
-                        Ok(DynSolValue::FixedArray(converted_arg_vec))
-                    } else {
-                        Ok(DynSolValue::Array(converted_arg_vec))
-                    }
+            let owned: Vec<Box<dyn Any>>;
+            let elements: Vec<&Box<dyn Any>> = match arg.downcast_ref::<Vec<Box<dyn Any>>>() {
+                Some(v) => v.iter().collect(),
+                None => {
+                    owned = array_elements(arg.as_ref())
+                        .ok_or_else(|| Error::new(&format!("invalid arg type, {} expected input Vec<&str> value", ty)))??;
+                    owned.iter().collect()
                 }
-                _ => Err(Error::new(&format!("invalid arg type, {} expected input Vec<&str> value", ty))),
             };
+            if size > 0 && elements.len() != size {
+                return Err(Error::new(&format!("{} expected length is {}, but actual length is {}", ty, size, elements.len())));
+            }
+            let mut converted_arg_vec: Vec<DynSolValue> = Vec::new();
+            for elem in elements {
+                converted_arg_vec.push(convert_argument(child_ty.as_str(), components.clone(), elem)?);
+            }
+            if size > 0 {
+                Ok(DynSolValue::FixedArray(converted_arg_vec))
+            } else {
+                Ok(DynSolValue::Array(converted_arg_vec))
+            }
         }
         _ if is_uint(ty) => {
             let (_, size) = parse_uint(ty);
-            if size == 0 {
+            if size == 0 || size > 256 || size % 8 != 0 {
                 return Err(Error::new(&format!("unsupported arg type, {}", ty)));
             }
-            let arg_str = arg.downcast_ref::<&str>();
-            let arg_string = arg.downcast_ref::<String>();
-            return match (arg_str, arg_string) {
-                (Some(v), _) => {
-                    let num = U256::from_str(*v).unwrap();
-                    Ok(DynSolValue::Uint(num, size))
-                }
-                (_, Some(v)) => {
-                    let num = U256::from_str(v).unwrap();
-                    Ok(DynSolValue::Uint(num, size))
-                }
-                _ => Err(Error::new(&format!("invalid arg type, {} expected input &str value", ty))),
-            };
+            let v = number_arg(arg.as_ref())
+                .ok_or_else(|| Error::new(&format!("invalid arg type, {} expected input &str or integer value", ty)))?;
+            parse_unsigned(ty, size, &v).map(|num| DynSolValue::Uint(num, size))
         }
         _ if is_int(ty) => {
             let (_, size) = parse_int(ty);
-            if size == 0 {
+            if size == 0 || size > 256 || size % 8 != 0 {
                 return Err(Error::new(&format!("unsupported arg type, {}", ty)));
             }
-            let arg_str = arg.downcast_ref::<&str>();
-            let arg_string = arg.downcast_ref::<String>();
-            return match (arg_str, arg_string) {
-                (Some(v), _) => {
-                    let num = I256::from_str(*v).unwrap();
-                    Ok(DynSolValue::Int(num, size))
-                }
-                (_, Some(v)) => {
-                    let num = I256::from_str(v).unwrap();
-                    Ok(DynSolValue::Int(num, size))
-                }
-                _ => Err(Error::new(&format!("invalid arg type, {} expected input &str value", ty))),
-            };
+            let v = number_arg(arg.as_ref())
+                .ok_or_else(|| Error::new(&format!("invalid arg type, {} expected input &str or integer value", ty)))?;
+            parse_signed(ty, size, &v).map(|num| DynSolValue::Int(num, size))
         }
         _ => Err(Error::new(&format!("unsupported arg type, {}", ty)))
     }
 }
 
+/// # 转换Tuple类型的参数
+/// ## 入参
+/// + `ty: &str`: 参数的类型
+/// + `components: &[Param]`: Tuple的子类型
+/// + `fields: TupleFields`: Tuple的各个字段的实参，按名称传入时每个子类型都必须有同名的字段，且不能有多余的字段
+///
+/// ## 出参
+/// + `Result<DynSolValue, Error>`
+fn convert_tuple(ty: &str, components: &[Param], fields: TupleFields<'_>) -> Result<DynSolValue, Error> {
+    let field_count = match fields {
+        TupleFields::Positional(v) => v.len(),
+        TupleFields::Named(v) => v.len(),
+    };
+    if field_count != components.len() {
+        return Err(Error::new(&format!("{} expected field count is {}, but actual field count is {}", ty, components.len(), field_count)));
+    }
+    let mut converted_arg_vec: Vec<DynSolValue> = Vec::new();
+    for (i, component) in components.iter().enumerate() {
+        let elem = match fields {
+            TupleFields::Positional(v) => &v[i],
+            TupleFields::Named(v) => v
+                .get(&component.name)
+                .ok_or_else(|| Error::new(&format!("{} missing field {}", ty, component.name)))?,
+        };
+        let converted = convert_argument(component.ty.as_str(), component.components.clone(), elem)?;
+        converted_arg_vec.push(converted);
+    }
+    Ok(DynSolValue::Tuple(converted_arg_vec))
+}
+
+/// # 取出tuple的各个字段的实参
+///
+/// 支持按顺序排列的`Vec<Box<dyn Any>>`和以字段名为键的`HashMap<String, Box<dyn Any>>`，其它类型返回`None`
+fn tuple_fields(arg: &dyn Any) -> Option<TupleFields<'_>> {
+    if let Some(v) = arg.downcast_ref::<Vec<Box<dyn Any>>>() {
+        return Some(TupleFields::Positional(v));
+    }
+    arg.downcast_ref::<HashMap<String, Box<dyn Any>>>().map(TupleFields::Named)
+}
+
+/// # 取出tuple数组的各个元素，每个元素为tuple各个字段的实参
+///
+/// 支持`Vec<Vec<Box<dyn Any>>>`、`Vec<HashMap<String, Box<dyn Any>>>`和元素为tuple实参的`Vec<Box<dyn Any>>`，其它类型返回`None`
+fn tuple_array_elements(arg: &Box<dyn Any>) -> Option<Vec<TupleFields<'_>>> {
+    if let Some(v) = arg.downcast_ref::<Vec<Vec<Box<dyn Any>>>>() {
+        return Some(v.iter().map(|elem| TupleFields::Positional(elem)).collect());
+    }
+    if let Some(v) = arg.downcast_ref::<Vec<HashMap<String, Box<dyn Any>>>>() {
+        return Some(v.iter().map(TupleFields::Named).collect());
+    }
+    arg.downcast_ref::<Vec<Box<dyn Any>>>()?
+        .iter()
+        .map(|elem| tuple_fields(elem.as_ref()))
+        .collect()
+}
+
+/// # 将数组类型的实参展开为各个元素的实参，再逐个按照内层类型转换
+///
+/// 支持`Vec<&str>`、`Vec<String>`、`Vec<u64>`、`Vec<U256>`、`Vec<bool>`和`Vec<Address>`，以及它们嵌套一层的二维数组，
+/// 例如`Vec<Vec<&str>>`，更多维度可以使用元素为`Box<dyn Any>`的`Vec<Box<dyn Any>>`，其它类型返回`None`
+fn array_elements(arg: &dyn Any) -> Option<Result<Vec<Box<dyn Any>>, Error>> {
+    if let Some(v) = scalar_elements(arg) {
+        return Some(v.map(|v| v.into_iter().map(|s| Box::new(s) as Box<dyn Any>).collect()));
+    }
+
+    macro_rules! nested_elements {
+        ($($ty:ty),*) => {
+            $(
+                if let Some(v) = arg.downcast_ref::<Vec<Vec<$ty>>>() {
+                    return Some(
+                        v.iter()
+                            .map(|inner| scalar_elements(inner).expect("supported element type").map(|s| Box::new(s) as Box<dyn Any>))
+                            .collect(),
+                    );
+                }
+            )*
+        };
+    }
+    nested_elements!(&str, String, u64, U256, bool, Address);
+    None
+}
+
+/// # 将一维数组的实参展开为字符串形式的元素，`Vec<Address>`中有不合法的地址时返回`Some(Err)`
+fn scalar_elements(arg: &dyn Any) -> Option<Result<Vec<String>, Error>> {
+    fn stringify<T: ToString>(v: &[T]) -> Result<Vec<String>, Error> {
+        Ok(v.iter().map(ToString::to_string).collect())
+    }
+
+    if let Some(v) = arg.downcast_ref::<Vec<&str>>() {
+        return Some(stringify(v));
+    }
+    if let Some(v) = arg.downcast_ref::<Vec<String>>() {
+        return Some(Ok(v.clone()));
+    }
+    if let Some(v) = arg.downcast_ref::<Vec<u64>>() {
+        return Some(stringify(v));
+    }
+    if let Some(v) = arg.downcast_ref::<Vec<U256>>() {
+        return Some(stringify(v));
+    }
+    if let Some(v) = arg.downcast_ref::<Vec<bool>>() {
+        return Some(stringify(v));
+    }
+    arg.downcast_ref::<Vec<Address>>().map(|v| {
+        v.iter()
+            .map(|addr| match addr.addr.starts_with(addr.config().title.as_str()) {
+                true => addr.try_to_ethereum_address(),
+                false => Ok(addr.addr.clone()),
+            })
+            .collect()
+    })
+}
+
+/// # 取出数值类型的实参，支持`&str`、`String`和`u8`-`u128`、`i8`-`i128`、`U256`、`I256`，整数统一转换为十进制字符串后再解析和校验位宽
+fn number_arg(arg: &dyn Any) -> Option<String> {
+    if let Some(v) = arg.downcast_ref::<&str>() {
+        return Some(v.to_string());
+    }
+    if let Some(v) = arg.downcast_ref::<String>() {
+        return Some(v.clone());
+    }
+
+    macro_rules! integer_arg {
+        ($($ty:ty),*) => {
+            $(
+                if let Some(v) = arg.downcast_ref::<$ty>() {
+                    return Some(v.to_string());
+                }
+            )*
+        };
+    }
+    integer_arg!(u8, u16, u32, u64, u128, i8, i16, i32, i64, i128, U256, I256);
+    None
+}
+
+/// # 解析bool类型的实参，不区分大小写
+fn parse_bool(v: &str) -> Result<bool, Error> {
+    v.to_lowercase().parse()
+        .map_err(|e| Error::new(&format!("invalid bool value {}", v)).with_source(e))
+}
+
+/// # 解析address类型的实参，支持Lattice地址和以太坊地址，Lattice地址会校验版本字节和校验和
+fn parse_address(v: &str) -> Result<SolAddress, Error> {
+    let addr = Address::new(v);
+    let eth = if v.starts_with(addr.config().title.as_str()) {
+        addr.try_to_ethereum_address()?
+    } else {
+        v.to_string()
+    };
+    SolAddress::from_str(&eth).map_err(|e| Error::new(&format!("invalid address value {}", v)).with_source(e))
+}
+
+/// # bytes类型的实参：hex字符串(可带0x前缀)、原始字节或者按UTF-8编码的字符串
+enum BytesArg<'a> {
+    Hex(&'a str),
+    Raw(&'a [u8]),
+    Utf8(&'a str),
+}
+
+/// # 按UTF-8编码而不是hex解析的`bytes`实参，只能用于动态长度的`bytes`
+///
+/// 例如`Box::new(Utf8Bytes("hello".to_string()))`编码为`0x68656c6c6f`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Utf8Bytes(pub String);
+
+/// # 读取bytes类型的实参，支持`&str`、`String`、`Vec<u8>`、`&[u8]`、`Utf8Bytes`
+fn bytes_arg(arg: &dyn Any) -> Option<BytesArg<'_>> {
+    if let Some(v) = arg.downcast_ref::<&str>() {
+        return Some(BytesArg::Hex(v));
+    }
+    if let Some(v) = arg.downcast_ref::<String>() {
+        return Some(BytesArg::Hex(v));
+    }
+    if let Some(v) = arg.downcast_ref::<Vec<u8>>() {
+        return Some(BytesArg::Raw(v));
+    }
+    if let Some(v) = arg.downcast_ref::<&[u8]>() {
+        return Some(BytesArg::Raw(v));
+    }
+    arg.downcast_ref::<Utf8Bytes>().map(|v| BytesArg::Utf8(&v.0))
+}
+
+/// # 转换bytes类型的实参，`size`为0时为动态长度的bytes，否则为`size`字节的定长bytes
+fn bytes_value(ty: &str, size: usize, bytes: Vec<u8>) -> Result<DynSolValue, Error> {
+    if size == 0 {
+        return Ok(DynSolValue::Bytes(bytes));
+    }
+    if bytes.len() != size {
+        return Err(Error::new(&format!("{} expected length is {}, but actual length is {}", ty, size, bytes.len())));
+    }
+    Ok(DynSolValue::FixedBytes(B256::right_padding_from(&bytes), size))
+}
+
+/// # 数值解析失败的错误，底层的解析错误作为`source`
+fn invalid_number<E: std::error::Error + Send + Sync + 'static>(v: &str, e: E) -> Error {
+    Error::new(&format!("invalid number value {}", v)).with_source(e)
+}
+
+/// # 解析uint类型的实参，`0x`前缀按十六进制解析，否则按十进制解析，并校验数值不超过`size`位
+fn parse_unsigned(ty: &str, size: usize, v: &str) -> Result<U256, Error> {
+    let v = v.trim();
+    let num = match v.strip_prefix("0x") {
+        Some(hex) => U256::from_str_radix(hex, 16),
+        None => U256::from_str_radix(v, 10),
+    }
+    .map_err(|e| invalid_number(v, e))?;
+    if num.bit_len() > size {
+        return Err(Error::new(&format!("{} out of range for {}", v, ty)));
+    }
+    Ok(num)
+}
+
+/// # 解析int类型的实参，支持可选的`-`前缀，`0x`前缀按十六进制解析，否则按十进制解析，并校验数值在`size`位的补码范围内
+fn parse_signed(ty: &str, size: usize, v: &str) -> Result<I256, Error> {
+    let v = v.trim();
+    let num = if v.trim_start_matches(['+', '-']).starts_with("0x") {
+        I256::from_hex_str(v)
+    } else {
+        I256::from_dec_str(v)
+    }
+    .map_err(|e| invalid_number(v, e))?;
+    if num.bits() as usize > size {
+        return Err(Error::new(&format!("{} out of range for {}", v, ty)));
+    }
+    Ok(num)
+}
+
 /// 匹配 solidity 的byte1-byte32类型
 const SOL_TY_BYTES_REGEX: &str = r"^(bytes)([1-9]*)$";
-/// 匹配 solidity 的uint1-uint256类型
-const SOL_TY_UINT_REGEX: &str = r"^(uint)([1-9]*)$";
-/// 匹配 solidity 的int1-int256类型
-const SOL_TY_INT_REGEX: &str = r"^(int)([1-9]*)$";
-/// 匹配 solidity 的 array 类型，Example: string[], bool[], bytes32[], uint256[]...
-const SOL_TY_ARRAY_REGEX: &str = r"^([a-z1-9]+)(\[([1-9]*)])$";
+/// 匹配 solidity 的uint8-uint256类型
+const SOL_TY_UINT_REGEX: &str = r"^(uint)([0-9]*)$";
+/// 匹配 solidity 的int8-int256类型
+const SOL_TY_INT_REGEX: &str = r"^(int)([0-9]*)$";
+/// 匹配 solidity 的 tuple 数组类型，Example: tuple[], tuple[2]
+const SOL_TY_TUPLE_ARRAY_REGEX: &str = r"^tuple\[([0-9]*)]$";
+/// 匹配 solidity 的 array 类型的最外层维度，Example: string[], bool[], bytes32[], uint256[], uint16[2][]...
+const SOL_TY_ARRAY_REGEX: &str = r"^(.+)(\[([0-9]*)])$";
 
 fn is_bytes(ty: &str) -> bool {
     let regex = Regex::new(SOL_TY_BYTES_REGEX).unwrap();
@@ -274,6 +476,17 @@ fn parse_int(ty: &str) -> (String, usize) {
     (ty.as_str().to_string(), size)
 }
 
+fn is_tuple_array(ty: &str) -> bool {
+    let regex = Regex::new(SOL_TY_TUPLE_ARRAY_REGEX).unwrap();
+    regex.is_match(ty)
+}
+
+fn parse_tuple_array(ty: &str) -> usize {
+    let regex = Regex::new(SOL_TY_TUPLE_ARRAY_REGEX).unwrap();
+    let c = regex.captures(ty).unwrap();
+    c.get(1).unwrap().as_str().parse().unwrap_or(0)
+}
+
 fn is_array(ty: &str) -> bool {
     let regex = Regex::new(SOL_TY_ARRAY_REGEX).unwrap();
     regex.is_match(ty)
@@ -291,18 +504,22 @@ fn parse_array(ty: &str) -> (String, usize) {
 #[cfg(test)]
 mod tests {
     use std::any::Any;
+    use std::collections::HashMap;
 
     use alloy_dyn_abi::{DynSolType, DynSolValue, JsonAbiExt};
-    use alloy_json_abi::JsonAbi;
-    use alloy_primitives::{b256, U256};
+    use alloy_json_abi::{JsonAbi, Param};
+    use alloy_primitives::{b256, I256, U256};
     use alloy_primitives::hex;
     use regex::Regex;
 
+    use model::common::Address;
     use model::HexString;
 
-    use crate::encode::convert_arguments;
+    use crate::encode::{convert_argument, convert_arguments, Tokenizable, Utf8Bytes};
 
     const LEDGER_ABI: &str = r#"[{"inputs":[{"internalType":"uint64","name":"protocolSuite","type":"uint64"},{"internalType":"bytes32[]","name":"data","type":"bytes32[]"}],"name":"addProtocol","outputs":[{"internalType":"uint64","name":"protocolUri","type":"uint64"}],"stateMutability":"nonpayable","type":"function"},{"inputs":[{"internalType":"uint64","name":"protocolUri","type":"uint64"}],"name":"getAddress","outputs":[{"components":[{"internalType":"address","name":"updater","type":"address"},{"internalType":"bytes32[]","name":"data","type":"bytes32[]"}],"internalType":"struct credibilidity.Protocol[]","name":"protocol","type":"tuple[]"}],"stateMutability":"view","type":"function"},{"inputs":[{"internalType":"uint64","name":"protocolUri","type":"uint64"},{"internalType":"bytes32[]","name":"data","type":"bytes32[]"}],"name":"updateProtocol","outputs":[],"stateMutability":"nonpayable","type":"function"},{"inputs":[{"internalType":"string","name":"hash","type":"string"},{"internalType":"address","name":"address","type":"address"}],"name":"getTraceability","outputs":[{"components":[{"internalType":"uint64","name":"number","type":"uint64"},{"internalType":"uint64","name":"protocol","type":"uint64"},{"internalType":"address","name":"updater","type":"address"},{"internalType":"bytes32[]","name":"data","type":"bytes32[]"}],"internalType":"struct credibilidity.Evidence[]","name":"evi","type":"tuple[]"}],"stateMutability":"view","type":"function"},{"inputs":[{"internalType":"string","name":"hash","type":"string"},{"internalType":"address","name":"address","type":"address"}],"name":"setDataSecret","outputs":[],"stateMutability":"nonpayable","type":"function"},{"inputs":[{"internalType":"uint64","name":"protocolUri","type":"uint64"},{"internalType":"string","name":"hash","type":"string"},{"internalType":"bytes32[]","name":"data","type":"bytes32[]"},{"internalType":"address","name":"address","type":"address"}],"name":"writeTraceability","outputs":[],"stateMutability":"nonpayable","type":"function"},{"inputs":[{"components":[{"internalType":"uint64","name":"protocolUri","type":"uint64"},{"internalType":"string","name":"hash","type":"string"},{"internalType":"bytes32[]","name":"data","type":"bytes32[]"},{"internalType":"address","name":"address","type":"address"}],"internalType":"struct Business.batch[]","name":"bt","type":"tuple[]"}],"name":"writeTraceabilityBatch","outputs":[],"stateMutability":"nonpayable","type":"function"}]"#;
+    const USER_ABI: &str = r#"[{"inputs":[],"name":"getUser","outputs":[{"components":[{"internalType":"uint256","name":"id","type":"uint256"},{"internalType":"string","name":"name","type":"string"},{"internalType":"bool","name":"isMan","type":"bool"},{"internalType":"string[]","name":"tags","type":"string[]"},{"internalType":"uint32[]","name":"levels","type":"uint32[]"}],"internalType":"struct Test.User","name":"","type":"tuple"}],"stateMutability":"view","type":"function"},{"inputs":[{"components":[{"internalType":"uint256","name":"id","type":"uint256"},{"internalType":"string","name":"name","type":"string"},{"internalType":"bool","name":"isMan","type":"bool"},{"internalType":"string[]","name":"tags","type":"string[]"},{"internalType":"uint32[]","name":"levels","type":"uint32[]"}],"internalType":"struct Test.User","name":"newUser","type":"tuple"}],"name":"setUser","outputs":[],"stateMutability":"nonpayable","type":"function"},{"inputs":[{"internalType":"uint32[]","name":"newLevels","type":"uint32[]"}],"name":"updateLevels","outputs":[],"stateMutability":"nonpayable","type":"function"},{"inputs":[{"internalType":"string","name":"newName","type":"string"}],"name":"updateName","outputs":[],"stateMutability":"nonpayable","type":"function"},{"inputs":[{"internalType":"string[]","name":"newTags","type":"string[]"}],"name":"updateTags","outputs":[],"stateMutability":"nonpayable","type":"function"}]"#;
+    const SET_USER_DATA: &str = "66e334840000000000000000000000000000000000000000000000000000000000000020000000000000000000000000000000000000000000000000000000000000006400000000000000000000000000000000000000000000000000000000000000a0000000000000000000000000000000000000000000000000000000000000000100000000000000000000000000000000000000000000000000000000000000e000000000000000000000000000000000000000000000000000000000000001c000000000000000000000000000000000000000000000000000000000000000044a61636b0000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000020000000000000000000000000000000000000000000000000000000000000040000000000000000000000000000000000000000000000000000000000000008000000000000000000000000000000000000000000000000000000000000000036d616e00000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000004676f6f64000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000003000000000000000000000000000000000000000000000000000000000000000100000000000000000000000000000000000000000000000000000000000000020000000000000000000000000000000000000000000000000000000000000003";
 
     #[test]
     fn test_encode_ledger_add_protocol() {
@@ -355,6 +572,29 @@ mod tests {
         assert_eq!(encoded, my_data);
     }
 
+    #[test]
+    fn test_encode_multi_dimensional_array() {
+        let inputs: Vec<Param> = serde_json::from_str(r#"[{"internalType":"uint16[2][]","name":"","type":"uint16[2][]"}]"#).unwrap();
+        let args = convert_arguments(inputs.clone(), vec![Box::new(vec![vec!["2", "3"]])]).unwrap();
+        let expected = DynSolValue::Array(vec![DynSolValue::FixedArray(vec![2u16.into(), 3u16.into()])]);
+        assert_eq!(vec![expected], args);
+
+        let my_data = hex!(
+            "0000000000000000000000000000000000000000000000000000000000000020" // offset
+            "0000000000000000000000000000000000000000000000000000000000000001" // length
+            "0000000000000000000000000000000000000000000000000000000000000002" // .[0][0]
+            "0000000000000000000000000000000000000000000000000000000000000003" // .[0][1]
+        );
+        assert_eq!(my_data.to_vec(), args[0].abi_encode());
+
+        assert!(convert_arguments(inputs, vec![Box::new(vec![vec!["2"]])]).is_err());
+
+        let nested: Vec<Box<dyn Any>> = vec![Box::new(vec![vec![1u64], vec![2u64, 3u64]])];
+        let actual = convert_argument("uint64[][][1]", vec![], &(Box::new(nested) as Box<dyn Any>)).unwrap();
+        let row = |v: &[u64]| DynSolValue::Array(v.iter().map(|n| DynSolValue::Uint(U256::from(*n), 64)).collect());
+        assert_eq!(DynSolValue::FixedArray(vec![DynSolValue::Array(vec![row(&[1]), row(&[2, 3])])]), actual);
+    }
+
     #[test]
     fn test_encode_arguments() {
         let abi: JsonAbi = serde_json::from_str(LEDGER_ABI).unwrap();
@@ -365,6 +605,141 @@ mod tests {
         assert_eq!(excepted_data, hex::encode(data));
     }
 
+    #[test]
+    fn test_encode_negative_int() {
+        let encoded = convert_argument("int8", vec![], &(Box::new("-128") as Box<dyn Any>)).unwrap().abi_encode();
+        assert_eq!(format!("{:f<62}80", ""), hex::encode(encoded));
+
+        let encoded = convert_argument("int64", vec![], &(Box::new(String::from("-42")) as Box<dyn Any>)).unwrap().abi_encode();
+        assert_eq!(format!("{:f<62}d6", ""), hex::encode(encoded));
+
+        let min = "-57896044618658097711785492504343953926634992332820282019728792003956564819968";
+        let encoded = convert_argument("int256", vec![], &(Box::new(min) as Box<dyn Any>)).unwrap().abi_encode();
+        assert_eq!(format!("80{:0<62}", ""), hex::encode(encoded));
+
+        assert!(convert_argument("int8", vec![], &(Box::new("-129") as Box<dyn Any>)).is_err());
+        assert!(convert_argument("int8", vec![], &(Box::new("128") as Box<dyn Any>)).is_err());
+        assert!(convert_argument("int160", vec![], &(Box::new("-1") as Box<dyn Any>)).is_ok());
+        assert!(convert_argument("int64", vec![], &(Box::new("--1") as Box<dyn Any>)).is_err());
+    }
+
+    #[test]
+    fn test_encode_hex_number() {
+        let hex = convert_argument("uint256", vec![], &(Box::new("0x64") as Box<dyn Any>)).unwrap();
+        let dec = convert_argument("uint256", vec![], &(Box::new("100") as Box<dyn Any>)).unwrap();
+        assert_eq!(dec, hex);
+        assert_eq!(DynSolValue::Uint(U256::from(100), 256), hex);
+
+        let hex = convert_argument("int16", vec![], &(Box::new(String::from("-0x64")) as Box<dyn Any>)).unwrap();
+        assert_eq!(DynSolValue::Int(I256::try_from(-100).unwrap(), 16), hex);
+
+        assert!(convert_argument("uint8", vec![], &(Box::new("0xff") as Box<dyn Any>)).is_ok());
+        assert!(convert_argument("uint8", vec![], &(Box::new("0x100") as Box<dyn Any>)).is_err());
+        assert!(convert_argument("uint8", vec![], &(Box::new("256") as Box<dyn Any>)).is_err());
+        assert!(convert_argument("int8", vec![], &(Box::new("0x80") as Box<dyn Any>)).is_err());
+        assert!(convert_argument("uint160", vec![], &(Box::new("0xff") as Box<dyn Any>)).is_ok());
+        assert!(convert_argument("uint64", vec![], &(Box::new("0xzz") as Box<dyn Any>)).is_err());
+    }
+
+    #[test]
+    fn test_encode_native_integers() {
+        let params: Vec<Param> = serde_json::from_str(r#"[{"name":"a","type":"uint8"},{"name":"b","type":"uint64"},{"name":"c","type":"uint256"},{"name":"d","type":"int32"},{"name":"e","type":"int256"},{"name":"f","type":"uint128"}]"#).unwrap();
+        let args: Vec<Box<dyn Any>> = vec![
+            Box::new(7u8),
+            Box::new("100"),
+            Box::new(U256::from(5)),
+            Box::new(-3i64),
+            Box::new(I256::MINUS_ONE),
+            Box::new(String::from("9")),
+        ];
+        let converted = convert_arguments(params, args).unwrap();
+        assert_eq!(converted, vec![
+            DynSolValue::Uint(U256::from(7), 8),
+            DynSolValue::Uint(U256::from(100), 64),
+            DynSolValue::Uint(U256::from(5), 256),
+            DynSolValue::Int(I256::try_from(-3).unwrap(), 32),
+            DynSolValue::Int(I256::MINUS_ONE, 256),
+            DynSolValue::Uint(U256::from(9), 128),
+        ]);
+
+        assert!(convert_argument("uint8", vec![], &(Box::new(256u64) as Box<dyn Any>)).is_err());
+        assert!(convert_argument("uint64", vec![], &(Box::new(-1i64) as Box<dyn Any>)).is_err());
+        assert!(convert_argument("int8", vec![], &(Box::new(200u8) as Box<dyn Any>)).is_err());
+        assert!(convert_argument("uint32", vec![], &(Box::new(1.5f64) as Box<dyn Any>)).is_err());
+    }
+
+    #[test]
+    fn test_encode_bad_values() {
+        let bad = |ty: &str, arg: Box<dyn Any>| assert!(convert_argument(ty, vec![], &arg).is_err(), "{} should be rejected", ty);
+        bad("bool", Box::new("yes"));
+        bad("bool", Box::new(String::from("1")));
+        bad("address", Box::new("0x5f2be9a02b43f748ee460bf36eed24fafa1099"));
+        bad("address", Box::new("not an address"));
+        bad("address", Box::new("zltc_Z1pnS94bP4hQSYLs4aP4UwBP9pH8bEvhj"));
+        bad("address", Box::new("zltc_0OIl"));
+        bad("string", Box::new(1u64));
+        bad("uint256", Box::new("one"));
+        bad("int256", Box::new("1.5"));
+        bad("bytes", Box::new("0xzz"));
+        bad("bytes32", Box::new(String::from("0x1234")));
+        bad("bytes4", Box::new("0x123"));
+        bad("bytes33", Box::new("0x00"));
+        bad("uint64[]", Box::new(vec!["1", "x"]));
+        bad("tuple", Box::new(vec!["1"]));
+
+        let bytes4 = convert_argument("bytes4", vec![], &(Box::new("0x12345678") as Box<dyn Any>)).unwrap();
+        assert_eq!(DynSolValue::FixedBytes(b256!("1234567800000000000000000000000000000000000000000000000000000000"), 4), bytes4);
+        let addr = convert_argument("address", vec![], &(Box::new("zltc_Z1pnS94bP4hQSYLs4aP4UwBP9pH8bEvhi") as Box<dyn Any>)).unwrap();
+        assert_eq!("0x5f2be9a02b43f748ee460bf36eed24fafa109920", addr.as_address().unwrap().to_string().to_lowercase());
+    }
+
+    #[test]
+    fn test_encode_bytes_sources() {
+        let convert = |ty: &str, arg: Box<dyn Any>| convert_argument(ty, vec![], &arg);
+        let expected = DynSolValue::Bytes(b"hello".to_vec());
+        assert_eq!(expected, convert("bytes", Box::new("0x68656c6c6f")).unwrap());
+        assert_eq!(expected, convert("bytes", Box::new(String::from("68656c6c6f"))).unwrap());
+        assert_eq!(expected, convert("bytes", Box::new(b"hello".to_vec())).unwrap());
+        assert_eq!(expected, convert("bytes", Box::new(&b"hello"[..])).unwrap());
+        assert_eq!(expected, convert("bytes", Box::new(Utf8Bytes("hello".to_string()))).unwrap());
+
+        let bytes4 = DynSolValue::FixedBytes(b256!("1234567800000000000000000000000000000000000000000000000000000000"), 4);
+        assert_eq!(bytes4, convert("bytes4", Box::new(vec![0x12u8, 0x34, 0x56, 0x78])).unwrap());
+        assert!(convert("bytes4", Box::new(vec![0x12u8, 0x34])).is_err());
+        assert!(convert("bytes4", Box::new(Utf8Bytes("abcd".to_string()))).is_err());
+        assert!(convert("bytes", Box::new("hello")).is_err());
+        assert!(convert("bytes", Box::new("0x123")).is_err());
+        assert!(convert("bytes", Box::new(1u64)).is_err());
+
+        let bytes_array = convert("bytes[]", Box::new(vec![Box::new(b"a".to_vec()) as Box<dyn Any>, Box::new("0x62")])).unwrap();
+        assert_eq!(DynSolValue::Array(vec![DynSolValue::Bytes(b"a".to_vec()), DynSolValue::Bytes(b"b".to_vec())]), bytes_array);
+    }
+
+    #[test]
+    fn test_encode_owned_numeric_vec() {
+        let expected = convert_argument("uint64[]", vec![], &(Box::new(vec!["1", "2", "3"]) as Box<dyn Any>)).unwrap();
+        let actual = convert_argument("uint64[]", vec![], &(Box::new(vec![1u64, 2u64, 3u64]) as Box<dyn Any>)).unwrap();
+        assert_eq!(expected, actual);
+
+        let actual = convert_argument("uint256[3]", vec![], &(Box::new(vec![U256::from(1), U256::from(2), U256::from(3)]) as Box<dyn Any>)).unwrap();
+        assert_eq!(DynSolValue::FixedArray(expected.as_array().unwrap().iter().map(|v| DynSolValue::Uint(v.as_uint().unwrap().0, 256)).collect()), actual);
+
+        let actual = convert_argument("bool[]", vec![], &(Box::new(vec![true, false]) as Box<dyn Any>)).unwrap();
+        assert_eq!(DynSolValue::Array(vec![DynSolValue::Bool(true), DynSolValue::Bool(false)]), actual);
+
+        let actual = convert_argument("address[]", vec![], &(Box::new(vec![Address::new("zltc_Z1pnS94bP4hQSYLs4aP4UwBP9pH8bEvhi")]) as Box<dyn Any>)).unwrap();
+        let expected = convert_argument("address[]", vec![], &(Box::new(vec!["0x5f2be9a02b43f748ee460bf36eed24fafa109920"]) as Box<dyn Any>)).unwrap();
+        assert_eq!(expected, actual);
+        let actual = convert_argument("address[]", vec![], &(Box::new(vec![Address::new("0x5f2be9a02b43f748ee460bf36eed24fafa109920")]) as Box<dyn Any>)).unwrap();
+        assert_eq!(expected, actual);
+        let bad = vec![Address::new("zltc_Z1pnS94bP4hQSYLs4aP4UwBP9pH8bEvhi"), Address::new("zltc_Z1pnS94bP4hQSYLs4aP4UwBP9pH8bEvhj")];
+        assert!(convert_argument("address[]", vec![], &(Box::new(bad) as Box<dyn Any>)).is_err());
+        let bad = vec![vec![Address::new("zltc_notAnAddress")]];
+        assert!(convert_argument("address[][]", vec![], &(Box::new(bad) as Box<dyn Any>)).is_err());
+
+        assert!(convert_argument("uint64[]", vec![], &(Box::new(vec![1u32]) as Box<dyn Any>)).is_err());
+    }
+
     #[test]
     fn test_encode_tuple_arguments() {
         let abi: JsonAbi = serde_json::from_str(r#"[{"inputs":[],"name":"getUser","outputs":[{"components":[{"internalType":"uint256","name":"id","type":"uint256"},{"internalType":"string","name":"name","type":"string"},{"internalType":"bool","name":"isMan","type":"bool"},{"internalType":"string[]","name":"tags","type":"string[]"},{"internalType":"uint32[]","name":"levels","type":"uint32[]"}],"internalType":"struct Test.User","name":"","type":"tuple"}],"stateMutability":"view","type":"function"},{"inputs":[{"components":[{"internalType":"uint256","name":"id","type":"uint256"},{"internalType":"string","name":"name","type":"string"},{"internalType":"bool","name":"isMan","type":"bool"},{"internalType":"string[]","name":"tags","type":"string[]"},{"internalType":"uint32[]","name":"levels","type":"uint32[]"}],"internalType":"struct Test.User","name":"newUser","type":"tuple"}],"name":"setUser","outputs":[],"stateMutability":"nonpayable","type":"function"},{"inputs":[{"internalType":"uint32[]","name":"newLevels","type":"uint32[]"}],"name":"updateLevels","outputs":[],"stateMutability":"nonpayable","type":"function"},{"inputs":[{"internalType":"string","name":"newName","type":"string"}],"name":"updateName","outputs":[],"stateMutability":"nonpayable","type":"function"},{"inputs":[{"internalType":"string[]","name":"newTags","type":"string[]"}],"name":"updateTags","outputs":[],"stateMutability":"nonpayable","type":"function"}]"#).unwrap();
@@ -383,6 +758,77 @@ mod tests {
         assert_eq!(excepted_data, hex::encode(data));
     }
 
+    #[test]
+    fn test_encode_named_tuple_arguments() {
+        struct User {
+            id: u64,
+            name: String,
+            is_man: bool,
+            tags: Vec<&'static str>,
+            levels: Vec<u64>,
+        }
+
+        impl Tokenizable for User {
+            fn into_named_fields(self) -> HashMap<String, Box<dyn Any>> {
+                HashMap::from([
+                    ("levels".to_string(), Box::new(self.levels) as Box<dyn Any>),
+                    ("tags".to_string(), Box::new(self.tags)),
+                    ("isMan".to_string(), Box::new(self.is_man.to_string())),
+                    ("name".to_string(), Box::new(self.name)),
+                    ("id".to_string(), Box::new(self.id)),
+                ])
+            }
+        }
+
+        let abi: JsonAbi = serde_json::from_str(USER_ABI).unwrap();
+        let func = abi.functions.get("setUser").unwrap().first().unwrap();
+        let user = || User { id: 100, name: "Jack".to_string(), is_man: true, tags: vec!["man", "good"], levels: vec![1, 2, 3] };
+
+        let args = convert_arguments(func.inputs.clone(), vec![Box::new(user().into_named_fields())]).unwrap();
+        let data = func.abi_encode_input(args.as_slice()).unwrap();
+        assert_eq!(SET_USER_DATA, hex::encode(data));
+
+        let mut fields = user().into_named_fields();
+        fields.remove("isMan");
+        fields.insert("is_man".to_string(), Box::new("true"));
+        let err = convert_arguments(func.inputs.clone(), vec![Box::new(fields)]).unwrap_err();
+        assert!(err.to_string().contains("missing field isMan"), "{}", err);
+
+        let mut fields = user().into_named_fields();
+        fields.insert("age".to_string(), Box::new(18u64));
+        assert!(convert_arguments(func.inputs.clone(), vec![Box::new(fields)]).is_err());
+
+        let users: Vec<HashMap<String, Box<dyn Any>>> = vec![user().into_named_fields(), user().into_named_fields()];
+        let converted = convert_argument("tuple[2]", func.inputs[0].components.clone(), &(Box::new(users) as Box<dyn Any>)).unwrap();
+        assert_eq!(DynSolValue::FixedArray(vec![args[0].clone(), args[0].clone()]), converted);
+    }
+
+    #[test]
+    fn test_encode_tuple_array_round_trip() {
+        let abi: JsonAbi = serde_json::from_str(LEDGER_ABI).unwrap();
+        let f = abi.functions.get("writeTraceabilityBatch").unwrap().first().unwrap();
+        let data = "0x516482b2880721149f75c9aea3b6a6a700022c78561f6e22fbd0d4f73e5e7432";
+        let item = |protocol: &str, hash: &str| -> Vec<Box<dyn Any>> {
+            vec![Box::new(protocol.to_string()), Box::new(hash.to_string()), Box::new(vec![data]), Box::new("zltc_Z1pnS94bP4hQSYLs4aP4UwBP9pH8bEvhi")]
+        };
+
+        let nested: Vec<Vec<Box<dyn Any>>> = vec![item("1", "hash1"), item("2", "hash2")];
+        let args = convert_arguments(f.inputs.clone(), vec![Box::new(nested)]).unwrap();
+        let boxed: Vec<Box<dyn Any>> = vec![Box::new(item("1", "hash1")), Box::new(item("2", "hash2"))];
+        assert_eq!(args, convert_arguments(f.inputs.clone(), vec![Box::new(boxed)]).unwrap());
+
+        let encoded = f.abi_encode_input(args.as_slice()).unwrap();
+        let decoded = f.abi_decode_input(&encoded[4..], true).unwrap();
+        assert_eq!(args, decoded);
+        let items = decoded[0].as_array().unwrap();
+        assert_eq!(2, items.len());
+        assert_eq!(Some("hash2"), items[1].as_tuple().unwrap()[1].as_str());
+
+        let fixed: Vec<Vec<Box<dyn Any>>> = vec![item("1", "hash1")];
+        assert!(convert_argument("tuple[2]", f.inputs[0].components.clone(), &(Box::new(fixed) as Box<dyn Any>)).is_err());
+        assert!(convert_argument("tuple[]", f.inputs[0].components.clone(), &(Box::new(vec!["1"]) as Box<dyn Any>)).is_err());
+    }
+
     #[test]
     fn test_decode_tuple_arguments() {
         let abi: JsonAbi = serde_json::from_str(r#"[{"inputs":[],"name":"getUser","outputs":[{"components":[{"internalType":"uint256","name":"id","type":"uint256"},{"internalType":"string","name":"name","type":"string"},{"internalType":"bool","name":"isMan","type":"bool"},{"internalType":"string[]","name":"tags","type":"string[]"},{"internalType":"uint32[]","name":"levels","type":"uint32[]"}],"internalType":"struct Test.User","name":"","type":"tuple"}],"stateMutability":"view","type":"function"},{"inputs":[{"components":[{"internalType":"uint256","name":"id","type":"uint256"},{"internalType":"string","name":"name","type":"string"},{"internalType":"bool","name":"isMan","type":"bool"},{"internalType":"string[]","name":"tags","type":"string[]"},{"internalType":"uint32[]","name":"levels","type":"uint32[]"}],"internalType":"struct Test.User","name":"newUser","type":"tuple"}],"name":"setUser","outputs":[],"stateMutability":"nonpayable","type":"function"},{"inputs":[{"internalType":"uint32[]","name":"newLevels","type":"uint32[]"}],"name":"updateLevels","outputs":[],"stateMutability":"nonpayable","type":"function"},{"inputs":[{"internalType":"string","name":"newName","type":"string"}],"name":"updateName","outputs":[],"stateMutability":"nonpayable","type":"function"},{"inputs":[{"internalType":"string[]","name":"newTags","type":"string[]"}],"name":"updateTags","outputs":[],"stateMutability":"nonpayable","type":"function"}]"#).unwrap();
@@ -0,0 +1,145 @@
+use std::fmt;
+
+use alloy_json_abi::AbiItem;
+
+/// 错误片段在出错位置前后各截取的字节数
+const SNIPPET_RADIUS: usize = 16;
+
+/// 人类可读abi的解析错误，携带出错位置和出错位置附近的片段
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LexerError {
+    /// 出错位置在整个输入中的字节偏移量
+    pub offset: usize,
+    /// 出错位置所在行附近的片段
+    pub snippet: String,
+    pub message: String,
+}
+
+impl LexerError {
+    fn new(line: &str, line_start: usize, column: usize, message: String) -> Self {
+        LexerError {
+            offset: line_start + column,
+            snippet: snippet(line, column),
+            message,
+        }
+    }
+}
+
+impl fmt::Display for LexerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at offset {}: `{}`", self.message, self.offset, self.snippet)
+    }
+}
+
+impl std::error::Error for LexerError {}
+
+impl From<LexerError> for model::Error {
+    fn from(e: LexerError) -> Self {
+        model::Error::new(&e.to_string())
+    }
+}
+
+/// # 解析人类可读的abi，每行一个条目，空行会被忽略
+///
+/// 示例：`function transfer(address to, uint256 amount) returns (bool)`
+///
+/// ## 入参
+/// + `input: &str`: 人类可读的abi
+///
+/// ## 出参
+/// + `Result<Vec<AbiItem>, LexerError>`: 括号不匹配、包含非法字符或者条目不合法时返回`Err`，错误中携带出错位置的字节偏移量和片段
+pub fn parse(input: &str) -> Result<Vec<AbiItem<'static>>, LexerError> {
+    let mut items = Vec::new();
+    let mut line_start = 0;
+    for line in input.split('\n') {
+        let trimmed = line.trim_end_matches('\r');
+        if !trimmed.trim().is_empty() {
+            lex(trimmed).map_err(|(column, message)| LexerError::new(trimmed, line_start, column, message))?;
+            let column = trimmed.len() - trimmed.trim_start().len();
+            let item = AbiItem::parse(trimmed.trim())
+                .map_err(|e| LexerError::new(trimmed, line_start, column, e.to_string()))?;
+            items.push(item);
+        }
+        line_start += line.len() + 1;
+    }
+    Ok(items)
+}
+
+/// # 校验一行中的字符和括号，返回出错位置在行内的字节偏移量和错误信息
+fn lex(line: &str) -> Result<(), (usize, String)> {
+    let mut brackets = Vec::new();
+    for (i, c) in line.char_indices() {
+        match c {
+            '(' | '[' => brackets.push((i, c)),
+            ')' | ']' => {
+                let open = if c == ')' { '(' } else { '[' };
+                match brackets.pop() {
+                    Some((_, o)) if o == open => {}
+                    Some((j, o)) => return Err((i, format!("mismatched `{}`, `{}` opened at column {} is not closed", c, o, j))),
+                    None => return Err((i, format!("unmatched `{}`", c))),
+                }
+            }
+            c if c.is_ascii_alphanumeric() || c.is_whitespace() || matches!(c, '_' | '$' | ',') => {}
+            c => return Err((i, format!("unexpected character `{}`", c))),
+        }
+    }
+    match brackets.pop() {
+        Some((i, c)) => Err((i, format!("unclosed `{}`", c))),
+        None => Ok(()),
+    }
+}
+
+/// # 截取`column`前后`SNIPPET_RADIUS`个字节的片段，保证不会截断多字节字符
+fn snippet(line: &str, column: usize) -> String {
+    let mut start = column.saturating_sub(SNIPPET_RADIUS);
+    while !line.is_char_boundary(start) {
+        start -= 1;
+    }
+    let mut end = (column + SNIPPET_RADIUS).min(line.len());
+    while !line.is_char_boundary(end) {
+        end += 1;
+    }
+    line[start..end].trim().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy_json_abi::AbiItem;
+
+    use crate::human_readable::parse;
+
+    #[test]
+    fn test_parse_valid_signature() {
+        let items = parse("function transfer(address to, uint256 amount) returns (bool)\n\nevent Transfer(address indexed from, address indexed to, uint256 value)").unwrap();
+        assert_eq!(2, items.len());
+        match &items[0] {
+            AbiItem::Function(f) => assert_eq!("transfer(address,uint256)", f.signature()),
+            item => panic!("unexpected item {}", item.debug_name()),
+        }
+        assert!(matches!(&items[1], AbiItem::Event(e) if e.name == "Transfer"));
+    }
+
+    #[test]
+    fn test_parse_mismatched_parenthesis() {
+        let input = "function get() view returns (uint256)\nfunction set(uint256 x returns (bool)";
+        let err = parse(input).unwrap_err();
+        assert_eq!(input.find("(uint256 x").unwrap(), err.offset);
+        assert!(err.message.contains("unclosed"));
+        assert!(err.snippet.contains("set(uint256"));
+
+        let err = parse("function set(uint256 x))").unwrap_err();
+        assert_eq!(23, err.offset);
+        assert!(err.message.contains("unmatched"));
+
+        let err = parse("function set(uint256[2) x)").unwrap_err();
+        assert_eq!(22, err.offset);
+        assert!(err.message.contains("mismatched"));
+    }
+
+    #[test]
+    fn test_parse_invalid_item() {
+        let err = parse("  func set(uint256 x)").unwrap_err();
+        assert_eq!(2, err.offset);
+        assert!(model::Error::from(err).to_string().contains("offset 2"));
+    }
+}
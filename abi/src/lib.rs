@@ -1,7 +1,9 @@
 extern crate core;
 
-pub use abi::Abi;
+pub use abi::{Abi, OwnedAbi};
 
 pub mod encode;
+pub mod decode;
 pub mod abi;
+pub mod human_readable;
 
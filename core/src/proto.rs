@@ -1,9 +1,11 @@
 use std::fs;
 
 use protobuf::descriptor::FileDescriptorProto;
-use protobuf::reflect::FileDescriptor;
+use protobuf::reflect::{FileDescriptor, MessageDescriptor};
 use protobuf_json_mapping::{parse_dyn_from_str, print_to_string};
 
+use model::Error;
+
 /// Dynamic message: See https://github.com/stepancheg/rust-protobuf/blob/master/protobuf-examples/dynamic/src/main.rs
 
 /// # Get FileDescriptor from proto content
@@ -41,6 +43,36 @@ pub fn make_file_descriptor(proto: &str) -> FileDescriptor {
     file_descriptor
 }
 
+/// # Find a message in the FileDescriptor
+///
+/// ## Parameters
+/// + `fd: &FileDescriptor`:
+/// + `message_name: &str`: package relative name, nested messages are separated by `.`, e.g. `Student.Address`
+///
+/// ## Returns
+/// + `Result<MessageDescriptor, Error>`: the error lists the available message names when the message does not exist
+pub fn find_message(fd: &FileDescriptor, message_name: &str) -> Result<MessageDescriptor, Error> {
+    fd.message_by_package_relative_name(message_name).ok_or_else(|| {
+        let mut names = Vec::new();
+        for message in fd.messages() {
+            collect_message_names(&message, &mut names);
+        }
+        Error::new(&format!(
+            "message {} not found in proto, available messages: [{}]",
+            message_name,
+            names.join(", ")
+        ))
+    })
+}
+
+/// # Collect the package relative names of the message and its nested messages
+fn collect_message_names(message: &MessageDescriptor, names: &mut Vec<String>) {
+    names.push(message.name_to_package().to_string());
+    for nested in message.nested_messages() {
+        collect_message_names(&nested, names);
+    }
+}
+
 /// # Serialize Dynamic Message
 ///
 /// ## Parameters
@@ -49,14 +81,24 @@ pub fn make_file_descriptor(proto: &str) -> FileDescriptor {
 /// + `json: &str`:
 ///
 /// ## Returns
-/// + `Vec<u8>`: serialized message bytes
-pub fn serialize_message(fd: FileDescriptor, message_name: &str, json: &str) -> Vec<u8> {
-    let message_descriptor = fd.message_by_package_relative_name(message_name).unwrap();
-
-    let parse_result = parse_dyn_from_str(&message_descriptor, json).unwrap();
-    let serialize_bytes = parse_result.write_to_bytes_dyn().unwrap();
-
-    serialize_bytes
+/// + `Result<Vec<u8>, Error>`: serialized message bytes, the error lists the available messages or fields when the
+///   message does not exist or the json does not match the message
+pub fn serialize_message(fd: FileDescriptor, message_name: &str, json: &str) -> Result<Vec<u8>, Error> {
+    let message_descriptor = find_message(&fd, message_name)?;
+
+    let parse_result = parse_dyn_from_str(&message_descriptor, json).map_err(|e| {
+        let fields: Vec<String> = message_descriptor.fields().map(|f| f.json_name().to_string()).collect();
+        Error::new(&format!(
+            "invalid json of message {}: {}, available fields: [{}]",
+            message_name,
+            e,
+            fields.join(", ")
+        ))
+        .with_source(e)
+    })?;
+    parse_result
+        .write_to_bytes_dyn()
+        .map_err(|e| Error::new(&format!("serialize message {} failed: {}", message_name, e)).with_source(e))
 }
 
 /// # Deserialize Dynamic Message
@@ -67,16 +109,19 @@ pub fn serialize_message(fd: FileDescriptor, message_name: &str, json: &str) ->
 /// + `bytes: Vec<u8>`:
 ///
 /// ## Returns
-/// + `String`: Json string
-pub fn deserialize_message(fd: FileDescriptor, message_name: &str, bytes: Vec<u8>) -> String {
-    let message_descriptor = fd.message_by_package_relative_name(message_name).unwrap();
+/// + `Result<String, Error>`: Json string
+pub fn deserialize_message(fd: FileDescriptor, message_name: &str, bytes: Vec<u8>) -> Result<String, Error> {
+    let message_descriptor = find_message(&fd, message_name)?;
 
     let mut message = message_descriptor.new_instance();
-    message.merge_from_bytes_dyn(bytes.as_slice()).unwrap();
+    message
+        .merge_from_bytes_dyn(bytes.as_slice())
+        .map_err(|e| Error::new(&format!("deserialize message {} failed: {}", message_name, e)).with_source(e))?;
 
     // protobuf::text_format::print_to_string(message.as_ref());
     // format!("{}", message.to_string())
-    print_to_string(message.as_ref()).unwrap()
+    print_to_string(message.as_ref())
+        .map_err(|e| Error::new(&format!("print message {} failed: {}", message_name, e)).with_source(e))
 }
 
 #[cfg(test)]
@@ -134,7 +179,8 @@ mod test {
             file_descriptor,
             "Student",
             r#"{"name": "Jack", "age": 18, "address": {"province": "AnHui", "city": "LuAn"}}"#,
-        );
+        )
+        .unwrap();
 
         assert_eq!(
             vec![
@@ -155,11 +201,23 @@ mod test {
                 10, 4, 74, 97, 99, 107, 16, 18, 26, 13, 10, 5, 65, 110, 72, 117, 105, 18, 4, 76,
                 117, 65, 110,
             ],
-        );
+        )
+        .unwrap();
 
         assert_eq!(
             r#"{"name": "Jack", "age": 18, "address": {"province": "AnHui", "city": "LuAn"}}"#,
             json
         )
     }
+
+    #[test]
+    fn test_serialize_unknown_message() {
+        let file_descriptor = make_file_descriptor(PROTO);
+        let err = serialize_message(file_descriptor.clone(), "Studnet", r#"{"name": "Jack"}"#).unwrap_err();
+        assert!(err.to_string().contains("message Studnet not found"), "{}", err);
+        assert!(err.to_string().contains("[Student, Address]"), "{}", err);
+
+        let err = serialize_message(file_descriptor, "Student", r#"{"nmae": "Jack"}"#).unwrap_err();
+        assert!(err.to_string().contains("available fields: [name, age, address]"), "{}", err);
+    }
 }
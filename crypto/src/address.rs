@@ -1,8 +1,8 @@
-use sha256::digest;
-
 use model::constants::PUBLIC_KEY_LENGTH;
-use model::Curve;
+use model::common::AddressConfig;
+use model::{Curve, Error};
 
+use crate::base58check::{self, ChecksumHash, DEFAULT_CHECKSUM_LENGTH};
 use crate::hash::hash_message;
 
 /// # 公钥转ZLTC地址
@@ -10,9 +10,23 @@ use crate::hash::hash_message;
 /// + `public_key: &[u8]`: 公钥，`0xaaa53093e7fc18c3335876afc3aa604cf624cf7091685f42e09ee69cab3a6bcee8e0297eda17b6d8d3bfda8cc44945304ffb8bc40b5b7ff47e132c0c3fa0bd7f`
 /// + `curve: Curve`: 椭圆曲线
 ///
+/// 地址为公钥哈希的后20字节。Ed25519的公钥本身就是32字节的压缩点，没有前缀也不做截断，哈希算法为sha256
+///
 /// ## 出参
 /// + `String`: Lattice地址，示例：zltc_Z1pnS94bP4hQSYLs4aP4UwBP9pH8bEvhi
 pub fn public_key_to_address(public_key: &[u8], curve: Curve) -> String {
+    public_key_to_address_with_checksum(public_key, curve, ChecksumHash::Sha256)
+}
+
+/// # 公钥转ZLTC地址，使用指定的哈希算法计算地址的校验和
+/// ## 入参
+/// + `public_key: &[u8]`: 公钥
+/// + `curve: Curve`: 椭圆曲线
+/// + `checksum: ChecksumHash`: 校验和的哈希算法，使用SM3作为地址校验和的国密网络可以传入`ChecksumHash::from_curve(curve)`
+///
+/// ## 出参
+/// + `String`: Lattice地址
+pub fn public_key_to_address_with_checksum(public_key: &[u8], curve: Curve, checksum: ChecksumHash) -> String {
     let truncated_pk = if public_key.len() > PUBLIC_KEY_LENGTH {
         &public_key[(public_key.len() - PUBLIC_KEY_LENGTH)..]
     } else {
@@ -20,7 +34,7 @@ pub fn public_key_to_address(public_key: &[u8], curve: Curve) -> String {
     };
     let key_hash = hash_message(truncated_pk, curve);
     let eth = &hex::decode(key_hash).unwrap()[12..];
-    eth_to_lattice(eth)
+    eth_to_lattice_with_checksum(eth, &AddressConfig::default(), checksum)
 }
 
 /// # 以太坊地址转为ZLTC地址
@@ -30,13 +44,31 @@ pub fn public_key_to_address(public_key: &[u8], curve: Curve) -> String {
 /// ## 出参
 /// + `String`: ZLTC地址，示例：`zltc_Z1pnS94bP4hQSYLs4aP4UwBP9pH8bEvhi`
 pub fn eth_to_lattice(addr: &[u8]) -> String {
-    let prefix = hex::decode("01").unwrap();
-    let hash = [&prefix, addr].concat();
-    let d1 = hex::decode(digest(&hash)).unwrap();
-    let d2 = hex::decode(digest(&d1)).unwrap();
-    let d3 = [&prefix, addr, &d2[0..4]].concat();
-    let encoded = bs58::encode(d3).into_string();
-    format!("zltc_{}", encoded)
+    eth_to_lattice_with(addr, &AddressConfig::default())
+}
+
+/// # 使用指定的地址配置将以太坊地址转为Lattice地址
+/// ## 入参
+/// + `addr: &[u8]`: 以太坊地址
+/// + `config: &AddressConfig`: 地址的前缀和版本字节
+///
+/// ## 出参
+/// + `String`: Lattice地址
+pub fn eth_to_lattice_with(addr: &[u8], config: &AddressConfig) -> String {
+    eth_to_lattice_with_checksum(addr, config, ChecksumHash::Sha256)
+}
+
+/// # 使用指定的地址配置和校验和的哈希算法将以太坊地址转为Lattice地址
+/// ## 入参
+/// + `addr: &[u8]`: 以太坊地址
+/// + `config: &AddressConfig`: 地址的前缀和版本字节
+/// + `checksum: ChecksumHash`: 校验和的哈希算法
+///
+/// ## 出参
+/// + `String`: Lattice地址
+pub fn eth_to_lattice_with_checksum(addr: &[u8], config: &AddressConfig, checksum: ChecksumHash) -> String {
+    let encoded = base58check::encode_with(&[config.version], addr, DEFAULT_CHECKSUM_LENGTH, checksum);
+    format!("{}{}", config.title, encoded)
 }
 
 /// # Lattice地址转为以太坊地址
@@ -44,14 +76,69 @@ pub fn eth_to_lattice(addr: &[u8]) -> String {
 /// + `addr: &str`: ZLTC地址，示例：`zltc_Z1pnS94bP4hQSYLs4aP4UwBP9pH8bEvhi`
 ///
 /// ## 出参
-/// + `String`: 示例：`0x5f2be9a02b43f748ee460bf36eed24fafa109920`
+/// + `String`: 示例：`0x5f2be9a02b43f748ee460bf36eed24fafa109920`，地址不合法时panic，不希望panic时使用`lattice_to_eth_with`
 pub fn lattice_to_eth(addr: &str) -> String {
-    let addr = &addr[5..]; // remove prefix
-    let decoded = bs58::decode(addr).into_vec().unwrap();
-    let len = decoded.len() - 4;
+    lattice_to_eth_with(addr, &AddressConfig::default()).unwrap()
+}
+
+/// # 使用指定的地址配置将Lattice地址转为以太坊地址
+/// ## 入参
+/// + `addr: &str`: Lattice地址
+/// + `config: &AddressConfig`: 地址的前缀和版本字节
+///
+/// ## 出参
+/// + `Result<String, Error>`: 不带`0x`前缀的以太坊地址，前缀不一致、base58不合法或校验和不一致时返回`Err`
+pub fn lattice_to_eth_with(addr: &str, config: &AddressConfig) -> Result<String, Error> {
+    lattice_to_eth_with_checksum(addr, config, ChecksumHash::Sha256)
+}
 
-    let data = &decoded[1..len];
-    hex::encode(data)
+/// # 使用指定的地址配置和校验和的哈希算法将Lattice地址转为以太坊地址
+/// ## 入参
+/// + `addr: &str`: Lattice地址
+/// + `config: &AddressConfig`: 地址的前缀和版本字节
+/// + `checksum: ChecksumHash`: 校验和的哈希算法
+///
+/// ## 出参
+/// + `Result<String, Error>`: 不带`0x`前缀的以太坊地址，前缀不一致、base58不合法或校验和不一致时返回`Err`
+pub fn lattice_to_eth_with_checksum(addr: &str, config: &AddressConfig, checksum: ChecksumHash) -> Result<String, Error> {
+    let encoded = addr
+        .strip_prefix(config.title.as_str())
+        .ok_or_else(|| Error::new(&format!("address {} does not start with {}", addr, config.title)))?;
+    let (_, data) = base58check::decode_with(encoded, 1, DEFAULT_CHECKSUM_LENGTH, checksum)?;
+    Ok(hex::encode(data))
+}
+
+/// # 预测部署合约后的合约地址
+///
+/// 合约地址为`hash(rlp([owner, height]))`的后20字节，其中`owner`为部署者的以太坊格式地址，`height`为部署交易的高度，哈希算法与曲线对应
+///
+/// ## 入参
+/// + `owner: &str`: 部署者的ZLTC地址
+/// + `height: u64`: 部署交易的高度
+/// + `curve: Curve`: 椭圆曲线
+///
+/// ## 出参
+/// + `Result<String, Error>`: ZLTC地址，部署者的地址不合法时返回`Err`
+pub fn predict_contract_address(owner: &str, height: u64, curve: Curve) -> Result<String, Error> {
+    predict_contract_address_with(owner, height, curve, &AddressConfig::default())
+}
+
+/// # 使用指定的地址配置预测部署合约后的合约地址，部署者的地址和合约地址都使用该配置
+/// ## 入参
+/// + `owner: &str`: 部署者的地址
+/// + `height: u64`: 部署交易的高度
+/// + `curve: Curve`: 椭圆曲线
+/// + `config: &AddressConfig`: 地址的前缀和版本字节
+///
+/// ## 出参
+/// + `Result<String, Error>`: 合约地址，部署者的地址不合法时返回`Err`
+pub fn predict_contract_address_with(owner: &str, height: u64, curve: Curve, config: &AddressConfig) -> Result<String, Error> {
+    let owner = hex::decode(lattice_to_eth_with(owner, config)?).map_err(|e| Error::new("invalid owner address").with_source(e))?;
+    let mut rlp = rlp::RlpStream::new_list(2);
+    rlp.append(&owner);
+    rlp.append(&height);
+    let hash = hex::decode(hash_message(&rlp.out(), curve)).unwrap();
+    Ok(eth_to_lattice_with(&hash[12..], config))
 }
 
 #[cfg(test)]
@@ -84,6 +171,38 @@ mod tests {
         assert_eq!(String::from("zltc_Z1pnS94bP4hQSYLs4aP4UwBP9pH8bEvhi"), lattice_addr)
     }
 
+    #[test]
+    fn test_eth_to_lattice_with_custom_config() {
+        let config = AddressConfig { title: String::from("fork_"), version: 0x02 };
+        let addr = hex::decode(String::from("5f2be9a02b43f748ee460bf36eed24fafa109920")).unwrap();
+        let lattice_addr = eth_to_lattice_with(&addr, &config);
+        assert!(lattice_addr.starts_with("fork_"));
+        assert_ne!(&lattice_addr[5..], &eth_to_lattice(&addr)[5..]);
+        assert_eq!(hex::encode(&addr), lattice_to_eth_with(&lattice_addr, &config).unwrap());
+    }
+
+    #[test]
+    fn test_lattice_to_eth_invalid_address() {
+        let config = AddressConfig::default();
+        assert!(lattice_to_eth_with("zltc_Z1pnS94bP4hQSYLs4aP4UwBP9pH8bEvhj", &config).is_err());
+        assert!(lattice_to_eth_with("fork_Z1pnS94bP4hQSYLs4aP4UwBP9pH8bEvhi", &config).is_err());
+        assert!(lattice_to_eth_with("zltc", &config).is_err());
+    }
+
+    #[test]
+    fn test_address_checksum_hash() {
+        let pk = HexString::new("0xaaa53093e7fc18c3335876afc3aa604cf624cf7091685f42e09ee69cab3a6bcee8e0297eda17b6d8d3bfda8cc44945304ffb8bc40b5b7ff47e132c0c3fa0bd7f").decode();
+        let sha256_addr = public_key_to_address_with_checksum(&pk, Curve::Sm2p256v1, ChecksumHash::Sha256);
+        assert_eq!("zltc_Z1pnS94bP4hQSYLs4aP4UwBP9pH8bEvhi", sha256_addr);
+
+        let sm3_addr = public_key_to_address_with_checksum(&pk, Curve::Sm2p256v1, ChecksumHash::from_curve(Curve::Sm2p256v1));
+        assert_eq!("zltc_Z1pnS94bP4hQSYLs4aP4UwBP9pHDGNXsn", sm3_addr);
+        let config = AddressConfig::default();
+        assert_eq!("5f2be9a02b43f748ee460bf36eed24fafa109920", lattice_to_eth_with_checksum(&sm3_addr, &config, ChecksumHash::Sm3).unwrap());
+        assert!(base58check::decode(&sm3_addr[config.title.len()..], 1, DEFAULT_CHECKSUM_LENGTH).is_err());
+        assert_eq!(ChecksumHash::Sha256, ChecksumHash::from_curve(Curve::Secp256k1));
+    }
+
     #[test]
     fn recovery_address_from_private_key_sm2p256v1() {
         let sk_hex = HexString::new("0x9860956de90cc61a05447ea067197be1fa08d712c4a5088c9cb62182bdca0f92").decode();
@@ -107,4 +226,23 @@ mod tests {
         let expect_address = String::from("zltc_cWAvRSgCKgfyp5Rz5TH8srmrZsH5fVYpg");
         assert_eq!(expect_address, address)
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_predict_contract_address() {
+        let owner = "zltc_Z1pnS94bP4hQSYLs4aP4UwBP9pH8bEvhi";
+        assert_eq!(predict_contract_address(owner, 1, Curve::Sm2p256v1).unwrap(), "zltc_kDpRzPk6VtnCW59XMm2BatRzv9EVM2QNN");
+        assert_eq!(predict_contract_address(owner, 1, Curve::Secp256k1).unwrap(), "zltc_Rfd4SBBxa7ZkXxzJDxSc2Nd3w59fdYXeP");
+        assert_eq!(predict_contract_address(owner, 2, Curve::Secp256k1).unwrap(), "zltc_fxSFkjX4F8BiowoeJ9eqGF6SjB8mcTaYe");
+        assert!(predict_contract_address("zltc_Z1pnS94bP4hQSYLs4aP4UwBP9pH8bEvhj", 1, Curve::Secp256k1).is_err());
+        assert!(predict_contract_address("0x5f2be9a02b43f748ee460bf36eed24fafa109920", 1, Curve::Secp256k1).is_err());
+
+        let config = AddressConfig { title: String::from("fork_"), version: 0x02 };
+        let fork_owner = eth_to_lattice_with(&hex::decode("5f2be9a02b43f748ee460bf36eed24fafa109920").unwrap(), &config);
+        let fork_address = predict_contract_address_with(&fork_owner, 1, Curve::Secp256k1, &config).unwrap();
+        assert!(fork_address.starts_with("fork_"));
+        assert_eq!(
+            lattice_to_eth_with(&fork_address, &config).unwrap(),
+            lattice_to_eth(&predict_contract_address(owner, 1, Curve::Secp256k1).unwrap())
+        );
+    }
+}
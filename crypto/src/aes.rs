@@ -1,8 +1,11 @@
 use aes::{Aes128, Aes128Ctr, BlockDecrypt, BlockEncrypt, NewBlockCipher};
 use aes::cipher::{NewCipher, StreamCipher, StreamCipherSeek};
 use aes::cipher::generic_array::GenericArray;
+use aes_gcm::aead::{Aead, NewAead};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
 
-use model::HexString;
+use model::common::random_bytes;
+use model::{Error, HexString};
 
 /// # Aes encrypt, 16 byte = 128 bit
 /// ## Parameters
@@ -119,6 +122,50 @@ pub fn decrypt_with_mode(mode: AesMode, cipher_text: &str, key: &[u8], iv: Optio
     }
 }
 
+/// `seal`输出的格式版本
+const SEAL_VERSION: u8 = 0x01;
+/// AES-GCM的初始化向量字节长度
+const GCM_IV_LENGTH: usize = 12;
+/// AES-GCM的认证标签字节长度
+const GCM_TAG_LENGTH: usize = 16;
+
+/// # 使用AES-256-GCM加密，随机生成初始化向量，输出包含解密所需的全部信息
+///
+/// ## 入参
+/// + `plaintext: &[u8]`: 明文
+/// + `key: &[u8; 32]`: 密钥
+///
+/// ## 出参
+/// + `Vec<u8>`: `version(1字节) ‖ iv(12字节) ‖ ciphertext ‖ tag(16字节)`
+pub fn seal(plaintext: &[u8], key: &[u8; 32]) -> Vec<u8> {
+    let iv = random_bytes(GCM_IV_LENGTH);
+    let cipher = Aes256Gcm::new(Key::from_slice(key));
+    let ciphertext = cipher.encrypt(Nonce::from_slice(&iv), plaintext).expect("aes-gcm encrypt failed");
+    [&[SEAL_VERSION], iv.as_slice(), ciphertext.as_slice()].concat()
+}
+
+/// # 解密`seal`的输出并校验认证标签
+///
+/// ## 入参
+/// + `blob: &[u8]`: `seal`的输出
+/// + `key: &[u8; 32]`: 密钥
+///
+/// ## 出参
+/// + `Result<Vec<u8>, Error>`: 明文，版本不支持、长度不足、密钥错误或数据被篡改时返回`Err`
+pub fn open(blob: &[u8], key: &[u8; 32]) -> Result<Vec<u8>, Error> {
+    if blob.len() < 1 + GCM_IV_LENGTH + GCM_TAG_LENGTH {
+        return Err(Error::new(&format!("sealed data of {} bytes is too short", blob.len())));
+    }
+    if blob[0] != SEAL_VERSION {
+        return Err(Error::new(&format!("unsupported sealed data version {}", blob[0])));
+    }
+    let (iv, ciphertext) = blob[1..].split_at(GCM_IV_LENGTH);
+    let cipher = Aes256Gcm::new(Key::from_slice(key));
+    cipher
+        .decrypt(Nonce::from_slice(iv), ciphertext)
+        .map_err(|_| Error::new("sealed data authentication failed"))
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -139,4 +186,26 @@ mod test {
         let expected = hex::encode(b"hello world");
         assert_eq!(expected, plain_text);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn seal_and_open() {
+        let key = [7u8; 32];
+        let blob = seal(b"hello world", &key);
+        assert_eq!(blob.len(), 1 + GCM_IV_LENGTH + 11 + GCM_TAG_LENGTH);
+        assert_eq!(b"hello world".to_vec(), open(&blob, &key).unwrap());
+        assert_ne!(blob, seal(b"hello world", &key));
+        assert!(open(&blob, &[8u8; 32]).is_err());
+    }
+
+    #[test]
+    fn open_tampered() {
+        let key = [7u8; 32];
+        let blob = seal(b"hello world", &key);
+        for i in 0..blob.len() {
+            let mut tampered = blob.clone();
+            tampered[i] ^= 0x01;
+            assert!(open(&tampered, &key).is_err());
+        }
+        assert!(open(&blob[..blob.len() - 1], &key).is_err());
+    }
+}
@@ -0,0 +1,138 @@
+use model::{Curve, Error};
+
+use crate::hash::{sha256, sm3};
+
+/// 默认的校验和字节长度
+pub const DEFAULT_CHECKSUM_LENGTH: usize = 4;
+
+/// 计算校验和使用的哈希算法
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ChecksumHash {
+    /// 两次SHA-256，默认的算法
+    #[default]
+    Sha256,
+    /// 两次SM3，部分国密网络的地址使用
+    Sm3,
+}
+
+impl ChecksumHash {
+    /// # 与曲线对应的哈希算法，Sm2p256v1为SM3，其它曲线为SHA-256
+    ///
+    /// ## 入参
+    /// + `curve: Curve`: 椭圆曲线
+    ///
+    /// ## 出参
+    /// + `ChecksumHash`
+    pub fn from_curve(curve: Curve) -> Self {
+        if curve.is_gm() { ChecksumHash::Sm3 } else { ChecksumHash::Sha256 }
+    }
+
+    fn digest(&self, data: &[u8]) -> [u8; 32] {
+        match self {
+            ChecksumHash::Sha256 => sha256(data),
+            ChecksumHash::Sm3 => sm3(data),
+        }
+    }
+}
+
+/// # 计算校验和，为两次哈希的前`checksum_len`个字节
+fn checksum(data: &[u8], checksum_len: usize, hash: ChecksumHash) -> Vec<u8> {
+    hash.digest(&hash.digest(data))[..checksum_len].to_vec()
+}
+
+/// # base58check编码：`base58(version ‖ payload ‖ checksum)`
+///
+/// ## 入参
+/// + `version: &[u8]`: 版本字节，例如地址为`[0x01]`
+/// + `payload: &[u8]`: 数据
+/// + `checksum_len: usize`: 校验和的字节长度，不超过32，通常为`DEFAULT_CHECKSUM_LENGTH`
+///
+/// ## 出参
+/// + `String`: base58字符串
+pub fn encode(version: &[u8], payload: &[u8], checksum_len: usize) -> String {
+    encode_with(version, payload, checksum_len, ChecksumHash::Sha256)
+}
+
+/// # 使用指定的哈希算法计算校验和的base58check编码
+///
+/// ## 入参
+/// + `version: &[u8]`: 版本字节
+/// + `payload: &[u8]`: 数据
+/// + `checksum_len: usize`: 校验和的字节长度，不超过32
+/// + `hash: ChecksumHash`: 计算校验和的哈希算法
+///
+/// ## 出参
+/// + `String`: base58字符串
+pub fn encode_with(version: &[u8], payload: &[u8], checksum_len: usize, hash: ChecksumHash) -> String {
+    let data = [version, payload].concat();
+    let checksum = checksum(&data, checksum_len, hash);
+    bs58::encode([data, checksum].concat()).into_string()
+}
+
+/// # base58check解码，是`encode`的逆操作，会校验校验和
+///
+/// ## 入参
+/// + `encoded: &str`: base58字符串
+/// + `version_len: usize`: 版本字节的长度
+/// + `checksum_len: usize`: 校验和的字节长度
+///
+/// ## 出参
+/// + `Result<(Vec<u8>, Vec<u8>), Error>`: 版本字节和数据，base58不合法、长度不足或校验和不一致时返回错误
+pub fn decode(encoded: &str, version_len: usize, checksum_len: usize) -> Result<(Vec<u8>, Vec<u8>), Error> {
+    decode_with(encoded, version_len, checksum_len, ChecksumHash::Sha256)
+}
+
+/// # 使用指定的哈希算法校验校验和的base58check解码，是`encode_with`的逆操作
+///
+/// ## 入参
+/// + `encoded: &str`: base58字符串
+/// + `version_len: usize`: 版本字节的长度
+/// + `checksum_len: usize`: 校验和的字节长度
+/// + `hash: ChecksumHash`: 计算校验和的哈希算法
+///
+/// ## 出参
+/// + `Result<(Vec<u8>, Vec<u8>), Error>`: 版本字节和数据，base58不合法、长度不足或校验和不一致时返回错误
+pub fn decode_with(encoded: &str, version_len: usize, checksum_len: usize, hash: ChecksumHash) -> Result<(Vec<u8>, Vec<u8>), Error> {
+    let decoded = bs58::decode(encoded)
+        .into_vec()
+        .map_err(|e| Error::new(&format!("invalid base58 string {}", encoded)).with_source(e))?;
+    if decoded.len() < version_len + checksum_len {
+        return Err(Error::new(&format!("base58check data {} is too short", encoded)));
+    }
+    let (data, actual) = decoded.split_at(decoded.len() - checksum_len);
+    if checksum(data, checksum_len, hash) != actual {
+        return Err(Error::new(&format!("invalid base58check checksum of {}", encoded)));
+    }
+    let (version, payload) = data.split_at(version_len);
+    Ok((version.to_vec(), payload.to_vec()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let payload = hex::decode("5f2be9a02b43f748ee460bf36eed24fafa109920").unwrap();
+        let encoded = encode(&[0x01], &payload, DEFAULT_CHECKSUM_LENGTH);
+        assert_eq!("Z1pnS94bP4hQSYLs4aP4UwBP9pH8bEvhi", encoded);
+        assert_eq!((vec![0x01], payload.clone()), decode(&encoded, 1, DEFAULT_CHECKSUM_LENGTH).unwrap());
+
+        let xprv_version = [0x04, 0x88, 0xad, 0xe4];
+        let encoded = encode(&xprv_version, &payload, 2);
+        assert_eq!((xprv_version.to_vec(), payload), decode(&encoded, 4, 2).unwrap());
+    }
+
+    #[test]
+    fn test_decode_corrupted_checksum() {
+        let payload = hex::decode("5f2be9a02b43f748ee460bf36eed24fafa109920").unwrap();
+        let mut data = bs58::decode(encode(&[0x01], &payload, DEFAULT_CHECKSUM_LENGTH)).into_vec().unwrap();
+        let last = data.len() - 1;
+        data[last] ^= 0x01;
+        let corrupted = bs58::encode(data).into_string();
+        assert!(decode(&corrupted, 1, DEFAULT_CHECKSUM_LENGTH).is_err());
+
+        assert!(decode("0OIl", 1, DEFAULT_CHECKSUM_LENGTH).is_err());
+        assert!(decode("2g", 1, DEFAULT_CHECKSUM_LENGTH).is_err());
+    }
+}
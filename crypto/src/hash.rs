@@ -5,19 +5,83 @@ use model::Curve;
 /// # 哈希
 /// ## 入参
 /// + `message: &[u8]`: 消息
-/// + `curve: Curve`: secp256k1时哈希算法为sha256, sm2p256v1时哈希算法为sm3
+/// + `curve: Curve`: secp256k1和ed25519时哈希算法为sha256, sm2p256v1时哈希算法为sm3
 ///
 /// ## 出参
 /// + `String`: 哈希字符串
 pub fn hash_message(message: &[u8], curve: Curve) -> String {
     match curve {
         Curve::Secp256k1 => {
-            sha256::digest(message)
+            hex::encode(sha256(message))
         }
         Curve::Sm2p256v1 => {
-            let mut hash = Sm3Hash::new(message);
-            let digest = hash.get_hash().to_vec();
-            hex::encode(digest)
+            hex::encode(sm3(message))
         }
+        #[cfg(feature = "ed25519")]
+        Curve::Ed25519 => {
+            hex::encode(sha256(message))
+        }
+    }
+}
+
+/// # EIP-191（personal_sign）消息的哈希，在消息前加上`"\x19Ethereum Signed Message:\n" + 消息的字节长度`后哈希
+/// ## 入参
+/// + `message: &[u8]`: 消息
+/// + `curve: Curve`: 决定哈希算法，与`hash_message`相同
+///
+/// ## 出参
+/// + `[u8; 32]`: 哈希
+pub fn hash_personal_message(message: &[u8], curve: Curve) -> [u8; 32] {
+    let prefixed = [format!("\x19Ethereum Signed Message:\n{}", message.len()).as_bytes(), message].concat();
+    match curve {
+        Curve::Sm2p256v1 => sm3(&prefixed),
+        _ => sha256(&prefixed),
+    }
+}
+
+/// # SHA-256哈希
+/// ## 入参
+/// + `data: &[u8]`: 数据
+///
+/// ## 出参
+/// + `[u8; 32]`: 哈希
+pub fn sha256(data: &[u8]) -> [u8; 32] {
+    let mut digest = [0u8; 32];
+    digest.copy_from_slice(&hex::decode(sha256::digest(data)).unwrap());
+    digest
+}
+
+/// # SM3哈希
+/// ## 入参
+/// + `data: &[u8]`: 数据
+///
+/// ## 出参
+/// + `[u8; 32]`: 哈希
+pub fn sm3(data: &[u8]) -> [u8; 32] {
+    let mut hash = Sm3Hash::new(data);
+    hash.get_hash()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sha256() {
+        let expected = "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad";
+        assert_eq!(expected, hex::encode(sha256(b"abc")));
+    }
+
+    #[test]
+    fn test_hash_personal_message() {
+        let prefixed = b"\x19Ethereum Signed Message:\n5hello";
+        assert_eq!(sha256(prefixed), hash_personal_message(b"hello", Curve::Secp256k1));
+        assert_eq!(sm3(prefixed), hash_personal_message(b"hello", Curve::Sm2p256v1));
+    }
+
+    #[test]
+    fn test_sm3() {
+        let expected = "66c7f0f462eeedd9d1f2d46bdc10e4e24167c4875cf2f7a2297da02b8f4ba8e0";
+        assert_eq!(expected, hex::encode(sm3(b"abc")));
     }
 }
\ No newline at end of file
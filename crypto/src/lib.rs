@@ -5,10 +5,13 @@ pub mod sign;
 pub mod model;
 
 pub mod address;
+pub mod base58check;
 pub mod aes;
 pub mod transaction;
+pub mod rlp_cursor;
 
 pub mod hash;
+pub mod typed_data;
 
 #[cfg(test)]
 mod tests {
@@ -0,0 +1,126 @@
+use rlp::Rlp;
+
+use model::Error;
+
+/// RLP列表的第一个字节的最小值，小于该值的为字节串
+const RLP_LIST_OFFSET: u8 = 0xc0;
+
+/// 按顺序读取RLP编码的游标，每次读取一个元素并前移，读取嵌套的列表时返回列表内容的游标
+#[derive(Debug, Clone, Copy)]
+pub struct RlpCursor<'a> {
+    buf: &'a [u8],
+}
+
+impl<'a> RlpCursor<'a> {
+    /// # 在RLP编码的字节上创建游标
+    ///
+    /// ## 入参
+    /// + `buf: &'a [u8]`: RLP编码，可以包含多个连续的元素
+    pub fn new(buf: &'a [u8]) -> Self {
+        RlpCursor { buf }
+    }
+
+    /// # 是否已经读取完所有元素
+    pub fn is_empty(&self) -> bool {
+        self.buf.is_empty()
+    }
+
+    /// # 读取一个列表，返回列表内容的游标，当前游标前移到列表之后
+    ///
+    /// ## 出参
+    /// + `Result<RlpCursor<'a>, Error>`: 没有剩余元素、数据不完整或者下一个元素不是列表时返回`Err`
+    pub fn enter_list(&mut self) -> Result<RlpCursor<'a>, Error> {
+        let (is_list, payload) = self.next_item()?;
+        if !is_list {
+            return Err(Error::new("rlp item is not a list"));
+        }
+        Ok(RlpCursor::new(payload))
+    }
+
+    /// # 读取一个字节串
+    ///
+    /// ## 出参
+    /// + `Result<&'a [u8], Error>`: 没有剩余元素、数据不完整或者下一个元素是列表时返回`Err`
+    pub fn next_bytes(&mut self) -> Result<&'a [u8], Error> {
+        let (is_list, payload) = self.next_item()?;
+        if is_list {
+            return Err(Error::new("rlp item is a list, expected bytes"));
+        }
+        Ok(payload)
+    }
+
+    /// # 读取一个大端编码的无符号整数，空字节串为0
+    ///
+    /// ## 出参
+    /// + `Result<u64, Error>`: 字节串超过8个字节或者有前导零时返回`Err`
+    pub fn next_u64(&mut self) -> Result<u64, Error> {
+        let bytes = self.next_bytes()?;
+        if bytes.len() > 8 {
+            return Err(Error::new(&format!("rlp integer of {} bytes overflows u64", bytes.len())));
+        }
+        if bytes.first() == Some(&0) {
+            return Err(Error::new("rlp integer has leading zero"));
+        }
+        Ok(bytes.iter().fold(0u64, |acc, b| (acc << 8) | *b as u64))
+    }
+
+    /// # 读取下一个元素，返回是否为列表和元素的内容（不含头部）
+    fn next_item(&mut self) -> Result<(bool, &'a [u8]), Error> {
+        let first = *self.buf.first().ok_or_else(|| Error::new("rlp cursor is empty"))?;
+        let info = Rlp::new(self.buf)
+            .payload_info()
+            .map_err(|e| Error::new(&format!("invalid rlp item, {}", e)))?;
+        let end = info.header_len + info.value_len;
+        let payload = &self.buf[info.header_len..end];
+        self.buf = &self.buf[end..];
+        Ok((first >= RLP_LIST_OFFSET, payload))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rlp::RlpStream;
+
+    use crate::rlp_cursor::RlpCursor;
+
+    #[test]
+    fn test_nested_list() {
+        // [a, [b, c], d]，a为整数1024，b为字节串，c为空字节串，d为56字节的长字节串
+        let long = vec![0xab; 56];
+        let mut stream = RlpStream::new_list(3);
+        stream.append(&1024u64);
+        stream.begin_list(2);
+        stream.append(&vec![0x01u8, 0x02]);
+        stream.append(&Vec::<u8>::new());
+        stream.append(&long);
+        let encoded = stream.out().to_vec();
+
+        let mut root = RlpCursor::new(&encoded);
+        let mut list = root.enter_list().unwrap();
+        assert!(root.is_empty());
+        assert_eq!(1024, list.next_u64().unwrap());
+
+        let mut inner = list.enter_list().unwrap();
+        assert_eq!(&[0x01, 0x02], inner.next_bytes().unwrap());
+        assert_eq!(0, inner.next_u64().unwrap());
+        assert!(inner.is_empty());
+        assert!(inner.next_bytes().is_err());
+
+        assert_eq!(long.as_slice(), list.next_bytes().unwrap());
+        assert!(list.is_empty());
+    }
+
+    #[test]
+    fn test_malformed() {
+        let mut stream = RlpStream::new_list(1);
+        stream.append(&vec![0x01u8, 0x02]);
+        let encoded = stream.out().to_vec();
+
+        assert!(RlpCursor::new(&encoded).next_bytes().is_err());
+        assert!(RlpCursor::new(&encoded[1..]).enter_list().is_err());
+        // 声明的长度超出实际数据
+        assert!(RlpCursor::new(&encoded[..encoded.len() - 1]).enter_list().is_err());
+        assert!(RlpCursor::new(&[0x89, 1, 2, 3, 4, 5, 6, 7, 8, 9]).next_u64().is_err());
+        assert!(RlpCursor::new(&[0x82, 0x00, 0x01]).next_u64().is_err());
+    }
+}
@@ -2,16 +2,20 @@ use libsm::sm2::ecc::EccCtx;
 use libsm::sm2::signature::{SigCtx, Signature};
 use num_bigint::BigUint;
 use once_cell::sync::Lazy;
+use rand::{CryptoRng, RngCore};
 use secp256k1::{All, Message, PublicKey, rand::rngs::OsRng, Secp256k1, SecretKey};
-use secp256k1::ecdsa::Signature as SigNist;
+use secp256k1::ecdsa::{RecoverableSignature, RecoveryId, Signature as SigNist};
 
+use model::constants::{PREFIX_OF_HEX, PRIVATE_KEY_LENGTH};
 use model::enums::Curve;
+use model::Error;
 
+use crate::hash::{hash_personal_message, sm3};
 use crate::public_key_to_address;
 
 #[derive(Debug)]
 pub struct KeyPair {
-    /// 公钥，非压缩公钥，由1字节的前缀(标识y坐标的奇偶，0x02/0x03)+32字节的x坐标+32字节的y坐标
+    /// 公钥，非压缩公钥，由1字节的前缀(标识y坐标的奇偶，0x02/0x03)+32字节的x坐标+32字节的y坐标；Ed25519为32字节的压缩点
     pub public_key: Vec<u8>,
     /// 私钥，32字节
     pub secret_key: BigUint,
@@ -25,10 +29,23 @@ pub static CURVE_SM2P256V1: Lazy<EccCtx> = Lazy::new(EccCtx::new);
 
 impl KeyPair {
     pub fn new_keypair(curve: Curve) -> KeyPair {
+        Self::new_keypair_with_rng(curve, &mut OsRng)
+    }
+
+    /// # 使用指定的随机数生成器生成密钥对，传入种子固定的随机数生成器可以得到确定的密钥对
+    ///
+    /// `libsm`生成国密密钥对时不支持传入随机数生成器，因此国密的私钥由`rng`在`[1, n-1]`范围内拒绝采样得到
+    ///
+    /// ## 入参
+    /// + `curve: Curve`: 椭圆曲线，Secp256k1 or Sm2p256v1
+    /// + `rng: &mut R`: 密码学安全的随机数生成器
+    ///
+    /// ## 出参
+    /// + `KeyPair`
+    pub fn new_keypair_with_rng<R: RngCore + CryptoRng>(curve: Curve, rng: &mut R) -> KeyPair {
         match curve {
             Curve::Secp256k1 => {
-                let mut rng = OsRng::default();
-                let (secret_key, public_key) = CONTEXT_SECP256K1.generate_keypair(&mut rng);
+                let (secret_key, public_key) = CONTEXT_SECP256K1.generate_keypair(rng);
 
                 KeyPair {
                     public_key: public_key.serialize_uncompressed().to_vec(),
@@ -37,7 +54,16 @@ impl KeyPair {
                 }
             }
             Curve::Sm2p256v1 => {
-                let (public_key, secret_key) = CONTEXT_SM2P256V1.new_keypair().expect("new keypair failed.");
+                let n = CURVE_SM2P256V1.get_n();
+                let secret_key = loop {
+                    let mut bytes = [0u8; 32];
+                    rng.fill_bytes(&mut bytes);
+                    let sk = BigUint::from_bytes_be(&bytes);
+                    if sk > BigUint::from(0u8) && sk < *n {
+                        break sk;
+                    }
+                };
+                let public_key = CONTEXT_SM2P256V1.pk_from_sk(&secret_key).expect("new keypair failed.");
 
                 KeyPair {
                     public_key: CURVE_SM2P256V1.point_to_bytes(&public_key, false).expect("convert point to bytes failed."),
@@ -45,33 +71,81 @@ impl KeyPair {
                     curve,
                 }
             }
+            #[cfg(feature = "ed25519")]
+            Curve::Ed25519 => {
+                let signing_key = ed25519_dalek::SigningKey::generate(rng);
+
+                KeyPair {
+                    public_key: signing_key.verifying_key().to_bytes().to_vec(),
+                    secret_key: BigUint::from_bytes_be(&signing_key.to_bytes()),
+                    curve,
+                }
+            }
         }
     }
 
-    /// 从私钥恢复密钥对
+    /// 从私钥恢复密钥对，私钥不合法时panic，不希望panic时使用`try_from_secret_key`
     /// bytes 私钥
     /// curve Secp256k1 or Sm2p256v1
     pub fn from_secret_key(bytes: &[u8], curve: Curve) -> KeyPair {
+        Self::try_from_secret_key(bytes, curve).unwrap()
+    }
+
+    /// # 从私钥恢复密钥对
+    ///
+    /// ## 入参
+    /// + `bytes: &[u8]`: 私钥，必须为32字节
+    /// + `curve: Curve`: 椭圆曲线，Secp256k1 or Sm2p256v1
+    ///
+    /// ## 出参
+    /// + `Result<KeyPair, Error>`: 私钥不是32字节，或者为0、不小于曲线的阶时返回`Err`
+    pub fn try_from_secret_key(bytes: &[u8], curve: Curve) -> Result<KeyPair, Error> {
+        if bytes.len() != PRIVATE_KEY_LENGTH {
+            return Err(Error::new(&format!(
+                "invalid secret key length {}, expected {}",
+                bytes.len(),
+                PRIVATE_KEY_LENGTH
+            )));
+        }
         match curve {
             Curve::Secp256k1 => {
-                let secret_key = SecretKey::from_slice(bytes).unwrap();
+                let secret_key = SecretKey::from_slice(bytes)
+                    .map_err(|e| Error::new("secret key is out of range of secp256k1").with_source(e))?;
                 let public_key = PublicKey::from_secret_key(&CONTEXT_SECP256K1, &secret_key);
 
-                KeyPair {
+                Ok(KeyPair {
                     public_key: public_key.serialize_uncompressed().to_vec(),
                     secret_key: BigUint::from_bytes_be(&secret_key.secret_bytes()),
                     curve,
-                }
+                })
             }
             Curve::Sm2p256v1 => {
                 let secret_key = BigUint::from_bytes_be(bytes);
-                let public_key = CONTEXT_SM2P256V1.pk_from_sk(&secret_key).unwrap();
-
-                KeyPair {
-                    public_key: CURVE_SM2P256V1.point_to_bytes(&public_key, false).unwrap(),
+                if secret_key == BigUint::from(0u8) || secret_key >= *CURVE_SM2P256V1.get_n() {
+                    return Err(Error::new("secret key is out of range of sm2p256v1"));
+                }
+                let public_key = CONTEXT_SM2P256V1
+                    .pk_from_sk(&secret_key)
+                    .map_err(|e| Error::new(&format!("derive sm2p256v1 public key failed, {}", e)))?;
+                let public_key = CURVE_SM2P256V1
+                    .point_to_bytes(&public_key, false)
+                    .map_err(|e| Error::new(&format!("convert point to bytes failed, {}", e)))?;
+
+                Ok(KeyPair {
+                    public_key,
                     secret_key,
                     curve,
-                }
+                })
+            }
+            #[cfg(feature = "ed25519")]
+            Curve::Ed25519 => {
+                let signing_key = ed25519_dalek::SigningKey::from_bytes(&bytes.try_into().unwrap());
+
+                Ok(KeyPair {
+                    public_key: signing_key.verifying_key().to_bytes().to_vec(),
+                    secret_key: BigUint::from_bytes_be(bytes),
+                    curve,
+                })
             }
         }
     }
@@ -85,7 +159,7 @@ impl KeyPair {
     pub fn sign(&self, message: &[u8]) -> String {
         match self.curve {
             Curve::Secp256k1 => {
-                let sk = SecretKey::from_slice(&self.secret_key.to_bytes_be()).unwrap();
+                let sk = SecretKey::from_slice(&to_bytes_32(&self.secret_key)).unwrap();
                 let msg = Message::from_digest_slice(&message).unwrap();
                 let (recovery_id, sig) = CONTEXT_SECP256K1
                     .sign_ecdsa_recoverable(&msg, &sk).serialize_compact();
@@ -93,7 +167,8 @@ impl KeyPair {
                 let s: &[u8] = &sig[32..];
                 let recovery_id = recovery_id.to_i32() as u32 + 27;
                 format!(
-                    "0x{}{}{}",
+                    "{}{}{}{}",
+                    PREFIX_OF_HEX,
                     hex::encode(r),
                     hex::encode(s),
                     hex::encode(BigUint::from(recovery_id).to_bytes_be()),
@@ -104,77 +179,338 @@ impl KeyPair {
                 // Get the value "e", which is the hash of message and ID, EC parameters and public key
                 let digest = CONTEXT_SM2P256V1.hash("1234567812345678", &pk, message).unwrap();
                 let sig = CONTEXT_SM2P256V1.sign_raw(&digest[..], &self.secret_key).unwrap();
-                let r_bytes = sig.get_r().to_bytes_be();
-                let s_bytes = sig.get_s().to_bytes_be();
-                let mut r_padded = vec![0u8; 32 - r_bytes.len()];
-                r_padded.extend_from_slice(&r_bytes);
-                let mut s_padded = vec![0u8; 32 - s_bytes.len()];
-                s_padded.extend_from_slice(&s_bytes);
+                format_sm2_signature(sig.get_r(), sig.get_s(), &digest)
+            }
+            #[cfg(feature = "ed25519")]
+            Curve::Ed25519 => {
+                use ed25519_dalek::Signer;
 
-                format!(
-                    "0x{}{}01{}",
-                    hex::encode(r_padded),
-                    hex::encode(s_padded),
-                    hex::encode(digest),
-                )
+                let signature = self.ed25519_signing_key().sign(message);
+                format!("{}{}", PREFIX_OF_HEX, hex::encode(signature.to_bytes()))
             }
         }
     }
 
-    /// # 验签
+    /// # EIP-191（personal_sign）签名，先按`hash_personal_message`给消息加前缀并哈希，再对哈希签名
+    ///
+    /// ## 入参
+    /// + `message: &[u8]`: 原始消息，不需要预先哈希
+    ///
+    /// ## 出参
+    /// + `String`: signature 签名结果，格式与`sign`相同
+    pub fn sign_personal(&self, message: &[u8]) -> String {
+        self.sign(&hash_personal_message(message, self.curve))
+    }
+
+    /// # 确定性签名，相同的私钥和消息总是得到相同的签名，用于可复现的测试向量和审计
+    ///
+    /// + Secp256k1: `secp256k1`库按照RFC6979(HMAC-SHA256)生成k，结果与`sign`相同
+    /// + Sm2p256v1: 按照RFC6979生成k，其中HMAC的哈希算法为SM3，`x`为私钥，`h1`为SM2的摘要`e = SM3(Z_A ‖ M)`，`q`为曲线的阶n，
+    ///   k不满足`r != 0`且`r + k != n`且`s != 0`时取HMAC_DRBG的下一个输出
+    /// + Ed25519: 签名算法本身是确定性的，结果与`sign`相同
+    ///
+    /// ## 入参
+    /// + `message: &[u8]`: 待签名的消息
+    ///
+    /// ## 出参
+    /// + `String`: signature 签名结果，格式与`sign`相同
+    pub fn sign_deterministic(&self, message: &[u8]) -> String {
+        match self.curve {
+            Curve::Sm2p256v1 => {
+                let pk = CURVE_SM2P256V1.bytes_to_point(&self.public_key).unwrap();
+                let digest = CONTEXT_SM2P256V1.hash("1234567812345678", &pk, message).unwrap();
+                let n = CURVE_SM2P256V1.get_n();
+                let e = BigUint::from_bytes_be(&digest);
+                let sk = &self.secret_key;
+                let mut nonces = Rfc6979Sm3::new(&to_bytes_32(sk), &to_bytes_32(&(&e % n)));
+                loop {
+                    let k = BigUint::from_bytes_be(&nonces.next_nonce());
+                    if k == BigUint::from(0u8) || k >= *n {
+                        continue;
+                    }
+                    let (x_1, _) = CURVE_SM2P256V1.to_affine(&CURVE_SM2P256V1.g_mul(&k).unwrap()).unwrap();
+                    let r = (&e + x_1.to_biguint()) % n;
+                    if r == BigUint::from(0u8) || &r + &k == *n {
+                        continue;
+                    }
+                    // s = (1 + sk)^-1 * (k - r * sk) mod n
+                    let s1 = CURVE_SM2P256V1.inv_n(&(sk + BigUint::from(1u8))).unwrap();
+                    let s2 = (&k + n - (&r * sk) % n) % n;
+                    let s = (s1 * s2) % n;
+                    if s != BigUint::from(0u8) {
+                        return format_sm2_signature(&r, &s, &digest);
+                    }
+                }
+            }
+            // secp256k1按照RFC6979生成k，Ed25519本身是确定性的
+            _ => self.sign(message),
+        }
+    }
+
+    /// # 验签，签名格式不合法时返回false
     pub fn verify(&self, message: &[u8], signature: &str) -> bool {
+        let signature = match KeyPair::get_clean_signature_hex(signature) {
+            Ok(signature) => signature,
+            Err(_) => return false,
+        };
         match self.curve {
             Curve::Secp256k1 => {
                 let msg = Message::from_digest_slice(&message).unwrap();
-                let sk = SecretKey::from_slice(self.secret_key.to_bytes_be().as_slice()).unwrap();
+                let sk = SecretKey::from_slice(&to_bytes_32(&self.secret_key)).unwrap();
                 let mut pk = PublicKey::from_secret_key(&CONTEXT_SECP256K1, &sk).serialize_uncompressed();
                 pk[0] = 4;
                 let public_key = PublicKey::from_slice(&pk).unwrap();
-                let signature = KeyPair::get_clean_signature_hex(&signature);
                 let signature = hex::decode(signature).unwrap();
-                let signature = SigNist::from_compact(signature.as_slice()).unwrap();
-                CONTEXT_SECP256K1.verify_ecdsa(&msg, &signature, &public_key).is_ok()
+                match SigNist::from_compact(signature.as_slice()) {
+                    Ok(signature) => CONTEXT_SECP256K1.verify_ecdsa(&msg, &signature, &public_key).is_ok(),
+                    Err(_) => false,
+                }
             }
             Curve::Sm2p256v1 => {
                 let sk = BigUint::from_bytes_be(self.secret_key.to_bytes_be().as_slice());
                 let pk = CONTEXT_SM2P256V1.pk_from_sk(&sk).unwrap();
-                let signature = KeyPair::get_clean_signature_hex(signature);
                 let r = hex::decode(&(signature[0..64])).unwrap();
                 let s = hex::decode(&(signature[64..])).unwrap();
                 let signature = Signature::new(r.as_slice(), s.as_slice());
-                CONTEXT_SM2P256V1.verify(&message, &pk, &signature).is_ok()
+                matches!(CONTEXT_SM2P256V1.verify(message, &pk, &signature), Ok(true))
+            }
+            #[cfg(feature = "ed25519")]
+            Curve::Ed25519 => {
+                let signature = hex::decode(signature).unwrap();
+                let signature = ed25519_dalek::Signature::from_bytes(&signature.try_into().unwrap());
+                self.ed25519_signing_key().verify_strict(message, &signature).is_ok()
             }
         }
     }
 
+    /// # Ed25519的签名私钥，私钥按大端补齐到32字节
+    #[cfg(feature = "ed25519")]
+    fn ed25519_signing_key(&self) -> ed25519_dalek::SigningKey {
+        let bytes = self.secret_key.to_bytes_be();
+        let mut padded = [0u8; 32];
+        padded[32 - bytes.len()..].copy_from_slice(&bytes);
+        ed25519_dalek::SigningKey::from_bytes(&padded)
+    }
+
     /// # 只获取签名中的r、s
-    fn get_clean_signature_hex(signature: &str) -> &str {
-        let hex_str = if signature.starts_with("0x") {
-            &signature[2..]
-        } else {
-            signature
-        };
-        &hex_str[..hex_str.len().min(128)]
+    ///
+    /// ## 入参
+    /// + `signature: &str`: 签名，可带0x前缀
+    ///
+    /// ## 出参
+    /// + `Result<&str, Error>`: r、s的hex字符串，签名不是偶数长度的hex字符串或者不足r、s的长度时返回错误
+    fn get_clean_signature_hex(signature: &str) -> Result<&str, Error> {
+        let hex_str = signature.strip_prefix(PREFIX_OF_HEX).unwrap_or(signature);
+        if !hex_str.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return Err(Error::new(&format!("signature {} is not a hex string", signature)));
+        }
+        if !hex_str.len().is_multiple_of(2) || hex_str.len() < 128 {
+            return Err(Error::new(&format!("invalid signature length {}", hex_str.len())));
+        }
+        Ok(&hex_str[..128])
     }
 
     /// # 获取地址
     pub fn address(&self) -> String {
+        #[cfg(feature = "ed25519")]
+        if let Curve::Ed25519 = self.curve {
+            return public_key_to_address(&self.public_key, self.curve);
+        }
         let key_encode = &hex::encode(&self.public_key)[2..];
         let key_decode = hex::decode(key_encode).unwrap();
         public_key_to_address(&key_decode, self.curve)
     }
 }
 
+/// # 使用公钥验证签名，验证方不需要签名者的私钥
+///
+/// ## 入参
+/// + `message: &[u8]`: 被签名的消息，32字节的哈希
+/// + `signature: &str`: 签名，可带0x前缀，格式与`KeyPair::sign`相同
+/// + `public_key: &[u8]`: 签名者的公钥，格式与`KeyPair::public_key`相同
+/// + `curve: Curve`: 椭圆曲线
+///
+/// ## 出参
+/// + `Result<bool, Error>`: 公钥或消息不合法时返回`Err`，签名格式不合法时返回`Ok(false)`
+pub fn verify_with_public_key(message: &[u8], signature: &str, public_key: &[u8], curve: Curve) -> Result<bool, Error> {
+    let signature = match KeyPair::get_clean_signature_hex(signature).ok().and_then(|v| hex::decode(v).ok()) {
+        Some(signature) => signature,
+        None => return Ok(false),
+    };
+    match curve {
+        Curve::Secp256k1 => {
+            let public_key = PublicKey::from_slice(public_key)
+                .map_err(|e| Error::new("invalid secp256k1 public key").with_source(e))?;
+            let message = Message::from_digest_slice(message)
+                .map_err(|e| Error::new("message must be a 32 bytes hash").with_source(e))?;
+            Ok(match SigNist::from_compact(&signature) {
+                Ok(signature) => CONTEXT_SECP256K1.verify_ecdsa(&message, &signature, &public_key).is_ok(),
+                Err(_) => false,
+            })
+        }
+        Curve::Sm2p256v1 => {
+            let public_key = CURVE_SM2P256V1
+                .bytes_to_point(public_key)
+                .map_err(|e| Error::new(&format!("invalid sm2p256v1 public key, {:?}", e)))?;
+            let signature = Signature::new(&signature[0..32], &signature[32..]);
+            Ok(matches!(CONTEXT_SM2P256V1.verify(message, &public_key, &signature), Ok(true)))
+        }
+        #[cfg(feature = "ed25519")]
+        Curve::Ed25519 => {
+            let public_key: [u8; 32] = public_key.try_into().map_err(|_| Error::new("invalid ed25519 public key"))?;
+            let public_key = ed25519_dalek::VerifyingKey::from_bytes(&public_key)
+                .map_err(|e| Error::new("invalid ed25519 public key").with_source(e))?;
+            Ok(match <[u8; 64]>::try_from(signature.as_slice()) {
+                Ok(signature) => public_key.verify_strict(message, &ed25519_dalek::Signature::from_bytes(&signature)).is_ok(),
+                Err(_) => false,
+            })
+        }
+    }
+}
+
+/// # 从签名中恢复签名者的地址
+///
+/// 只有Secp256k1支持，签名的最后一个字节为恢复id（`27`/`28`，也接受`0`/`1`）；国密等其他曲线没有标准的公钥恢复算法，返回`Err`
+///
+/// ## 入参
+/// + `message: &[u8]`: 被签名的消息，32字节的哈希
+/// + `signature: &str`: 签名，可带0x前缀，格式与`KeyPair::sign`相同
+/// + `curve: Curve`: 椭圆曲线
+///
+/// ## 出参
+/// + `Result<String, Error>`: 签名者的`zltc_`地址
+pub fn recover_address(message: &[u8], signature: &str, curve: Curve) -> Result<String, Error> {
+    if curve != Curve::Secp256k1 {
+        return Err(Error::new(&format!("recover address is unsupported for curve {:?}", curve)));
+    }
+    let rs = KeyPair::get_clean_signature_hex(signature)?;
+    let hex_str = signature.strip_prefix(PREFIX_OF_HEX).unwrap_or(signature);
+    let v = hex_str
+        .get(128..130)
+        .and_then(|v| u8::from_str_radix(v, 16).ok())
+        .ok_or_else(|| Error::new("signature is missing the recovery id"))?;
+    let v = if v >= 27 { v - 27 } else { v };
+    let recovery_id = RecoveryId::from_i32(v as i32)
+        .map_err(|e| Error::new(&format!("invalid recovery id {}", v)).with_source(e))?;
+    let rs = hex::decode(rs).map_err(|e| Error::new("signature is not a hex string").with_source(e))?;
+    let signature = RecoverableSignature::from_compact(&rs, recovery_id)
+        .map_err(|e| Error::new("invalid secp256k1 signature").with_source(e))?;
+    let message = Message::from_digest_slice(message)
+        .map_err(|e| Error::new("message must be a 32 bytes hash").with_source(e))?;
+    let public_key = CONTEXT_SECP256K1
+        .recover_ecdsa(&message, &signature)
+        .map_err(|e| Error::new("recover public key failed").with_source(e))?;
+    Ok(public_key_to_address(&public_key.serialize_uncompressed()[1..], curve))
+}
+
+/// # 从`KeyPair::sign_personal`的签名中恢复签名者的地址，与`recover_address`一样只支持Secp256k1
+///
+/// ## 入参
+/// + `message: &[u8]`: 原始消息，不需要预先哈希
+/// + `signature: &str`: 签名，可带0x前缀
+/// + `curve: Curve`: 椭圆曲线
+///
+/// ## 出参
+/// + `Result<String, Error>`: 签名者的`zltc_`地址
+pub fn recover_personal(message: &[u8], signature: &str, curve: Curve) -> Result<String, Error> {
+    recover_address(&hash_personal_message(message, curve), signature, curve)
+}
+
+/// # 国密签名的格式：32字节的r+32字节的s+1字节的分隔符`01`+32字节的e
+fn format_sm2_signature(r: &BigUint, s: &BigUint, digest: &[u8]) -> String {
+    format!(
+        "{}{}{}01{}",
+        PREFIX_OF_HEX,
+        hex::encode(to_bytes_32(r)),
+        hex::encode(to_bytes_32(s)),
+        hex::encode(digest),
+    )
+}
+
+/// # 大端编码并在左侧补零到32字节
+fn to_bytes_32(value: &BigUint) -> [u8; 32] {
+    let bytes = value.to_bytes_be();
+    let mut padded = [0u8; 32];
+    padded[32 - bytes.len()..].copy_from_slice(&bytes);
+    padded
+}
+
+/// # HMAC-SM3
+fn hmac_sm3(key: &[u8], data: &[&[u8]]) -> [u8; 32] {
+    const BLOCK_SIZE: usize = 64;
+    let mut block = [0u8; BLOCK_SIZE];
+    if key.len() > BLOCK_SIZE {
+        block[..32].copy_from_slice(&sm3(key));
+    } else {
+        block[..key.len()].copy_from_slice(key);
+    }
+    let ipad: Vec<u8> = block.iter().map(|b| b ^ 0x36).collect();
+    let opad: Vec<u8> = block.iter().map(|b| b ^ 0x5c).collect();
+    let inner = sm3(&[ipad.as_slice(), &data.concat()].concat());
+    sm3(&[opad.as_slice(), &inner].concat())
+}
+
+/// RFC6979 3.2节中生成k的HMAC_DRBG，哈希算法为SM3。SM3的输出长度与曲线阶的长度都是256位，因此每次输出一个哈希即为一个候选的k
+struct Rfc6979Sm3 {
+    k: [u8; 32],
+    v: [u8; 32],
+    /// 是否已经输出过候选的k，再次输出前需要更新状态
+    started: bool,
+}
+
+impl Rfc6979Sm3 {
+    /// # 初始化
+    ///
+    /// ## 入参
+    /// + `x: &[u8; 32]`: 私钥
+    /// + `h1: &[u8; 32]`: 摘要对曲线的阶取模
+    fn new(x: &[u8; 32], h1: &[u8; 32]) -> Self {
+        let v = [0x01u8; 32];
+        let k = hmac_sm3(&[0x00u8; 32], &[&v, &[0x00], x, h1]);
+        let v = hmac_sm3(&k, &[&v]);
+        let k = hmac_sm3(&k, &[&v, &[0x01], x, h1]);
+        let v = hmac_sm3(&k, &[&v]);
+        Rfc6979Sm3 { k, v, started: false }
+    }
+
+    /// # 下一个候选的k
+    fn next_nonce(&mut self) -> [u8; 32] {
+        if self.started {
+            self.k = hmac_sm3(&self.k, &[&self.v, &[0x00]]);
+            self.v = hmac_sm3(&self.k, &[&self.v]);
+        }
+        self.started = true;
+        self.v = hmac_sm3(&self.k, &[&self.v]);
+        self.v
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use model::constants::{PRIVATE_KEY_LENGTH, UNCOMPRESSED_PUBLIC_KEY_LENGTH};
     use model::enums::Curve;
     use model::HexString;
 
-    use crate::hash::hash_message;
+    use crate::hash::{hash_message, sha256};
 
     use super::*;
 
+    #[test]
+    fn new_keypair_with_seeded_rng() {
+        use rand::rngs::StdRng;
+        use rand::SeedableRng;
+
+        for curve in [Curve::Sm2p256v1, Curve::Secp256k1] {
+            let keypair = KeyPair::new_keypair_with_rng(curve, &mut StdRng::seed_from_u64(42));
+            let same = KeyPair::new_keypair_with_rng(curve, &mut StdRng::seed_from_u64(42));
+            let other = KeyPair::new_keypair_with_rng(curve, &mut StdRng::seed_from_u64(43));
+            assert_eq!(keypair.secret_key, same.secret_key);
+            assert_eq!(keypair.public_key, same.public_key);
+            assert_ne!(keypair.secret_key, other.secret_key);
+            assert_eq!(KeyPair::from_secret_key(&keypair.secret_key.to_bytes_be(), curve).public_key, keypair.public_key);
+        }
+    }
+
     #[test]
     fn new_keypair() {
         let keypair_sm2p256v1 = KeyPair::new_keypair(Curve::Sm2p256v1);
@@ -203,6 +539,24 @@ mod tests {
         assert_eq!(actual, expected);
     }
 
+    #[test]
+    fn try_from_secret_key_invalid() {
+        for curve in [Curve::Secp256k1, Curve::Sm2p256v1] {
+            assert!(KeyPair::try_from_secret_key(&[0x23; 31], curve).is_err());
+            assert!(KeyPair::try_from_secret_key(&[0x23; 33], curve).is_err());
+            assert!(KeyPair::try_from_secret_key(&[0; 32], curve).is_err());
+            assert!(KeyPair::try_from_secret_key(&[0xff; 32], curve).is_err());
+            assert!(KeyPair::try_from_secret_key(&[0x23; 32], curve).is_ok());
+        }
+
+        let secp256k1_n = hex::decode("fffffffffffffffffffffffffffffffebaaedce6af48a03bbfd25e8cd0364141").unwrap();
+        assert!(KeyPair::try_from_secret_key(&secp256k1_n, Curve::Secp256k1).is_err());
+        let sm2p256v1_n = to_bytes_32(CURVE_SM2P256V1.get_n());
+        assert!(KeyPair::try_from_secret_key(&sm2p256v1_n, Curve::Sm2p256v1).is_err());
+        let below_n = to_bytes_32(&(CURVE_SM2P256V1.get_n() - BigUint::from(1u8)));
+        assert!(KeyPair::try_from_secret_key(&below_n, Curve::Sm2p256v1).is_ok());
+    }
+
     #[test]
     fn sign_and_verify_secp256k1() {
         let sk = HexString::new("0xc842e1ef9ece7e992a4021423a58d6e89c751881e43fd7dbebe70f932ad493e2").decode();
@@ -226,6 +580,50 @@ mod tests {
         assert_eq!(b, true);
     }
 
+    #[test]
+    fn recover_address_secp256k1() {
+        let sk = hex::decode("c842e1ef9ece7e992a4021423a58d6e89c751881e43fd7dbebe70f932ad493e2").unwrap();
+        let message = hex::decode("790dcb1e43ac151998f8c2e59e0959072f9d476d19fb6f98d7a4e59ea5f8e59e").unwrap();
+        let signature = "0xc8eced818b011433b5d486f9f0c97c8d0180a0df042bcaf1e75a7cd20d66920a5bbc4901bd90353fc62828ed2a821a801440f294779fc402033bf92c7657c3061b";
+        let expected = KeyPair::from_secret_key(&sk, Curve::Secp256k1).address();
+
+        assert_eq!(recover_address(&message, signature, Curve::Secp256k1).unwrap(), expected);
+        assert_eq!(recover_address(&message, &signature[2..], Curve::Secp256k1).unwrap(), expected);
+        // 恢复id为0/1的签名
+        let raw_v = format!("{}00", &signature[..signature.len() - 2]);
+        assert_eq!(recover_address(&message, &raw_v, Curve::Secp256k1).unwrap(), expected);
+
+        assert_ne!(recover_address(&[0u8; 32], signature, Curve::Secp256k1).unwrap(), expected);
+        assert!(recover_address(&message, &signature[..130], Curve::Secp256k1).is_err());
+        assert!(recover_address(&message, "0x0102", Curve::Secp256k1).is_err());
+        assert!(recover_address(&message[..31], signature, Curve::Secp256k1).is_err());
+    }
+
+    #[test]
+    fn sign_personal() {
+        let message = b"hello lattice";
+        let secp256k1 = KeyPair::from_secret_key(&hex::decode("c842e1ef9ece7e992a4021423a58d6e89c751881e43fd7dbebe70f932ad493e2").unwrap(), Curve::Secp256k1);
+        let signature = secp256k1.sign_personal(message);
+        assert!(secp256k1.verify(&hash_personal_message(message, Curve::Secp256k1), &signature));
+        assert_eq!(recover_personal(message, &signature, Curve::Secp256k1).unwrap(), secp256k1.address());
+        assert_ne!(recover_personal(b"hello", &signature, Curve::Secp256k1).unwrap(), secp256k1.address());
+        // 与直接对原始消息的哈希签名不同
+        assert_ne!(recover_address(&sha256(message), &signature, Curve::Secp256k1).unwrap(), secp256k1.address());
+
+        let sm2p256v1 = KeyPair::from_secret_key(&hex::decode("29d63245990076b0bbb33f7482beef21855a8d2197c8d076c2356c49e2a06322").unwrap(), Curve::Sm2p256v1);
+        let signature = sm2p256v1.sign_personal(message);
+        assert!(sm2p256v1.verify(&hash_personal_message(message, Curve::Sm2p256v1), &signature));
+        assert!(!sm2p256v1.verify(&hash_personal_message(b"hello", Curve::Sm2p256v1), &signature));
+        assert!(recover_personal(message, &signature, Curve::Sm2p256v1).is_err());
+    }
+
+    #[test]
+    fn recover_address_sm2p256v1_unsupported() {
+        let message = hex::decode("790dcb1e43ac151998f8c2e59e0959072f9d476d19fb6f98d7a4e59ea5f8e59e").unwrap();
+        let signature = "0xa7fd7d7675f3db3917dbf667ff6b981fc79fef75b51a2de6bd032fac4e06159e8cbf1fa9e84c8dc4fe6a5b9c01e45246b1bfb6a066c19f9e25d1185cba313374011bab3d01ceb5c070d2291bd15fa2087205cbce2cc68df51561d915956ed83ed5";
+        assert!(recover_address(&message, signature, Curve::Sm2p256v1).is_err());
+    }
+
     #[test]
     fn verify_sm2p256v1() {
         let sk = hex::decode("ae96ce342785f0a2663098336a42598eae814a5020433f193aca6c08af71a6a6").unwrap();
@@ -245,7 +643,7 @@ mod tests {
 
         let keypair = KeyPair::from_secret_key(&sk, Curve::Sm2p256v1);
         let b = keypair.verify(&message, &signature);
-        assert_eq!(b, true);
+        assert!(!b);
     }
 
     #[test]
@@ -274,6 +672,38 @@ mod tests {
         assert_eq!(pass, true)
     }
 
+    #[test]
+    fn sign_deterministic() {
+        let message = hex::decode("0102030405060708010203040506070801020304050607080102030405060708").unwrap();
+        for (sk, curve) in [
+            ("c842e1ef9ece7e992a4021423a58d6e89c751881e43fd7dbebe70f932ad493e2", Curve::Secp256k1),
+            ("29d63245990076b0bbb33f7482beef21855a8d2197c8d076c2356c49e2a06322", Curve::Sm2p256v1),
+        ] {
+            let key_pair = KeyPair::from_secret_key(&hex::decode(sk).unwrap(), curve);
+            let signature = key_pair.sign_deterministic(&message);
+            assert_eq!(signature, key_pair.sign_deterministic(&message));
+            assert!(key_pair.verify(&message, &signature));
+
+            let other = hex::decode("0807060504030201080706050403020108070605040302010807060504030201").unwrap();
+            assert_ne!(signature, key_pair.sign_deterministic(&other));
+        }
+    }
+
+    /// RFC6979的secp256k1测试向量，私钥为1，消息为`sha256("Satoshi Nakamoto")`，s为low-s形式
+    #[test]
+    fn sign_deterministic_rfc6979_secp256k1() {
+        let mut sk = [0u8; 32];
+        sk[31] = 1;
+        let key_pair = KeyPair::from_secret_key(&sk, Curve::Secp256k1);
+        let message = crate::hash::sha256(b"Satoshi Nakamoto");
+        let signature = key_pair.sign_deterministic(&message);
+        assert_eq!(
+            &signature[2..130],
+            "934b1ea10a4b3c1757e2b0c017d0b6143ce3c9a7e6a4a49860d7a6ab210ee3d82442ce9d2b916064108014783e923ec36b49743e2ffa1c4496f01a512aafd9e5"
+        );
+        assert_eq!(signature, key_pair.sign_deterministic(&message));
+    }
+
     #[test]
     fn recovery_keypair() {
         let sk = HexString::new("0x72ffdd7245e0ad7cffd533ad99f54048bf3fa6358e071fba8c2d7783d992d997").decode();
@@ -300,4 +730,53 @@ mod tests {
         let expected = "zltc_hJzY5yCoXmbnKFYJQDBeZHgamcSQALJFx";
         assert_eq!(address, expected)
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn verify_malformed_signature() {
+        let sk = hex::decode("ae96ce342785f0a2663098336a42598eae814a5020433f193aca6c08af71a6a6").unwrap();
+        let message = hex::decode("790dcb1e43ac151998f8c2e59e0959072f9d476d19fb6f98d7a4e59ea5f8e59e").unwrap();
+        let odd_length = "0xa7fd7d7675f3db3917dbf667ff6b981fc79fef75b51a2de6bd032fac4e06159e8cbf1fa9e84c8dc4fe6a5b9c01e45246b1bfb6a066c19f9e25d1185cba3133740";
+        let non_hex = "0xzzfd7d7675f3db3917dbf667ff6b981fc79fef75b51a2de6bd032fac4e06159e8cbf1fa9e84c8dc4fe6a5b9c01e45246b1bfb6a066c19f9e25d1185cba313374";
+
+        assert!(KeyPair::get_clean_signature_hex(odd_length).is_err());
+        assert!(KeyPair::get_clean_signature_hex(non_hex).is_err());
+        assert!(KeyPair::get_clean_signature_hex("0x0102").is_err());
+        for curve in [Curve::Sm2p256v1, Curve::Secp256k1] {
+            let keypair = KeyPair::from_secret_key(&sk, curve);
+            assert!(!keypair.verify(&[0u8; 32], &keypair.sign(&message)));
+            assert!(!keypair.verify(&message, odd_length));
+            assert!(!keypair.verify(&message, non_hex));
+            assert!(!keypair.verify(&message, ""));
+        }
+    }
+
+    /// RFC 8032 TEST 1
+    #[cfg(feature = "ed25519")]
+    const ED25519_SK: &str = "9d61b19deffd5a60ba844af492ec2cc44449c5697b326919703bac031cae7f60";
+
+    #[test]
+    #[cfg(feature = "ed25519")]
+    fn sign_verify_ed25519() {
+        let keypair = KeyPair::from_secret_key(&hex::decode(ED25519_SK).unwrap(), Curve::Ed25519);
+        assert_eq!(hex::encode(&keypair.public_key), "d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a");
+
+        let signature = keypair.sign(&[]);
+        assert_eq!(signature, "0xe5564300c360ac729086e2cc806e828a84877f1eb8e5d974d873e065224901555fb8821590a33bacc61e39701cf9b46bd25bf5f0595bbe24655141438e7a100b");
+        assert!(keypair.verify(&[], &signature));
+        assert!(!keypair.verify(&[0u8], &signature));
+        assert!(!keypair.verify(&[], "0x0102"));
+
+        let keypair = KeyPair::new_keypair(Curve::Ed25519);
+        let message = hash_message(b"lattice", Curve::Ed25519);
+        assert!(keypair.verify(message.as_bytes(), &keypair.sign(message.as_bytes())));
+    }
+
+    #[test]
+    #[cfg(feature = "ed25519")]
+    fn generate_address_ed25519() {
+        let keypair = KeyPair::from_secret_key(&hex::decode(ED25519_SK).unwrap(), Curve::Ed25519);
+        let eth = &crate::hash::sha256(&keypair.public_key)[12..];
+        assert_eq!(keypair.address(), crate::address::eth_to_lattice(eth));
+        assert_eq!(keypair.address(), public_key_to_address(&keypair.public_key, Curve::Ed25519));
+    }
+}
@@ -1,19 +1,20 @@
 use std::ops::Shl;
+use std::sync::atomic::{AtomicBool, Ordering};
 
 use num_bigint::BigUint;
-use rlp::RlpStream;
-use serde::{Deserialize, Serialize, Serializer};
+use rlp::{Rlp, RlpStream};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
-use model::{Curve, HexString};
+use model::{Curve, Error, HexString};
 use model::common::Address;
-use model::constants::{ZERO_HASH_STRING, ZERO_ZLTC_ADDRESS};
+use model::constants::{zero_hash, PREFIX_OF_HEX, SECP256K1_SIGNATURE_LENGTH, SM2P256V1_SIGNATURE_LENGTH, ZERO_ZLTC_ADDRESS};
 use model::convert::{number_to_vec, option_number_to_vec};
 
 use crate::hash::hash_message;
 use crate::sign::KeyPair;
 
 /// 交易
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Transaction {
     #[serde(rename = "number")]
     pub height: u64,
@@ -31,6 +32,7 @@ pub struct Transaction {
     pub linker: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub code: Option<String>,
+    /// 代码哈希，已设置时编码直接使用，为None时由`code`计算
     #[serde(rename = "codeHash")]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub code_hash: Option<String>,
@@ -41,9 +43,12 @@ pub struct Transaction {
     pub sign: String,
     pub proof_of_work: String,
     pub version: TxVersion,
+    /// 最近一次签名所用的code_hash，只用于发送给节点的`RawTransaction`，不参与编码
+    #[serde(skip)]
+    signed_code_hash: Option<String>,
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
 pub enum TxType {
     Genesis,
     Create,
@@ -55,15 +60,43 @@ pub enum TxType {
 }
 
 impl TxType {
-    fn to_vec(&self) -> Vec<u8> {
+    /// # 获取交易类型对应的字节值
+    ///
+    /// ## 出参
+    /// + `u8`: 示例：send => 0x02
+    pub fn as_byte(&self) -> u8 {
         match &self {
-            TxType::Genesis => vec![0x00],
-            TxType::Create => vec![0x01],
-            TxType::Send => vec![0x02],
-            TxType::Receive => vec![0x03],
-            TxType::Contract => vec![0x04],
-            TxType::Execute => vec![0x05],
-            TxType::Update => vec![0x06],
+            TxType::Genesis => 0x00,
+            TxType::Create => 0x01,
+            TxType::Send => 0x02,
+            TxType::Receive => 0x03,
+            TxType::Contract => 0x04,
+            TxType::Execute => 0x05,
+            TxType::Update => 0x06,
+        }
+    }
+
+    fn to_vec(self) -> Vec<u8> {
+        vec![self.as_byte()]
+    }
+
+    /// # 根据交易类型的名称获取交易类型
+    ///
+    /// ## 入参
+    /// + `name: &str`: 交易类型的名称，示例：send
+    ///
+    /// ## 出参
+    /// + `Option<TxType>`
+    pub fn from_name(name: &str) -> Option<TxType> {
+        match name {
+            "genesis" => Some(TxType::Genesis),
+            "create" => Some(TxType::Create),
+            "send" => Some(TxType::Send),
+            "receive" => Some(TxType::Receive),
+            "contract" => Some(TxType::Contract),
+            "execute" => Some(TxType::Execute),
+            "update" => Some(TxType::Update),
+            _ => None,
         }
     }
 
@@ -98,7 +131,17 @@ impl Serialize for TxType {
     }
 }
 
-#[derive(Deserialize, Debug)]
+impl<'de> Deserialize<'de> for TxType {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let name = String::deserialize(deserializer)?;
+        TxType::from_name(&name).ok_or_else(|| serde::de::Error::custom(format!("unknown transaction type {}", name)))
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
 pub enum TxVersion {
     /// 混沌-0
     Chaos,
@@ -121,6 +164,25 @@ impl TxVersion {
     }
 }
 
+impl TxVersion {
+    /// # 根据序号获取交易版本
+    ///
+    /// ## 入参
+    /// + `ordinal: u16`: 序号
+    ///
+    /// ## 出参
+    /// + `Option<TxVersion>`
+    pub fn from_ordinal(ordinal: u16) -> Option<TxVersion> {
+        match ordinal {
+            0 => Some(Self::Chaos),
+            1 => Some(Self::PanGu),
+            2 => Some(Self::NuWa),
+            3 => Some(Self::Latest),
+            _ => None,
+        }
+    }
+}
+
 impl Serialize for TxVersion {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error> where S: Serializer {
         let ordinal = self.ordinal();
@@ -128,9 +190,27 @@ impl Serialize for TxVersion {
     }
 }
 
+impl<'de> Deserialize<'de> for TxVersion {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let ordinal = u16::deserialize(deserializer)?;
+        TxVersion::from_ordinal(ordinal).ok_or_else(|| serde::de::Error::custom(format!("unknown transaction version {}", ordinal)))
+    }
+}
+
 const DIFFICULTY_BYTE_ARRAY: Vec<u8> = vec![];
 const POW_BYTE_ARRAY: Vec<u8> = vec![];
-const DIFFICULTY: usize = 12;
+/// 计算pow的默认难度，哈希值不大于`2^(256-difficulty)`时满足条件
+pub const DEFAULT_POW_DIFFICULTY: usize = 12;
+/// 计算pow时检查取消标志的间隔(nonce个数)
+const POW_CANCEL_CHECK_INTERVAL: usize = 256;
+/// 带签名的RLP编码中各个元素的名称，与`rlp_encode`的追加顺序一致
+const RLP_FIELD_LABELS: [&str; 16] = [
+    "height", "type", "parent_hash", "hub", "daemon_hash", "code_hash", "owner", "linker",
+    "amount", "joule", "difficulty", "pow", "payload", "timestamp", "chain_id", "signature",
+];
 
 impl Transaction {
     /// # 创建空交易
@@ -157,6 +237,7 @@ impl Transaction {
             sign: String::new(),
             proof_of_work: String::new(),
             version: TxVersion::Latest,
+            signed_code_hash: None,
         }
     }
 
@@ -170,7 +251,7 @@ impl Transaction {
     ///
     /// ## 出参
     /// + `Vec<u8>`
-    fn rlp_encode(&mut self, chain_id: u64, pow: String, curve: Curve, use_pow: bool, is_sign: bool) -> Vec<u8> {
+    fn rlp_encode(&self, chain_id: u64, pow: String, curve: Curve, use_pow: bool, is_sign: bool) -> Vec<u8> {
         let mut rlp = RlpStream::new();
         rlp.begin_list(15 + if is_sign { 2 } else { 0 });
 
@@ -190,15 +271,7 @@ impl Transaction {
             None => HexString::new(Address::new(ZERO_ZLTC_ADDRESS).to_zltc_address().as_str()).decode(),
             Some(v) => HexString::new(Address::new(v).to_ethereum_address().as_str()).decode()
         };
-        let code_hash = match &self.code {
-            None => ZERO_HASH_STRING[2..].to_string(),
-            Some(v) => {
-                let bytes = HexString::new(v).decode();
-                hash_message(&bytes, curve)
-            }
-        };
-        self.set_code_hash(format!("0x{}", code_hash)); // update transaction code_hash
-        let code_hash = HexString::new(code_hash.as_str()).decode();
+        let code_hash = HexString::new(&self.effective_code_hash(curve)).decode();
         let payload = match &self.payload {
             None => vec![],
             Some(v) => HexString::new(v).decode()
@@ -215,6 +288,7 @@ impl Transaction {
         rlp.append(&option_number_to_vec(self.amount));
         rlp.append(&option_number_to_vec(self.joule));
         if use_pow {
+            rlp.append(&DIFFICULTY_BYTE_ARRAY);
             rlp.append(&HexString::new(pow.as_str()).decode());
         } else {
             rlp.append(&DIFFICULTY_BYTE_ARRAY);
@@ -235,25 +309,52 @@ impl Transaction {
     /// ## 入参
     /// + `chain_id: u64`: 区块链id
     /// + `curve: Curve`: Secp256k or Sm2p256v1
+    /// + `difficulty: usize`: 难度，默认为`DEFAULT_POW_DIFFICULTY`
     ///
     /// ## 出参
     /// + `BigUint`: pow
-    #[allow(dead_code)]
-    fn pow(&mut self, chain_id: u64, curve: Curve) -> BigUint {
-        let mut i: u32 = 0;
-        let min: BigUint = BigUint::from(1u32).shl(256 - DIFFICULTY);
-
-        loop {
-            i = i + 1;
-            let pow = BigUint::from(i);
-            let rlp = self.rlp_encode(chain_id, hex::encode(&pow.to_bytes_be()), curve, true, false);
+    fn pow(&self, chain_id: u64, curve: Curve, difficulty: usize) -> BigUint {
+        self.pow_cancellable(chain_id, curve, difficulty, &AtomicBool::new(false), 1)
+            .expect("pow nonce space exhausted")
+    }
+
+    /// # 已计算的pow，`proof_of_work`为空或者为0时不使用pow，返回None
+    fn computed_pow(&self) -> Option<BigUint> {
+        let bytes = hex::decode(HexString::new(&self.proof_of_work).clean_hex_string()).ok()?;
+        let pow = BigUint::from_bytes_be(&bytes);
+        (pow != BigUint::from(0u8)).then_some(pow)
+    }
+
+    /// # 计算pow，可取消，也可以从指定的nonce继续计算
+    ///
+    /// 每计算`POW_CANCEL_CHECK_INTERVAL`个nonce检查一次取消标志
+    ///
+    /// ## 入参
+    /// + `chain_id: u64`: 区块链id
+    /// + `curve: Curve`: Secp256k or Sm2p256v1
+    /// + `difficulty: usize`: 难度，哈希值不大于`2^(256-difficulty)`时满足条件
+    /// + `cancel: &AtomicBool`: 取消标志，置为`true`后尽快返回
+    /// + `start_nonce: u64`: 起始的nonce(包含)，用于从上次中断的位置继续
+    ///
+    /// ## 出参
+    /// + `Option<BigUint>`: pow，被取消或nonce耗尽时返回`None`
+    pub fn pow_cancellable(&self, chain_id: u64, curve: Curve, difficulty: usize, cancel: &AtomicBool, start_nonce: u64) -> Option<BigUint> {
+        let min: BigUint = BigUint::from(1u32).shl(256 - difficulty.min(256));
+
+        for (i, nonce) in (start_nonce..=u64::MAX).enumerate() {
+            if i % POW_CANCEL_CHECK_INTERVAL == 0 && cancel.load(Ordering::Relaxed) {
+                return None;
+            }
+            let pow = BigUint::from(nonce);
+            let rlp = self.rlp_encode(chain_id, hex::encode(pow.to_bytes_be()), curve, true, false);
             let hash = hash_message(&rlp, curve);
             let bytes = HexString::new(hash.as_str()).decode();
             let calculated = BigUint::from_bytes_be(&bytes);
             if calculated.le(&min) {
-                return pow;
+                return Some(pow);
             }
         }
+        None
     }
 
     /// # encode
@@ -264,10 +365,12 @@ impl Transaction {
     /// ## 出参
     /// + `BigUint`
     /// + `Vec<u8>`
-    fn encode(&mut self, chain_id: u64, curve: Curve) -> (BigUint, Vec<u8>) {
-        // let pow = self.pow(chain_id, curve);
-        let pow = BigUint::from_bytes_be(HexString::new("0x00").decode().as_slice());
-        let code = self.rlp_encode(chain_id, hex::encode(&pow.to_bytes_be()), curve, false, true);
+    fn encode(&self, chain_id: u64, curve: Curve) -> (BigUint, Vec<u8>) {
+        let (pow, use_pow) = match self.computed_pow() {
+            Some(pow) => (pow, true),
+            None => (BigUint::from(0u8), false),
+        };
+        let code = self.rlp_encode(chain_id, hex::encode(pow.to_bytes_be()), curve, use_pow, true);
         (pow, code)
     }
 
@@ -282,9 +385,21 @@ impl Transaction {
     /// + `String`: signature
     pub fn sign(&mut self, chain_id: u64, sk: &[u8], curve: Curve) -> (BigUint, String) {
         let key_pair = KeyPair::from_secret_key(sk, curve);
+        self.sign_with_key_pair(chain_id, &key_pair)
+    }
 
-        let (pow, encoded) = self.encode(chain_id, curve);
-        let hash = hash_message(&encoded, curve);
+    /// # 使用已派生的密钥对签名交易，批量签名时避免每笔交易都从私钥重新派生密钥对
+    /// ## 入参
+    /// + `chain_id: u64`: 区块链id
+    /// + `key_pair: &KeyPair`: 密钥对，签名使用其曲线
+    ///
+    /// ## 出参
+    /// + `BigUint`: pow
+    /// + `String`: signature
+    pub fn sign_with_key_pair(&mut self, chain_id: u64, key_pair: &KeyPair) -> (BigUint, String) {
+        self.record_code_hash(key_pair.curve);
+        let (pow, encoded) = self.encode(chain_id, key_pair.curve);
+        let hash = hash_message(&encoded, key_pair.curve);
         let data = HexString::new(hash.as_str()).decode();
         let signature = key_pair.sign(&data);
         self.sign = signature;
@@ -292,6 +407,163 @@ impl Transaction {
         (pow, self.sign.to_string())
     }
 
+    /// # 计算pow并写入`proof_of_work`后签名交易，用于要求pow的链
+    /// ## 入参
+    /// + `chain_id: u64`: 区块链id
+    /// + `key_pair: &KeyPair`: 密钥对，签名使用其曲线
+    /// + `difficulty: usize`: pow的难度，默认为`DEFAULT_POW_DIFFICULTY`
+    ///
+    /// ## 出参
+    /// + `BigUint`: pow
+    /// + `String`: signature
+    pub fn sign_with_pow(&mut self, chain_id: u64, key_pair: &KeyPair, difficulty: usize) -> (BigUint, String) {
+        let pow = self.pow(chain_id, key_pair.curve, difficulty);
+        self.proof_of_work = format!("{}{}", PREFIX_OF_HEX, hex::encode(pow.to_bytes_be()));
+        self.sign_with_key_pair(chain_id, key_pair)
+    }
+
+    /// # 获取交易待签名的摘要，用于在硬件钱包等外部设备中签名
+    /// ## 入参
+    /// + `chain_id: u64`: 区块链id
+    /// + `curve: Curve`: Secp256k or Sm2p256v1
+    ///
+    /// ## 出参
+    /// + `[u8; 32]`: 交易摘要
+    pub fn signing_hash(&mut self, chain_id: u64, curve: Curve) -> [u8; 32] {
+        self.record_code_hash(curve);
+        let (_, encoded) = self.encode(chain_id, curve);
+        let hash = hash_message(&encoded, curve);
+        let mut digest = [0u8; 32];
+        digest.copy_from_slice(HexString::new(hash.as_str()).decode().as_slice());
+        digest
+    }
+
+    /// # 交易的RLP编码，即签名时哈希的字节或发送给节点的字节，不修改交易本身
+    ///
+    /// 字段依次为：`height`、`type`、`parentHash`、`hub`、`daemonHash`、`codeHash`、`owner`、`linker`、`amount`、`joule`、
+    /// `difficulty`、`pow`、`payload`、`timestamp`、`chainId`。`owner`和`linker`为以太坊格式的地址，`codeHash`未设置时由`code`计算。
+    /// 待签名的编码在末尾追加两个空字段，带签名的编码在末尾追加签名
+    ///
+    /// ## 入参
+    /// + `chain_id: u64`: 区块链id
+    /// + `curve: Curve`: Secp256k or Sm2p256v1
+    /// + `with_signature: bool`: false时为待签名的编码，其哈希即`signing_hash`；true时为带上`sign`的编码
+    ///
+    /// ## 出参
+    /// + `Vec<u8>`
+    pub fn encode_for_signing(&self, chain_id: u64, curve: Curve, with_signature: bool) -> Vec<u8> {
+        if with_signature {
+            let signature = HexString::new(&self.sign).decode();
+            self.rlp_encode_with_signature(chain_id, curve, &signature)
+        } else {
+            self.encode(chain_id, curve).1
+        }
+    }
+
+    /// # 交易哈希，即节点返回的交易哈希，为带签名的RLP编码按曲线的哈希算法计算的哈希，需要在签名后调用
+    /// ## 入参
+    /// + `chain_id: u64`: 区块链id
+    /// + `curve: Curve`: Secp256k or Sm2p256v1
+    ///
+    /// ## 出参
+    /// + `String`: 0x开头的交易哈希
+    pub fn hash(&self, chain_id: u64, curve: Curve) -> String {
+        let signature = HexString::new(&self.sign).decode();
+        let encoded = self.rlp_encode_with_signature(chain_id, curve, &signature);
+        format!("{}{}", PREFIX_OF_HEX, hash_message(&encoded, curve))
+    }
+
+    /// # 设置外部对交易摘要的签名
+    /// ## 入参
+    /// + `signature: String`: 对`signing_hash`的签名
+    pub fn attach_signature(&mut self, signature: String) {
+        self.sign = signature;
+    }
+
+    /// # 校验交易类型要求的字段，避免发送后被节点以难以理解的原因拒绝
+    ///
+    /// + Send: 不能包含`code`，`payload`可选
+    /// + Contract: 必须包含`code`
+    /// + Execute: 必须包含`code`和`linker`
+    ///
+    /// ## 出参
+    /// + `Result<(), Error>`: 字段不满足交易类型的要求时返回`Err`
+    pub fn validate_for_type(&self) -> Result<(), Error> {
+        let has_code = self.code.as_ref().is_some_and(|code| !HexString::new(code).clean_hex_string().is_empty());
+        let has_linker = self.linker.as_ref().is_some_and(|linker| !linker.is_empty());
+        match self.tx_type {
+            TxType::Send if has_code => Err(Error::new("send transaction must not carry code")),
+            TxType::Contract if !has_code => Err(Error::new("contract transaction requires code")),
+            TxType::Execute if !has_code => Err(Error::new("execute transaction requires code")),
+            TxType::Execute if !has_linker => Err(Error::new("execute transaction requires a contract address as linker")),
+            _ => Ok(()),
+        }
+    }
+
+    /// # 估算交易签名后的RLP编码的字节长度，使用长度正确的空签名代替真实签名，不会执行签名
+    /// ## 入参
+    /// + `chain_id: u64`: 区块链id
+    /// + `curve: Curve`: Secp256k or Sm2p256v1
+    ///
+    /// ## 出参
+    /// + `usize`: 字节长度
+    pub fn estimated_size(&self, chain_id: u64, curve: Curve) -> usize {
+        let signature_length = match curve {
+            Curve::Secp256k1 => SECP256K1_SIGNATURE_LENGTH,
+            Curve::Sm2p256v1 => SM2P256V1_SIGNATURE_LENGTH,
+            #[cfg(feature = "ed25519")]
+            Curve::Ed25519 => model::constants::ED25519_SIGNATURE_LENGTH,
+        };
+        self.rlp_encode_with_signature(chain_id, curve, &vec![0u8; signature_length]).len()
+    }
+
+    /// # 带签名的RLP编码
+    /// ## 入参
+    /// + `chain_id: u64`: 区块链id
+    /// + `curve: Curve`: Secp256k or Sm2p256v1
+    /// + `signature: &[u8]`: 签名
+    ///
+    /// ## 出参
+    /// + `Vec<u8>`
+    fn rlp_encode_with_signature(&self, chain_id: u64, curve: Curve, signature: &[u8]) -> Vec<u8> {
+        let pow = self.computed_pow().map(|pow| hex::encode(pow.to_bytes_be()));
+        let use_pow = pow.is_some();
+        let encoded = self.rlp_encode(chain_id, pow.unwrap_or_default(), curve, use_pow, false);
+        let fields = Rlp::new(&encoded);
+        let mut rlp = RlpStream::new_list(fields.item_count().unwrap_or(0) + 1);
+        for field in fields.iter() {
+            rlp.append_raw(field.as_raw(), 1);
+        }
+        rlp.append(&signature);
+        rlp.out().to_vec()
+    }
+
+    /// # 按照字段逐个列出带签名的RLP编码中的元素，包括十六进制的值和字节长度，用于排查节点拒绝交易或签名不一致的问题
+    /// ## 入参
+    /// + `chain_id: u64`: 区块链id
+    /// + `curve: Curve`: Secp256k or Sm2p256v1
+    ///
+    /// ## 出参
+    /// + `String`: 每行一个元素，示例：`height       (1 bytes) 0x01`
+    pub fn debug_rlp(&self, chain_id: u64, curve: Curve) -> String {
+        let signature = hex::decode(HexString::new(&self.sign).clean_hex_string()).unwrap_or_default();
+        let encoded = self.rlp_encode_with_signature(chain_id, curve, &signature);
+        let mut dump = String::new();
+        for (label, field) in RLP_FIELD_LABELS.iter().zip(Rlp::new(&encoded).iter()) {
+            if field.is_list() {
+                dump.push_str(&format!("{:<12} list[{}]\n", label, field.item_count().unwrap_or(0)));
+                for (i, item) in field.iter().enumerate() {
+                    let data = item.data().unwrap_or_default();
+                    dump.push_str(&format!("  [{}] ({} bytes) 0x{}\n", i, data.len(), hex::encode(data)));
+                }
+            } else {
+                let data = field.data().unwrap_or_default();
+                dump.push_str(&format!("{:<12} ({} bytes) 0x{}\n", label, data.len(), hex::encode(data)));
+            }
+        }
+        dump
+    }
+
     pub fn to_raw_tx(self) -> RawTransaction {
         RawTransaction {
             height: self.height,
@@ -303,8 +575,8 @@ impl Transaction {
             ty: self.tx_type.name(),
             hub: self.hub.unwrap_or(vec![]),
             code: self.code.unwrap_or(String::new()),
-            code_hash: self.code_hash,
-            payload: self.payload.unwrap_or(String::from("0x")),
+            code_hash: self.code_hash.or(self.signed_code_hash),
+            payload: self.payload.unwrap_or(String::from(PREFIX_OF_HEX)),
             amount: self.amount.unwrap_or(0),
             joule: self.joule.unwrap_or(0),
             sign: self.sign,
@@ -317,9 +589,25 @@ impl Transaction {
     pub fn set_code_hash(&mut self, code_hash: String) {
         self.code_hash = Some(code_hash.to_string())
     }
+
+    /// # 编码使用的code_hash，0x开头
+    ///
+    /// 已设置的`code_hash`优先，例如反序列化的交易或外部签名者确定的哈希，未设置时由`code`按曲线的哈希算法计算，没有`code`时为零哈希
+    fn effective_code_hash(&self, curve: Curve) -> String {
+        match (&self.code_hash, &self.code) {
+            (Some(v), _) => format!("{}{}", PREFIX_OF_HEX, HexString::new(v).clean_hex_string()),
+            (None, None) => format!("{}{}", PREFIX_OF_HEX, zero_hash().clean_hex_string()),
+            (None, Some(v)) => format!("{}{}", PREFIX_OF_HEX, hash_message(&HexString::new(v).decode(), curve)),
+        }
+    }
+
+    /// # 签名时记录签名所用的code_hash，使发送给节点的交易携带与签名一致的哈希，不影响之后的编码
+    fn record_code_hash(&mut self, curve: Curve) {
+        self.signed_code_hash = Some(self.effective_code_hash(curve));
+    }
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct RawTransaction {
     #[serde(rename = "number")]
     pub height: u64,
@@ -347,8 +635,268 @@ pub struct RawTransaction {
     pub difficulty: u32,
 }
 
+impl TryFrom<RawTransaction> for Transaction {
+    type Error = Error;
+
+    /// # 将节点返回的交易转为交易
+    fn try_from(raw: RawTransaction) -> Result<Self, Self::Error> {
+        let tx_type = TxType::from_name(&raw.ty)
+            .ok_or_else(|| Error::new(&format!("unknown transaction type {}", raw.ty)))?;
+        let version = TxVersion::from_ordinal(raw.version)
+            .ok_or_else(|| Error::new(&format!("unknown transaction version {}", raw.version)))?;
+        Ok(Transaction {
+            height: raw.height,
+            parent_hash: raw.parent_hash,
+            daemon_hash: raw.daemon_hash,
+            payload: Some(raw.payload),
+            hub: if raw.hub.is_empty() { None } else { Some(raw.hub) },
+            timestamp: raw.timestamp,
+            tx_type,
+            owner: raw.owner,
+            linker: Some(raw.linker),
+            code: if raw.code.is_empty() { None } else { Some(raw.code) },
+            code_hash: raw.code_hash,
+            amount: if raw.amount == 0 { None } else { Some(raw.amount) },
+            joule: if raw.joule == 0 { None } else { Some(raw.joule) },
+            sign: raw.sign,
+            proof_of_work: raw.proof_of_work,
+            version,
+            signed_code_hash: None,
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    use model::constants::ZERO_HASH_STRING;
+
+    use super::*;
+
+    const SK: &str = "0x23d5b2a2eb0a9c8b86d62cbc3955cfd1fb26ec576ecc379f402d0f5d2b27a7bb";
+
+    fn new_tx() -> Transaction {
+        let mut tx = Transaction::empty_tx();
+        tx.height = 1;
+        tx.tx_type = TxType::Send;
+        tx.parent_hash = ZERO_HASH_STRING.to_string();
+        tx.daemon_hash = ZERO_HASH_STRING.to_string();
+        tx.owner = String::from("zltc_Z1pnS94bP4hQSYLs4aP4UwBP9pH8bEvhi");
+        tx.linker = Some(ZERO_ZLTC_ADDRESS.to_string());
+        tx.payload = Some(String::from("0x0102"));
+        tx.timestamp = 1719804632;
+        tx
+    }
+
     #[test]
     fn test_sign_tx() {}
+
+    #[test]
+    fn test_debug_rlp() {
+        let mut tx = new_tx();
+        tx.hub = Some(vec![ZERO_HASH_STRING.to_string()]);
+        tx.sign_with_key_pair(1, &KeyPair::from_secret_key(&HexString::new(SK).decode(), Curve::Sm2p256v1));
+        let dump = tx.debug_rlp(1, Curve::Sm2p256v1);
+        for label in RLP_FIELD_LABELS {
+            assert!(dump.lines().any(|line| line.starts_with(label)), "missing {}", label);
+        }
+        assert!(dump.contains("height       (1 bytes) 0x01"));
+        assert!(dump.contains("hub          list[1]"));
+        assert!(dump.contains("payload      (2 bytes) 0x0102"));
+        assert!(dump.contains(&format!("signature    ({} bytes) 0x", HexString::new(&tx.sign).decode().len())));
+    }
+
+    #[test]
+    fn test_pow_cancellable() {
+        let tx = new_tx();
+        let cancel = AtomicBool::new(false);
+        let pow = tx.pow_cancellable(1, Curve::Sm2p256v1, 4, &cancel, 1).unwrap();
+        let nonce = u64::try_from(&pow).unwrap();
+        assert_eq!(Some(pow.clone()), tx.pow_cancellable(1, Curve::Sm2p256v1, 4, &cancel, nonce));
+        assert!(tx.pow_cancellable(1, Curve::Sm2p256v1, 4, &cancel, nonce + 1).unwrap() > pow);
+
+        cancel.store(true, Ordering::Relaxed);
+        assert_eq!(None, tx.pow_cancellable(1, Curve::Sm2p256v1, 4, &cancel, 1));
+    }
+
+    #[test]
+    fn test_hash() {
+        let mut tx = new_tx();
+        tx.sign_with_key_pair(1, &KeyPair::from_secret_key(&HexString::new(SK).decode(), Curve::Secp256k1));
+        let hash = tx.hash(1, Curve::Secp256k1);
+        assert_eq!(hash, "0xd6d4a2dd999bcac08d1da723320f0d9177bdaf56970cb1b93d12138c143f86bf");
+        assert_eq!(hash, tx.hash(1, Curve::Secp256k1));
+        let encoded = tx.encode_for_signing(1, Curve::Secp256k1, true);
+        assert_eq!(hash, format!("0x{}", hash_message(&encoded, Curve::Secp256k1)));
+        assert_ne!(hash, tx.hash(2, Curve::Secp256k1));
+    }
+
+    #[test]
+    fn test_sign_with_pow() {
+        let key_pair = KeyPair::from_secret_key(&HexString::new(SK).decode(), Curve::Sm2p256v1);
+        let mut tx = new_tx();
+        let unsigned = tx.clone().encode_for_signing(1, Curve::Sm2p256v1, false);
+        let (pow, signature) = tx.sign_with_pow(1, &key_pair, 8);
+        assert_eq!(tx.proof_of_work, format!("0x{}", hex::encode(pow.to_bytes_be())));
+
+        // pow满足难度，且写入了待签名的编码
+        let rlp = tx.clone().rlp_encode(1, hex::encode(pow.to_bytes_be()), Curve::Sm2p256v1, true, false);
+        let hash = BigUint::from_bytes_be(&HexString::new(&hash_message(&rlp, Curve::Sm2p256v1)).decode());
+        assert!(hash <= BigUint::from(1u32).shl(256 - 8));
+        assert_eq!(Rlp::new(&tx.encode_for_signing(1, Curve::Sm2p256v1, true)).at(11).unwrap().data().unwrap(), pow.to_bytes_be());
+        assert_ne!(unsigned, tx.encode_for_signing(1, Curve::Sm2p256v1, false));
+        assert!(key_pair.verify(&tx.signing_hash(1, Curve::Sm2p256v1), &signature));
+
+        // 没有pow时编码不变
+        let mut plain = new_tx();
+        plain.proof_of_work = String::from("0x00");
+        assert_eq!(unsigned, plain.encode_for_signing(1, Curve::Sm2p256v1, false));
+    }
+
+    #[test]
+    fn test_pow_cancel_while_running() {
+        let cancel = AtomicBool::new(false);
+        let started = std::time::Instant::now();
+        let result = std::thread::scope(|s| {
+            // 难度为256时几乎不可能找到解，只能被取消
+            let handle = s.spawn(|| new_tx().pow_cancellable(1, Curve::Sm2p256v1, 256, &cancel, 1));
+            std::thread::sleep(std::time::Duration::from_millis(50));
+            cancel.store(true, Ordering::Relaxed);
+            handle.join().unwrap()
+        });
+        assert_eq!(None, result);
+        assert!(started.elapsed() < std::time::Duration::from_secs(5));
+    }
+
+    #[test]
+    fn test_tx_type_eq() {
+        let tx_type: TxType = serde_json::from_str("\"send\"").unwrap();
+        assert!(tx_type == TxType::Send);
+        assert_ne!(tx_type, TxType::Receive);
+        assert_eq!(tx_type.as_byte(), 0x02);
+        assert_eq!(TxType::from_name(&tx_type.name()), Some(tx_type));
+        assert_eq!(TxVersion::from_ordinal(3), Some(TxVersion::Latest));
+    }
+
+    #[test]
+    fn test_tx_type_and_version_serde() {
+        for tx_type in [TxType::Genesis, TxType::Create, TxType::Send, TxType::Receive, TxType::Contract, TxType::Execute, TxType::Update] {
+            let json = serde_json::to_string(&tx_type).unwrap();
+            assert_eq!(serde_json::from_str::<TxType>(&json).unwrap(), tx_type);
+        }
+        assert!(serde_json::from_str::<TxType>("\"Send\"").is_err());
+
+        assert_eq!(serde_json::from_str::<TxVersion>("2").unwrap(), TxVersion::NuWa);
+        assert_eq!(serde_json::to_string(&TxVersion::NuWa).unwrap(), "2");
+        assert!(serde_json::from_str::<TxVersion>("4").is_err());
+    }
+
+    #[test]
+    fn test_estimated_size() {
+        for curve in [Curve::Sm2p256v1, Curve::Secp256k1] {
+            let unsigned = new_tx();
+            let estimated = unsigned.estimated_size(1, curve);
+            let mut signed = unsigned.clone();
+            signed.sign(1, &HexString::new(SK).decode(), curve);
+            assert_eq!(estimated, signed.encode_for_signing(1, curve, true).len());
+        }
+    }
+
+    #[test]
+    fn test_encode_for_signing() {
+        for curve in [Curve::Sm2p256v1, Curve::Secp256k1] {
+            let mut tx = new_tx();
+            let encoded = tx.encode_for_signing(1, curve, false);
+            assert_eq!(encoded, tx.encode(1, curve).1);
+            assert_eq!(HexString::new(&hash_message(&encoded, curve)).decode(), tx.signing_hash(1, curve));
+
+            tx.sign(1, &HexString::new(SK).decode(), curve);
+            let signature = HexString::new(&tx.sign.clone()).decode();
+            let encoded = tx.encode_for_signing(1, curve, true);
+            assert_eq!(encoded, tx.rlp_encode_with_signature(1, curve, &signature));
+            assert_eq!(Rlp::new(&encoded).item_count().unwrap(), 16);
+            assert_eq!(Rlp::new(&encoded).at(15).unwrap().data().unwrap(), signature.as_slice());
+        }
+    }
+
+    #[test]
+    fn test_preset_code_hash_preserved() {
+        let code_hash = "0x1111111111111111111111111111111111111111111111111111111111111111";
+        for curve in [Curve::Sm2p256v1, Curve::Secp256k1] {
+            let mut computed = new_tx();
+            computed.code = Some(String::from("0x0102"));
+            let mut preset = new_tx();
+            preset.code = Some(String::from("0x0102"));
+            preset.set_code_hash(code_hash.to_string());
+
+            let computed_hash = format!("0x{}", hash_message(&[0x01, 0x02], curve));
+            assert_ne!(computed.signing_hash(1, curve), preset.signing_hash(1, curve));
+            assert_eq!(computed.code_hash, None);
+            assert_eq!(preset.code_hash.as_deref(), Some(code_hash));
+
+            // 计算出的哈希与预先设置相同的哈希时，编码一致
+            let mut same = new_tx();
+            same.code = Some(String::from("0x0102"));
+            same.code_hash = Some(computed_hash.clone());
+            assert_eq!(computed.signing_hash(1, curve), same.signing_hash(1, curve));
+            assert_eq!(computed.to_raw_tx().code_hash, Some(computed_hash));
+            assert_eq!(preset.to_raw_tx().code_hash.as_deref(), Some(code_hash));
+        }
+    }
+
+    #[test]
+    fn test_code_hash_follows_code_and_curve() {
+        let sk = HexString::new(SK).decode();
+        let mut tx = new_tx();
+        tx.tx_type = TxType::Contract;
+        tx.code = Some(String::from("0x0102"));
+        tx.sign(1, &sk, Curve::Sm2p256v1);
+
+        // 签名后修改代码或曲线，重新签名使用新的代码哈希
+        tx.code = Some(String::from("0x0304"));
+        tx.sign(1, &sk, Curve::Secp256k1);
+        let code_hash = format!("0x{}", hash_message(&[0x03, 0x04], Curve::Secp256k1));
+        let mut expected = new_tx();
+        expected.tx_type = TxType::Contract;
+        expected.code = Some(String::from("0x0304"));
+        expected.set_code_hash(code_hash.clone());
+        assert_eq!(tx.signing_hash(1, Curve::Secp256k1), expected.signing_hash(1, Curve::Secp256k1));
+        assert_eq!(tx.to_raw_tx().code_hash, Some(code_hash));
+    }
+
+    #[test]
+    fn test_validate_for_type() {
+        let mut tx = new_tx();
+        assert!(tx.validate_for_type().is_ok());
+        tx.payload = None;
+        assert!(tx.validate_for_type().is_ok());
+        tx.code = Some(String::from("0x6080"));
+        assert!(tx.validate_for_type().is_err());
+
+        tx.tx_type = TxType::Contract;
+        assert!(tx.validate_for_type().is_ok());
+        tx.code = Some(String::from("0x"));
+        assert!(tx.validate_for_type().is_err());
+
+        tx.tx_type = TxType::Execute;
+        tx.code = Some(String::from("0x5b34b966"));
+        assert!(tx.validate_for_type().is_ok());
+        tx.linker = None;
+        assert!(tx.validate_for_type().is_err());
+        tx.code = None;
+        assert!(tx.validate_for_type().is_err());
+
+        tx.tx_type = TxType::Receive;
+        assert!(tx.validate_for_type().is_ok());
+    }
+
+    #[test]
+    fn test_attach_external_signature() {
+        for curve in [Curve::Sm2p256v1, Curve::Secp256k1] {
+            let key_pair = KeyPair::from_secret_key(&HexString::new(SK).decode(), curve);
+            let mut tx = new_tx();
+            let digest = tx.signing_hash(1, curve);
+            tx.attach_signature(key_pair.sign(&digest));
+            assert!(key_pair.verify(&tx.signing_hash(1, curve), &tx.sign));
+        }
+    }
 }
\ No newline at end of file
@@ -0,0 +1,360 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use num_bigint::{BigInt, BigUint, Sign};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use model::common::Address;
+use model::constants::PREFIX_OF_HEX;
+use model::{Curve, Error, HexString};
+
+use crate::hash::{sha256, sm3};
+use crate::sign::{verify_with_public_key, KeyPair};
+
+/// 域类型的名称
+const DOMAIN_TYPE: &str = "EIP712Domain";
+
+/// 结构化数据的域，用于区分不同的dApp、版本和链，未设置的字段不参与编码
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct TypedDataDomain {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub chain_id: Option<u64>,
+    /// 合约地址，可以是zltc地址或以太坊地址
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub verifying_contract: Option<String>,
+    /// 32字节的hex字符串
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub salt: Option<String>,
+}
+
+impl TypedDataDomain {
+    /// # 域的类型定义，只包含已设置的字段
+    fn fields(&self) -> Vec<TypedDataField> {
+        [
+            (self.name.is_some(), "name", "string"),
+            (self.version.is_some(), "version", "string"),
+            (self.chain_id.is_some(), "chainId", "uint256"),
+            (self.verifying_contract.is_some(), "verifyingContract", "address"),
+            (self.salt.is_some(), "salt", "bytes32"),
+        ]
+            .into_iter()
+            .filter(|(present, _, _)| *present)
+            .map(|(_, name, ty)| TypedDataField { name: name.to_string(), ty: ty.to_string() })
+            .collect()
+    }
+}
+
+/// 结构体的字段定义，示例：`{"name": "from", "type": "Person"}`
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct TypedDataField {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub ty: String,
+}
+
+/// 结构体的类型定义，key为结构体名，与EIP-712的`types`格式一致，不需要包含`EIP712Domain`
+pub type TypedDataTypes = BTreeMap<String, Vec<TypedDataField>>;
+
+/// # 计算结构化数据的待签名摘要：`hash(0x1901 ‖ domainSeparator ‖ hashStruct(message))`
+///
+/// 编码规则与EIP-712一致，哈希算法使用曲线对应的哈希，secp256k1为sha256，sm2p256v1为sm3
+///
+/// ## 入参
+/// + `domain: &TypedDataDomain`: 域
+/// + `types: &TypedDataTypes`: 结构体的类型定义，主类型为没有被其它类型引用的类型
+/// + `message: &Value`: 主类型的数据
+/// + `curve: Curve`: 椭圆曲线
+///
+/// ## 出参
+/// + `Result<[u8; 32], Error>`: 类型定义或数据不合法时返回错误
+pub fn typed_data_hash(domain: &TypedDataDomain, types: &TypedDataTypes, message: &Value, curve: Curve) -> Result<[u8; 32], Error> {
+    let primary_type = primary_type(types)?;
+    let domain_types = BTreeMap::from([(DOMAIN_TYPE.to_string(), domain.fields())]);
+    let domain_separator = hash_struct(DOMAIN_TYPE, &json!(domain), &domain_types, curve)?;
+    let struct_hash = hash_struct(&primary_type, message, types, curve)?;
+    Ok(hash(&[&[0x19, 0x01], &domain_separator[..], &struct_hash[..]].concat(), curve))
+}
+
+/// # 签名结构化数据
+///
+/// ## 入参
+/// + `domain: &TypedDataDomain`: 域
+/// + `types: &TypedDataTypes`: 结构体的类型定义
+/// + `message: &Value`: 主类型的数据
+/// + `sk: &[u8]`: 私钥
+/// + `curve: Curve`: 椭圆曲线
+///
+/// ## 出参
+/// + `Result<String, Error>`: 签名，类型定义、数据或私钥不合法时返回错误
+pub fn sign_typed_data(domain: &TypedDataDomain, types: &TypedDataTypes, message: &Value, sk: &[u8], curve: Curve) -> Result<String, Error> {
+    let digest = typed_data_hash(domain, types, message, curve)?;
+    Ok(KeyPair::try_from_secret_key(sk, curve)?.sign(&digest))
+}
+
+/// # 验证结构化数据的签名
+///
+/// ## 入参
+/// + `domain: &TypedDataDomain`: 域
+/// + `types: &TypedDataTypes`: 结构体的类型定义
+/// + `message: &Value`: 主类型的数据
+/// + `signature: &str`: `sign_typed_data`的签名
+/// + `public_key: &[u8]`: 签名者的公钥，格式与`KeyPair::public_key`相同
+/// + `curve: Curve`: 椭圆曲线
+///
+/// ## 出参
+/// + `Result<bool, Error>`: 类型定义、数据或公钥不合法时返回错误
+pub fn verify_typed_data(domain: &TypedDataDomain, types: &TypedDataTypes, message: &Value, signature: &str, public_key: &[u8], curve: Curve) -> Result<bool, Error> {
+    let digest = typed_data_hash(domain, types, message, curve)?;
+    verify_with_public_key(&digest, signature, public_key, curve)
+}
+
+/// # 曲线对应的哈希
+fn hash(data: &[u8], curve: Curve) -> [u8; 32] {
+    match curve {
+        Curve::Secp256k1 => sha256(data),
+        Curve::Sm2p256v1 => sm3(data),
+        #[cfg(feature = "ed25519")]
+        Curve::Ed25519 => sha256(data),
+    }
+}
+
+/// # 去掉数组后缀的类型名，例如`Person[][2]`为`Person`
+fn base_type(ty: &str) -> &str {
+    ty.split('[').next().unwrap_or(ty)
+}
+
+/// # 找出没有被其它类型引用的主类型
+fn primary_type(types: &TypedDataTypes) -> Result<String, Error> {
+    let referenced: BTreeSet<&str> = types.values()
+        .flatten()
+        .map(|field| base_type(&field.ty))
+        .collect();
+    let candidates: Vec<&String> = types.keys()
+        .filter(|name| name.as_str() != DOMAIN_TYPE && !referenced.contains(name.as_str()))
+        .collect();
+    match candidates.as_slice() {
+        [primary] => Ok(primary.to_string()),
+        _ => Err(Error::new(&format!("expected exactly one primary type, found {:?}", candidates))),
+    }
+}
+
+/// # 收集类型引用的所有结构体类型，包括自身
+fn dependencies<'a>(ty: &'a str, types: &'a TypedDataTypes, found: &mut BTreeSet<&'a str>) -> Result<(), Error> {
+    if found.contains(ty) {
+        return Ok(());
+    }
+    let fields = types.get(ty).ok_or_else(|| Error::new(&format!("type {} not found", ty)))?;
+    found.insert(ty);
+    for field in fields {
+        let field_type = base_type(&field.ty);
+        if types.contains_key(field_type) {
+            dependencies(field_type, types, found)?;
+        }
+    }
+    Ok(())
+}
+
+/// # 编码类型，主类型在前，其余引用的类型按名称排序，例如`Mail(Person from,Person to,string contents)Person(string name,address wallet)`
+pub fn encode_type(primary_type: &str, types: &TypedDataTypes) -> Result<String, Error> {
+    let mut found = BTreeSet::new();
+    dependencies(primary_type, types, &mut found)?;
+    found.remove(primary_type);
+    let encoded = std::iter::once(primary_type)
+        .chain(found)
+        .map(|ty| {
+            let fields: Vec<String> = types[ty].iter()
+                .map(|field| format!("{} {}", field.ty, field.name))
+                .collect();
+            format!("{}({})", ty, fields.join(","))
+        })
+        .collect();
+    Ok(encoded)
+}
+
+/// # 计算结构体的哈希：`hash(typeHash ‖ encodeData(data))`
+fn hash_struct(ty: &str, data: &Value, types: &TypedDataTypes, curve: Curve) -> Result<[u8; 32], Error> {
+    let type_hash = hash(encode_type(ty, types)?.as_bytes(), curve);
+    let mut encoded = type_hash.to_vec();
+    for field in &types[ty] {
+        let value = data.get(&field.name)
+            .ok_or_else(|| Error::new(&format!("field {}.{} is missing", ty, field.name)))?;
+        encoded.extend(encode_value(&field.ty, value, types, curve)?);
+    }
+    Ok(hash(&encoded, curve))
+}
+
+/// # 将字段的值编码为32字节
+fn encode_value(ty: &str, value: &Value, types: &TypedDataTypes, curve: Curve) -> Result<[u8; 32], Error> {
+    let invalid = || Error::new(&format!("invalid {} value {}", ty, value));
+    if let Some(index) = ty.rfind('[') {
+        let items = value.as_array().ok_or_else(invalid)?;
+        let mut encoded = Vec::with_capacity(items.len() * 32);
+        for item in items {
+            encoded.extend(encode_value(&ty[..index], item, types, curve)?);
+        }
+        return Ok(hash(&encoded, curve));
+    }
+    if types.contains_key(ty) {
+        return hash_struct(ty, value, types, curve);
+    }
+    match ty {
+        "string" => Ok(hash(value.as_str().ok_or_else(invalid)?.as_bytes(), curve)),
+        "bytes" => Ok(hash(&decode_hex(value).ok_or_else(invalid)?, curve)),
+        "bool" => Ok(left_pad(&[value.as_bool().ok_or_else(invalid)? as u8])),
+        "address" => {
+            let addr = value.as_str().ok_or_else(invalid)?;
+            let addr = match addr.starts_with(PREFIX_OF_HEX) {
+                true => addr.to_string(),
+                false => Address::new(addr).try_to_ethereum_address().map_err(|_| invalid())?,
+            };
+            let bytes = hex::decode(HexString::new(&addr).clean_hex_string()).map_err(|_| invalid())?;
+            if bytes.len() != 20 {
+                return Err(invalid());
+            }
+            Ok(left_pad(&bytes))
+        }
+        _ if ty.starts_with("bytes") => {
+            let size: usize = ty["bytes".len()..].parse().map_err(|_| invalid())?;
+            let bytes = decode_hex(value).ok_or_else(invalid)?;
+            if size == 0 || size > 32 || bytes.len() > size {
+                return Err(invalid());
+            }
+            let mut padded = [0u8; 32];
+            padded[..bytes.len()].copy_from_slice(&bytes);
+            Ok(padded)
+        }
+        _ if ty.starts_with("uint") || ty.starts_with("int") => {
+            let signed = ty.starts_with("int");
+            let bits: u64 = match ty.trim_start_matches("u").trim_start_matches("int") {
+                "" => 256,
+                bits => bits.parse().map_err(|_| invalid())?,
+            };
+            if bits == 0 || bits > 256 || !bits.is_multiple_of(8) {
+                return Err(Error::new(&format!("unsupported type {}", ty)));
+            }
+            let num = parse_integer(value).ok_or_else(invalid)?;
+            let limit = BigInt::from(1u8) << (bits - signed as u64);
+            let min = if signed { -limit.clone() } else { BigInt::from(0u8) };
+            if num < min || num >= limit {
+                return Err(invalid());
+            }
+            // 负数按256位的补码编码
+            let unsigned = if num.sign() == Sign::Minus {
+                ((BigInt::from(1u8) << 256u32) + num).to_biguint().unwrap()
+            } else {
+                num.to_biguint().unwrap()
+            };
+            Ok(left_pad(&unsigned.to_bytes_be()))
+        }
+        _ => Err(Error::new(&format!("unsupported type {}", ty))),
+    }
+}
+
+/// # 左侧补0至32字节
+fn left_pad(bytes: &[u8]) -> [u8; 32] {
+    let mut padded = [0u8; 32];
+    padded[32 - bytes.len()..].copy_from_slice(bytes);
+    padded
+}
+
+/// # 解析hex字符串，`0x`前缀可选
+fn decode_hex(value: &Value) -> Option<Vec<u8>> {
+    hex::decode(HexString::new(value.as_str()?).clean_hex_string()).ok()
+}
+
+/// # 解析整数，可以是json数字、十进制字符串或`0x`开头的十六进制字符串
+fn parse_integer(value: &Value) -> Option<BigInt> {
+    match value {
+        Value::Number(num) => num.as_i64().map(BigInt::from).or_else(|| num.as_u64().map(BigInt::from)),
+        Value::String(s) => match s.strip_prefix(PREFIX_OF_HEX) {
+            Some(hex) => BigUint::parse_bytes(hex.as_bytes(), 16).map(BigInt::from),
+            None => s.parse().ok(),
+        },
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mail_types() -> TypedDataTypes {
+        serde_json::from_value(json!({
+            "Person": [
+                {"name": "name", "type": "string"},
+                {"name": "wallet", "type": "address"}
+            ],
+            "Mail": [
+                {"name": "from", "type": "Person"},
+                {"name": "to", "type": "Person"},
+                {"name": "contents", "type": "string"}
+            ]
+        })).unwrap()
+    }
+
+    fn mail_domain() -> TypedDataDomain {
+        serde_json::from_value(json!({
+            "name": "Ether Mail",
+            "version": "1",
+            "chainId": 1,
+            "verifyingContract": "0xCcCCccccCCCCcCCCCCCcCcCccCcCCCcCcccccccC"
+        })).unwrap()
+    }
+
+    fn mail() -> Value {
+        json!({
+            "from": {"name": "Cow", "wallet": "0xCD2a3d9F938E13CD947Ec05AbC7FE734Df8DD826"},
+            "to": {"name": "Bob", "wallet": "zltc_Z1pnS94bP4hQSYLs4aP4UwBP9pH8bEvhi"},
+            "contents": "Hello, Bob!"
+        })
+    }
+
+    #[test]
+    fn test_encode_type() {
+        let types = mail_types();
+        assert_eq!(primary_type(&types).unwrap(), "Mail");
+        assert_eq!(
+            encode_type("Mail", &types).unwrap(),
+            "Mail(Person from,Person to,string contents)Person(string name,address wallet)"
+        );
+    }
+
+    #[test]
+    fn test_sign_and_verify_typed_data() {
+        let sk = HexString::new("0x29d63245990076b0bbb33f7482beef21855a8d2197c8d076c2356c49e2a06322").decode();
+        let (domain, types, message) = (mail_domain(), mail_types(), mail());
+        for curve in [Curve::Sm2p256v1, Curve::Secp256k1] {
+            let public_key = KeyPair::from_secret_key(&sk, curve).public_key;
+            let signature = sign_typed_data(&domain, &types, &message, &sk, curve).unwrap();
+            assert!(verify_typed_data(&domain, &types, &message, &signature, &public_key, curve).unwrap());
+
+            let mut tampered = message.clone();
+            tampered["contents"] = json!("Hello, Alice!");
+            assert!(!verify_typed_data(&domain, &types, &tampered, &signature, &public_key, curve).unwrap());
+
+            let other_domain = TypedDataDomain { chain_id: Some(2), ..domain.clone() };
+            assert!(!verify_typed_data(&other_domain, &types, &message, &signature, &public_key, curve).unwrap());
+
+            let other_key = KeyPair::new_keypair(curve).public_key;
+            assert!(!verify_typed_data(&domain, &types, &message, &signature, &other_key, curve).unwrap());
+            assert!(verify_typed_data(&domain, &types, &message, &signature, &[0x04, 0x01], curve).is_err());
+        }
+    }
+
+    #[test]
+    fn test_typed_data_hash_invalid_message() {
+        let (domain, types) = (mail_domain(), mail_types());
+        let mut message = mail();
+        message["from"]["wallet"] = json!("0x01");
+        assert!(typed_data_hash(&domain, &types, &message, Curve::Secp256k1).is_err());
+        message["from"]["wallet"] = json!("zltc_notAnAddress");
+        assert!(typed_data_hash(&domain, &types, &message, Curve::Secp256k1).is_err());
+        message.as_object_mut().unwrap().remove("contents");
+        assert!(typed_data_hash(&domain, &types, &message, Curve::Secp256k1).is_err());
+    }
+}
@@ -3,13 +3,14 @@ use std::ops::Add;
 use std::sync::Mutex;
 use std::time::{Duration, SystemTime};
 
-use log::debug;
+use log::{debug, warn};
 use moka::sync::Cache;
 
 use model::block::LatestBlock;
-use model::common::Address;
+use model::common::{hash_eq, Address};
 
 use crate::client::HttpClient;
+use crate::lattice::Options;
 
 /// 账户缓存的实现
 pub trait AccountCacheTrait: Sync + Send {
@@ -38,6 +39,9 @@ pub trait AccountCacheTrait: Sync + Send {
     /// ## 入参
     /// + `http_client: HttpClient`:
     fn set_http_client(&mut self, http_client: HttpClient);
+
+    /// # 清空所有账户的区块缓存，客户端关闭时调用
+    fn clear(&self) {}
 }
 
 /// 账户缓存的默认实现
@@ -50,8 +54,16 @@ pub struct DefaultAccountCache {
     http_client: HttpClient,
     /// 维护一个链（子链/通道）和其对应的守护区块过期时间的Map
     daemon_hash_expire_at_map: Mutex<HashMap<u64, SystemTime>>,
+    /// 维护一个链（子链/通道）和其最近一次更新的守护区块哈希的Map
+    daemon_hash_map: Mutex<HashMap<u64, String>>,
     /// 守护区块哈希的过期时长
     daemon_hash_expiration_duration: Duration,
+    /// 每发送N笔交易后与节点对账一次账户高度，为0时不对账
+    reconcile_interval: u64,
+    /// 从节点查询账户的最新区块时，是否包括pending中的交易
+    use_pending: bool,
+    /// 维护一个账户和其自上次对账以来发送的交易笔数的Map
+    send_count_map: Mutex<HashMap<String, u64>>,
 }
 
 impl DefaultAccountCache {
@@ -81,7 +93,118 @@ impl DefaultAccountCache {
             cache,
             http_client,
             daemon_hash_expire_at_map,
+            daemon_hash_map: Mutex::new(HashMap::new()),
             daemon_hash_expiration_duration,
+            reconcile_interval: 0,
+            use_pending: false,
+            send_count_map: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// # 根据可选项初始化
+    ///
+    /// ## 入参
+    /// + `options: &Options`: 可选项，使用其中的账户缓存配置
+    /// + `http_client: HttpClient`: 链的http客户端
+    ///
+    /// ## 出参
+    /// + `DefaultAccountCache`: 账户缓存
+    pub fn from_options(options: &Options, http_client: HttpClient) -> Self {
+        let mut account_cache = Self::new(
+            options.enable_cache,
+            Duration::from_secs(options.daemon_hash_expiration_seconds),
+            http_client,
+        )
+        .with_reconcile_interval(options.cache_reconcile_interval);
+        account_cache.cache = Cache::builder()
+            .time_to_idle(Duration::from_secs(options.cache_expiration_seconds))
+            .build();
+        account_cache.use_pending = options.use_pending;
+        account_cache
+    }
+
+    /// # 设置对账的间隔，每发送N笔交易后与节点对账一次账户高度，默认为0，不对账
+    ///
+    /// ## 入参
+    /// + `reconcile_interval: u64`: 对账的间隔
+    ///
+    /// ## 出参
+    /// + `DefaultAccountCache`: 账户缓存
+    pub fn with_reconcile_interval(mut self, reconcile_interval: u64) -> Self {
+        self.reconcile_interval = reconcile_interval;
+        self
+    }
+
+    /// # 从节点查询账户的最新区块，`use_pending`为true时包括pending中的交易
+    ///
+    /// ## 入参
+    /// + `chain_id: u64`: 链ID
+    /// + `account_address: &str`: 账户地址
+    ///
+    /// ## 出参
+    /// + `LatestBlock`
+    fn get_latest_block_from_node(&self, chain_id: u64, account_address: &str) -> LatestBlock {
+        let address = Address::new(account_address);
+        let result = if self.use_pending {
+            self.http_client.get_latest_block_with_pending(chain_id, &address)
+        } else {
+            self.http_client.get_latest_block(chain_id, &address)
+        };
+        result.unwrap()
+    }
+
+    /// # 判断账户是否需要与节点对账，需要对账时重置发送的交易笔数
+    ///
+    /// ## 入参
+    /// + `key: &str`: 缓存的key
+    ///
+    /// ## 出参
+    /// + `bool`
+    fn should_reconcile(&self, key: &str) -> bool {
+        if self.reconcile_interval == 0 {
+            return false;
+        }
+        let mut map = self.send_count_map.lock().unwrap();
+        match map.get(key) {
+            Some(count) if *count >= self.reconcile_interval => {
+                map.remove(key);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// # 将缓存的区块与节点上包含pending交易的最新区块对账，高度不一致时以节点为准
+    ///
+    /// ## 入参
+    /// + `chain_id: u64`: 链ID
+    /// + `account_address: &str`: 账户地址
+    /// + `cached_block: LatestBlock`: 缓存的区块
+    ///
+    /// ## 出参
+    /// + `LatestBlock`: 对账后的区块
+    fn reconcile(&self, chain_id: u64, account_address: &str, cached_block: LatestBlock) -> LatestBlock {
+        let result = self
+            .http_client
+            .get_latest_block_with_pending(chain_id, &Address::new(account_address));
+        match result {
+            Ok(block) => {
+                if block.height != cached_block.height {
+                    warn!(
+                        "账户【{}】在链【{}】上的缓存高度【{}】与节点高度【{}】不一致，已按节点的区块重置缓存",
+                        account_address, chain_id, cached_block.height, block.height
+                    );
+                    return block;
+                }
+                cached_block
+            }
+            Err(e) => {
+                warn!(
+                    "账户【{}】在链【{}】上对账失败，继续使用缓存的区块：{}",
+                    account_address, chain_id, e
+                );
+                cached_block
+            }
         }
     }
 }
@@ -101,6 +224,8 @@ impl AccountCacheTrait for DefaultAccountCache {
         let _cache = self.cache.clone();
         _cache.insert(key.clone(), block);
 
+        *self.send_count_map.lock().unwrap().entry(key).or_insert(0) += 1;
+
         let mut map = self.daemon_hash_expire_at_map.lock().unwrap();
         if !map.contains_key(&chain_id) {
             map.insert(
@@ -120,26 +245,28 @@ impl AccountCacheTrait for DefaultAccountCache {
     /// + `LatestBlock`: 最新区块
     fn get(&self, chain_id: u64, account_address: &str) -> LatestBlock {
         if !&self.enable {
-            let result = self
-                .http_client
-                .get_latest_block(chain_id, &Address::new(account_address));
-            return result.unwrap();
+            return self.get_latest_block_from_node(chain_id, account_address);
         }
 
         let key = format!("{}_{}", chain_id, account_address);
         let cached_block_option = self.cache.get(&key);
+        let from_cache = cached_block_option.is_some();
         let mut cached_block: LatestBlock;
         match cached_block_option {
-            Some(block) => cached_block = block,
+            Some(block) => {
+                cached_block = block;
+                if self.should_reconcile(&key) {
+                    cached_block = self.reconcile(chain_id, account_address, cached_block);
+                    self.cache.insert(key.clone(), cached_block.clone());
+                }
+            }
             None => {
-                let result = self
-                    .http_client
-                    .get_latest_block(chain_id, &Address::new(account_address));
-                cached_block = result.unwrap();
+                cached_block = self.get_latest_block_from_node(chain_id, account_address);
+                self.send_count_map.lock().unwrap().remove(&key);
             }
         }
 
-        // 判断守护区块的哈希是否过期
+        // 判断守护区块的哈希是否过期，持有锁期间更新，保证每个过期周期内只更新一次
         let mut map = self.daemon_hash_expire_at_map.lock().unwrap();
         if map.contains_key(&chain_id) {
             let daemon_hash_expire_at = map.get(&chain_id).unwrap();
@@ -153,12 +280,23 @@ impl AccountCacheTrait for DefaultAccountCache {
                 let daemon_hash_expire_at =
                     SystemTime::now().add(self.daemon_hash_expiration_duration);
                 map.insert(chain_id, daemon_hash_expire_at);
-                cached_block.daemon_hash = latest_daemon_block.hash;
+                self.daemon_hash_map.lock().unwrap().insert(chain_id, latest_daemon_block.hash);
             }
         } else {
             let daemon_hash_expire_at = SystemTime::now().add(self.daemon_hash_expiration_duration);
             map.insert(chain_id, daemon_hash_expire_at);
         }
+        drop(map);
+
+        // 使用链上最近一次更新的守护区块哈希，并写回缓存，避免之后的get再次返回过期的哈希
+        if let Some(daemon_hash) = self.daemon_hash_map.lock().unwrap().get(&chain_id) {
+            if !hash_eq(&cached_block.daemon_hash, daemon_hash) {
+                cached_block.daemon_hash = daemon_hash.clone();
+                if from_cache {
+                    self.cache.insert(key, cached_block.clone());
+                }
+            }
+        }
 
         cached_block
     }
@@ -166,14 +304,43 @@ impl AccountCacheTrait for DefaultAccountCache {
     fn set_http_client(&mut self, http_client: HttpClient) {
         self.http_client = http_client
     }
+
+    fn clear(&self) {
+        self.cache.invalidate_all();
+        self.send_count_map.lock().unwrap().clear();
+        self.daemon_hash_expire_at_map.lock().unwrap().clear();
+        self.daemon_hash_map.lock().unwrap().clear();
+    }
 }
 
 #[cfg(test)]
 mod test {
     use std::thread;
 
+    use serde_json::json;
+
+    use crate::lattice::OptionsBuilder;
+
     use super::*;
 
+    const ACCOUNT_ADDRESS: &str = "zltc_Z1pnS94bP4hQSYLs4aP4UwBP9pH8bEvhi";
+
+    /// # 启动一个模拟节点，返回`latest`作为账户的最新区块
+    fn mock_node(latest: LatestBlock) -> HttpClient {
+        crate::mock::mock_node(move |method, _| match method {
+            "latc_getCurrentTBDB" | "latc_getPendingTBDB" => json!(latest),
+            method => panic!("unexpected method {}", method),
+        })
+    }
+
+    fn new_block(height: u64) -> LatestBlock {
+        LatestBlock {
+            height,
+            hash: format!("0x{:064x}", height),
+            daemon_hash: format!("0x{:064x}", 1),
+        }
+    }
+
     #[test]
     fn test_get() {
         let http_client = HttpClient::new("192.168.1.185", 13800);
@@ -184,4 +351,85 @@ mod test {
         block = default.get(2, "zltc_Z1pnS94bP4hQSYLs4aP4UwBP9pH8bEvhi");
         println!("block: {:?}", block);
     }
+
+    #[test]
+    fn test_reconcile_height_gap() {
+        let http_client = mock_node(new_block(5));
+        let options = OptionsBuilder::builder()
+            .set_cache_reconcile_interval(1)
+            .build();
+        let cache = DefaultAccountCache::from_options(&options, http_client);
+        // 模拟一笔交易发送成功后缓存高度加1，但节点丢弃了这笔交易
+        cache.set(1, ACCOUNT_ADDRESS, new_block(6));
+
+        let block = cache.get(1, ACCOUNT_ADDRESS);
+        assert_eq!(block.height, 5);
+        assert_eq!(block.hash, new_block(5).hash);
+    }
+
+    #[test]
+    fn test_reconcile_after_interval() {
+        let http_client = mock_node(new_block(5));
+        let options = OptionsBuilder::builder()
+            .set_cache_reconcile_interval(2)
+            .build();
+        let cache = DefaultAccountCache::from_options(&options, http_client);
+        cache.set(1, ACCOUNT_ADDRESS, new_block(6));
+        assert_eq!(cache.get(1, ACCOUNT_ADDRESS).height, 6);
+
+        cache.set(1, ACCOUNT_ADDRESS, new_block(7));
+        assert_eq!(cache.get(1, ACCOUNT_ADDRESS).height, 5);
+    }
+
+    #[test]
+    fn test_concurrent_get_refreshes_daemon_hash_once() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::Arc;
+
+        let daemon_block_requests = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&daemon_block_requests);
+        let http_client = crate::mock::mock_node(move |method, _| match method {
+            "latc_getCurrentDBlock" => {
+                counter.fetch_add(1, Ordering::SeqCst);
+                json!({"hash": format!("0x{:064x}", 2), "parentHash": format!("0x{:064x}", 1), "number": 2, "timestamp": 0, "version": 3})
+            }
+            method => panic!("unexpected method {}", method),
+        });
+        let cache = Arc::new(DefaultAccountCache::new(true, Duration::from_millis(200), http_client));
+        cache.set(1, ACCOUNT_ADDRESS, new_block(6));
+        thread::sleep(Duration::from_millis(300));
+
+        let handles: Vec<_> = (0..8)
+            .map(|_| {
+                let cache = Arc::clone(&cache);
+                thread::spawn(move || cache.get(1, ACCOUNT_ADDRESS))
+            })
+            .collect();
+        for handle in handles {
+            let block = handle.join().unwrap();
+            assert_eq!(block.height, 6);
+            assert_eq!(block.daemon_hash, format!("0x{:064x}", 2));
+        }
+        assert_eq!(daemon_block_requests.load(Ordering::SeqCst), 1);
+
+        // 刷新后的守护区块哈希已写回缓存
+        assert_eq!(cache.cache.get(&format!("{}_{}", 1, ACCOUNT_ADDRESS)).unwrap().daemon_hash, format!("0x{:064x}", 2));
+        assert_eq!(cache.get(1, ACCOUNT_ADDRESS).daemon_hash, format!("0x{:064x}", 2));
+        assert_eq!(daemon_block_requests.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_new_with_options() {
+        let http_client = HttpClient::new("127.0.0.1", 13000);
+        let options = OptionsBuilder::builder()
+            .set_cache_expiration_seconds(30)
+            .set_daemon_hash_expiration_seconds(5)
+            .set_use_pending(true)
+            .build();
+        let cache = DefaultAccountCache::from_options(&options, http_client);
+        assert_eq!(cache.cache.policy().time_to_idle(), Some(Duration::from_secs(30)));
+        assert_eq!(cache.daemon_hash_expiration_duration, Duration::from_secs(5));
+        assert!(cache.enable);
+        assert!(cache.use_pending);
+    }
 }
@@ -5,6 +5,8 @@ use serde::{Deserialize, Serialize};
 use crypto::Transaction;
 use crypto::transaction::TxType;
 use model::block::LatestBlock;
+use model::convert::compress_payload;
+use model::HexString;
 
 /// 交易构造
 pub trait TransactionBuilder {
@@ -15,6 +17,8 @@ pub trait TransactionBuilder {
     fn set_linker(self, linker: &str) -> Self;
     fn set_code(self, code: &str) -> Self;
     fn set_payload(self, payload: &str) -> Self;
+    fn set_payload_compressed(self, data: &[u8]) -> Self;
+    fn set_hub(self, hub: Vec<String>) -> Self;
     fn set_amount(self, amount: Option<u128>) -> Self;
     fn set_joule(self, joule: Option<u128>) -> Self;
     fn build(self) -> Transaction;
@@ -99,6 +103,30 @@ macro_rules! impl_transaction_builder {
                 self
             }
 
+            /// # 使用deflate压缩数据后设置为交易的备注，适用于较大的存证数据或备注
+            ///
+            /// ## 入参
+            /// + `data: &[u8]`: 原始数据，压缩后带有1字节的方案前缀，可用`model::convert::decompress_payload`解压
+            ///
+            /// ## 出参
+            /// + `Self`
+            fn set_payload_compressed(mut self, data: &[u8]) -> Self {
+                self.transaction.payload = Some(HexString::from(&compress_payload(data)).hex_string);
+                self
+            }
+
+            /// # 设置交易引用的其它交易的哈希
+            ///
+            /// ## 入参
+            /// + `hub: Vec<String>`: 交易哈希，示例：0xe8df1f1e250cd0eac75eee3f8733e26e9422ef5ea88650ab54498cd8e4928144
+            ///
+            /// ## 出参
+            /// + `Self`
+            fn set_hub(mut self, hub: Vec<String>) -> Self {
+                self.transaction.hub = Some(hub);
+                self
+            }
+
             /// # 设置交易的amount
             ///
             /// ## 入参
@@ -133,6 +161,7 @@ macro_rules! impl_transaction_builder {
 impl_transaction_builder!(TransferBuilder, TxType::Send);
 impl_transaction_builder!(DeployContractBuilder, TxType::Contract);
 impl_transaction_builder!(CallContractBuilder, TxType::Execute);
+impl_transaction_builder!(ReceiveBuilder, TxType::Receive);
 
 #[cfg(test)]
 mod test {
@@ -142,6 +171,7 @@ mod test {
     use abi::abi::Abi;
     use model::{Curve, HexString};
     use model::common::Address;
+    use model::constants::{PREFIX_OF_HEX, ZERO_HASH_STRING};
 
     use crate::client::HttpClient;
 
@@ -149,6 +179,50 @@ mod test {
 
     const CHAIN_ID: u64 = 1;
 
+    #[test]
+    fn test_build_receive() {
+        let send_hash = "0x616bf03baa685df9fddeff4701f170b30176e54120df726142a534f8f2b51873";
+        let transaction = ReceiveBuilder::builder()
+            .set_current_block(LatestBlock {
+                height: 1,
+                hash: String::from("0x01"),
+                daemon_hash: String::from("0x02"),
+            })
+            .set_owner("zltc_nbrZcx1AzBXC361nWSwry8JgSJNEzrNiD")
+            .set_hub(vec![send_hash.to_string()])
+            .build();
+        assert!(matches!(transaction.tx_type, TxType::Receive));
+        assert_eq!(transaction.hub, Some(vec![send_hash.to_string()]));
+        assert_eq!(transaction.height, 2);
+    }
+
+    #[test]
+    fn test_payload_compressed() {
+        let data = "traceability ".repeat(100).into_bytes();
+        let transaction = TransferBuilder::builder()
+            .set_owner("zltc_UXpJCXdhTkg6edriiaRUVkYgTfv2Z5npe")
+            .set_payload_compressed(&data)
+            .build();
+        let payload = HexString::new(&transaction.payload.unwrap()).decode();
+        assert!(payload.len() < data.len());
+        assert_eq!(data, model::convert::decompress_payload(&payload).unwrap());
+    }
+
+    #[test]
+    fn test_default_payload() {
+        let transaction = TransferBuilder::builder()
+            .set_current_block(LatestBlock {
+                height: 0,
+                hash: ZERO_HASH_STRING.to_string(),
+                daemon_hash: ZERO_HASH_STRING.to_string(),
+            })
+            .set_owner("zltc_UXpJCXdhTkg6edriiaRUVkYgTfv2Z5npe")
+            .set_linker("zltc_nbrZcx1AzBXC361nWSwry8JgSJNEzrNiD")
+            .build();
+        assert_eq!(None, transaction.payload);
+        assert_eq!(PREFIX_OF_HEX, transaction.to_raw_tx().payload);
+    }
+
     #[test]
     fn test_transfer() {
         let client = HttpClient::new("192.168.1.185", 13000);
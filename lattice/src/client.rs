@@ -1,29 +1,34 @@
 use std::collections::HashMap;
 use std::fmt::Debug;
+use std::io::Read;
+use std::net::{TcpStream as StdTcpStream, ToSocketAddrs};
 use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
 use async_trait::async_trait;
-use futures_util::stream::{SplitSink, SplitStream};
-use futures_util::{SinkExt, StreamExt};
-use log::debug;
+use futures_util::stream::{self, SplitSink, SplitStream};
+use futures_util::{SinkExt, Stream, StreamExt};
+use log::{debug, error};
 use reqwest::blocking::Client;
 use reqwest::header::{HeaderMap, HeaderName, HeaderValue, CONTENT_TYPE};
 use reqwest::Url;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 use tokio::net::TcpStream;
-use tokio::sync::mpsc::Receiver;
-use tokio::sync::mpsc::Sender;
+use tokio::sync::mpsc::{self, Receiver, Sender};
+use tokio::task::AbortHandle;
 use tokio_tungstenite::tungstenite::Message;
 use tokio_tungstenite::{connect_async, MaybeTlsStream, WebSocketStream};
 
+use crypto::transaction::RawTransaction;
 use crypto::Transaction;
-use model::block::{DBlock, LatestBlock};
+use model::block::{DBlock, LatestBlock, TBlock};
 use model::common::Address;
 use model::receipt::Receipt;
 use model::Error;
 
-use crate::constants::JSON_RPC_VERSION;
+use crate::constants::{JSON_RPC_VERSION, WS_CHANNEL_CAPACITY, WS_RECONNECT_INTERVAL_MILLIS};
 
 /// 定义一个同步阻塞的客户端trait
 pub trait HttpRequest {
@@ -72,6 +77,22 @@ impl JsonRpcBody {
         let body = JsonRpcBody::new("latc_subscribe".to_string(), vec![json!("newDBlock")]);
         serde_json::to_string(&body).unwrap()
     }
+
+    fn new_latest_block(addr: &Address) -> Self {
+        JsonRpcBody::new("latc_getCurrentTBDB".to_string(), vec![json!(addr.to_zltc_address())])
+    }
+
+    fn new_send_raw_tx(signed_tx: Transaction) -> Self {
+        JsonRpcBody::new("wallet_sendRawTBlock".to_string(), vec![json!(signed_tx.to_raw_tx())])
+    }
+
+    fn new_pre_call_contract(unsigned_tx: Transaction) -> Self {
+        JsonRpcBody::new("wallet_preExecuteContract".to_string(), vec![json!(unsigned_tx.to_raw_tx())])
+    }
+
+    fn new_receipt(hash: &str) -> Self {
+        JsonRpcBody::new("latc_getReceipt".to_string(), vec![json!(hash)])
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -85,12 +106,49 @@ pub struct Response<T> {
     error: Option<JsonRpcError>,
 }
 
+impl<T> Response<T> {
+    /// # 响应的ID，与请求的ID相同
+    pub fn id(&self) -> u32 {
+        self.id
+    }
+
+    /// # 取出结果
+    ///
+    /// ## 出参
+    /// + `Result<T, Error>`: 节点返回错误或结果为空时返回`Err`
+    pub fn into_result(self) -> Result<T, Error> {
+        if let Some(err) = self.error {
+            return Err(Error::json_rpc(err.code as i32, err.message));
+        }
+        self.result.ok_or(Error::new("结果为空"))
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 struct JsonRpcError {
     code: i16,
     message: String,
 }
 
+/// 探测节点连通性的超时时长
+const DIAL_TIMEOUT: Duration = Duration::from_secs(3);
+/// 节点健康状态的默认缓存时长
+const DEFAULT_HEALTH_CHECK_WINDOW: Duration = Duration::from_secs(5);
+/// 默认的响应体最大字节数
+const DEFAULT_MAX_RESPONSE_SIZE: usize = 16 * 1024 * 1024;
+/// 响应体无法解析时，错误信息中保留的响应体最大字符数
+const RESPONSE_SNIPPET_CHARS: usize = 256;
+
+/// 可插拔的传输层，设置后`HttpClient`通过它发送json-rpc请求，不再请求节点的http端口
+#[derive(Clone)]
+struct Transport(Arc<dyn HttpRequest + Send + Sync>);
+
+impl Debug for Transport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Transport")
+    }
+}
+
 /// HTTP客户端
 #[derive(Debug, Clone)]
 pub struct HttpClient {
@@ -98,18 +156,90 @@ pub struct HttpClient {
     pub ip: String,
     pub port: u16,
     url: String,
+    /// 节点健康状态的缓存时长，在该时长内复用上一次的探测结果
+    health_check_window: Duration,
+    /// 最近一次探测节点连通性的时间和结果，克隆的客户端之间共享
+    health: Arc<Mutex<Option<(Instant, bool)>>>,
+    /// 自定义的传输层，为None时通过http发送请求
+    transport: Option<Transport>,
+    /// 响应体的最大字节数，超过时返回错误，避免异常节点返回过大的响应耗尽内存
+    max_response_size: usize,
 }
 
 impl HttpClient {
     pub fn new(ip: &str, port: u16) -> Self {
         HttpClient {
-            client: Client::new(),
+            client: Client::builder()
+                .gzip(true)
+                .deflate(true)
+                .brotli(true)
+                .build()
+                .expect("failed to build http client"),
             ip: ip.to_string(),
             port,
             url: format!("http://{}:{}", ip, port),
+            health_check_window: DEFAULT_HEALTH_CHECK_WINDOW,
+            health: Arc::new(Mutex::new(None)),
+            transport: None,
+            max_response_size: DEFAULT_MAX_RESPONSE_SIZE,
         }
     }
 
+    /// # 设置响应体的最大字节数
+    ///
+    /// ## 入参
+    /// + `max_response_size: usize`: 最大字节数，默认16MiB
+    pub fn set_max_response_size(&mut self, max_response_size: usize) {
+        self.max_response_size = max_response_size;
+    }
+
+    /// # 设置自定义的传输层，之后的json-rpc请求都交由`transport`处理，可用于测试或接入其它协议
+    ///
+    /// ## 入参
+    /// + `transport: Arc<dyn HttpRequest + Send + Sync>`: 传输层
+    pub fn set_transport(&mut self, transport: Arc<dyn HttpRequest + Send + Sync>) {
+        self.transport = Some(Transport(transport));
+    }
+
+    /// # 设置节点健康状态的缓存时长
+    ///
+    /// ## 入参
+    /// + `window: Duration`: 缓存时长，为0时每次都重新探测
+    pub fn set_health_check_window(&mut self, window: Duration) {
+        self.health_check_window = window;
+    }
+
+    /// # 探测能否与节点建立TCP连接，每次调用都会建立一个新的连接
+    ///
+    /// ## 出参
+    /// + `bool`
+    pub fn can_dial(&self) -> bool {
+        let healthy = match (self.ip.as_str(), self.port).to_socket_addrs() {
+            Ok(addrs) => addrs
+                .into_iter()
+                .any(|addr| StdTcpStream::connect_timeout(&addr, DIAL_TIMEOUT).is_ok()),
+            Err(e) => {
+                debug!("解析节点地址【{}:{}】失败：{}", self.ip, self.port, e);
+                false
+            }
+        };
+        *self.health.lock().unwrap() = Some((Instant::now(), healthy));
+        healthy
+    }
+
+    /// # 节点是否健康，在缓存时长内复用上一次`can_dial`的结果
+    ///
+    /// ## 出参
+    /// + `bool`
+    pub fn is_healthy(&self) -> bool {
+        if let Some((checked_at, healthy)) = *self.health.lock().unwrap() {
+            if checked_at.elapsed() < self.health_check_window {
+                return healthy;
+            }
+        }
+        self.can_dial()
+    }
+
     /// # 创建http的请求头
     ///
     /// ## 入参
@@ -147,12 +277,48 @@ impl HttpClient {
     {
         let message = serde_json::to_string(&body)?;
         let response = self.send(message.as_str(), headers)?;
-        let response: Response<T> = serde_json::from_str(&response)?;
-        let err_option = response.error;
-        if let Some(err) = err_option {
-            return Err(Error::custom(err.code as i32, format!("{}", err.message)));
+        json_rpc_result(&response)
+    }
+
+    /// # 批量发送json-rpc请求，一次http请求完成多个查询
+    ///
+    /// 请求的ID会按顺序重新设置为`1..=n`，响应按ID与请求对应，单个请求的错误保留在对应的响应中
+    ///
+    /// ## 入参
+    /// + `chain_id: u64`: 链ID
+    /// + `bodies: Vec<JsonRpcBody>`: 请求体
+    ///
+    /// ## 出参
+    /// + `Result<Vec<Response<serde_json::Value>>, Error>`: 与请求的顺序相同，缺少某个请求的响应，或者响应的ID未知、重复时返回`Err`
+    pub fn send_batch(&self, chain_id: u64, mut bodies: Vec<JsonRpcBody>) -> Result<Vec<Response<serde_json::Value>>, Error> {
+        if bodies.is_empty() {
+            return Ok(vec![]);
+        }
+        for (i, body) in bodies.iter_mut().enumerate() {
+            body.id = i as u32 + 1;
+        }
+        let message = serde_json::to_string(&bodies)?;
+        let response = self.send(message.as_str(), Self::new_headers(chain_id))?;
+        let responses: Vec<Response<serde_json::Value>> = serde_json::from_str(&response).map_err(|e| {
+            Error::new(&format!("解析批量响应失败，{}，响应体：{}", e, response_snippet(&response))).with_source(e)
+        })?;
+
+        let mut ordered: Vec<Option<Response<serde_json::Value>>> = vec![None; bodies.len()];
+        for response in responses {
+            let slot = (response.id as usize)
+                .checked_sub(1)
+                .and_then(|i| ordered.get_mut(i))
+                .ok_or_else(|| Error::new(&format!("批量响应中有未知的ID{}", response.id)))?;
+            if slot.is_some() {
+                return Err(Error::new(&format!("批量响应中有重复的ID{}", response.id)));
+            }
+            *slot = Some(response);
         }
-        response.result.ok_or(Error::new("结果为空"))
+        ordered
+            .into_iter()
+            .enumerate()
+            .map(|(i, response)| response.ok_or_else(|| Error::new(&format!("批量响应中缺少ID为{}的响应", i + 1))))
+            .collect()
     }
 
     /// # 查询最新的守护区块信息
@@ -180,10 +346,7 @@ impl HttpClient {
     ///   + `Ok(CurrentTDBlock)`
     ///   + `Err(err)`
     pub fn get_latest_block(&self, chain_id: u64, addr: &Address) -> Result<LatestBlock, Error> {
-        let body = JsonRpcBody::new(
-            "latc_getCurrentTBDB".to_string(),
-            vec![json!(addr.to_zltc_address())],
-        );
+        let body = JsonRpcBody::new_latest_block(addr);
         let result: Result<LatestBlock, Error> =
             self.send_json_rpc_request(&body, Self::new_headers(chain_id));
         result
@@ -213,6 +376,23 @@ impl HttpClient {
         result
     }
 
+    /// # 查询账户指定高度的账户区块
+    ///
+    /// ## 入参
+    /// + `chain_id: u64`: 链ID
+    /// + `addr: &Address`: 账户地址
+    /// + `height: u64`: 账户区块高度
+    ///
+    /// ## 出参
+    /// + `Result<TBlock, Error>`
+    pub fn get_tblock_by_height(&self, chain_id: u64, addr: &Address, height: u64) -> Result<TBlock, Error> {
+        let body = JsonRpcBody::new(
+            "latc_getTBlockByNumber".to_string(),
+            vec![json!(addr.to_zltc_address()), json!(height)],
+        );
+        self.send_json_rpc_request(&body, Self::new_headers(chain_id))
+    }
+
     /// # 发送已签名的交易
     ///
     /// ## 入参
@@ -223,17 +403,31 @@ impl HttpClient {
     /// ## 出参
     /// + `Result<String, Error>`
     ///   + `Ok(String)`
-    ///   + `Err(err)`
+    ///   + `Err(err)`: 交易的字段不满足交易类型的要求，或者节点返回错误
     pub fn send_raw_tx(&self, chain_id: u64, signed_tx: Transaction) -> Result<String, Error> {
-        let body = JsonRpcBody::new(
-            "wallet_sendRawTBlock".to_string(),
-            vec![json!(signed_tx.to_raw_tx())],
-        );
+        signed_tx.validate_for_type()?;
+        let body = JsonRpcBody::new_send_raw_tx(signed_tx);
         let result: Result<String, Error> =
             self.send_json_rpc_request(&body, Self::new_headers(chain_id));
         result
     }
 
+    /// # 转发离线签名的交易，签名和发送可以在不同的机器上完成
+    ///
+    /// ## 入参
+    /// + `chain_id: u64`: 链ID
+    /// + `raw_tx_json: &str`: 已签名的`RawTransaction`的json字符串
+    ///
+    /// ## 出参
+    /// + `Result<String, Error>`: 交易哈希，json不是合法的`RawTransaction`或字段不满足交易类型的要求时返回`Err`
+    pub fn send_raw_json(&self, chain_id: u64, raw_tx_json: &str) -> Result<String, Error> {
+        let raw_tx: RawTransaction = serde_json::from_str(raw_tx_json)
+            .map_err(|e| Error::new(&format!("invalid raw transaction json, {}", e)).with_source(e))?;
+        Transaction::try_from(raw_tx.clone())?.validate_for_type()?;
+        let body = JsonRpcBody::new("wallet_sendRawTBlock".to_string(), vec![json!(raw_tx)]);
+        self.send_json_rpc_request(&body, Self::new_headers(chain_id))
+    }
+
     /// # 预执行合约
     ///
     /// ## 入参
@@ -250,10 +444,7 @@ impl HttpClient {
         chain_id: u64,
         unsigned_tx: Transaction,
     ) -> Result<Receipt, Error> {
-        let body = JsonRpcBody::new(
-            "wallet_preExecuteContract".to_string(),
-            vec![json!(unsigned_tx.to_raw_tx())],
-        );
+        let body = JsonRpcBody::new_pre_call_contract(unsigned_tx);
         let result: Result<Receipt, Error> =
             self.send_json_rpc_request(&body, Self::new_headers(chain_id));
         result
@@ -268,7 +459,7 @@ impl HttpClient {
     /// ## Returns
     /// + `Box<Receipt>`
     pub fn get_receipt(&self, chain_id: u64, hash: &str) -> Result<Receipt, Error> {
-        let body = JsonRpcBody::new("latc_getReceipt".to_string(), vec![json!(hash)]);
+        let body = JsonRpcBody::new_receipt(hash);
         let result: Result<Receipt, Error> =
             self.send_json_rpc_request(&body, Self::new_headers(chain_id));
         result
@@ -277,25 +468,202 @@ impl HttpClient {
 
 impl HttpRequest for HttpClient {
     fn send(&self, message: &str, headers: HashMap<String, String>) -> Result<String, Error> {
+        if let Some(Transport(transport)) = &self.transport {
+            return transport.send(message, headers);
+        }
         debug!("开始发送JsonRpc请求，url: {}, body: {}", &self.url, message);
-        let mut header_map = HeaderMap::new();
-        header_map.insert(
-            HeaderName::from_str(CONTENT_TYPE.as_str()).unwrap(),
-            HeaderValue::from_str("application/json").unwrap(),
-        );
-        for (k, v) in headers {
-            let key = HeaderName::from_str(&k).unwrap();
-            let value = HeaderValue::from_str(&v).unwrap();
-            header_map.insert(key, value);
+        let response = self
+            .client
+            .post(&self.url)
+            .body(message.to_string())
+            .headers(header_map(headers))
+            .send()?;
+        let limit = self.max_response_size;
+        if let Some(len) = response.content_length() {
+            if len > limit as u64 {
+                return Err(Error::new(&format!("响应体的长度{}超过了最大长度{}", len, limit)));
+            }
+        }
+        // 没有Content-Length或其与实际长度不符时，最多读取limit+1个字节来判断是否超长
+        let mut body = Vec::new();
+        response
+            .take(limit as u64 + 1)
+            .read_to_end(&mut body)
+            .map_err(|e| Error::new(&format!("读取响应体失败，{}", e)).with_source(e))?;
+        if body.len() > limit {
+            return Err(Error::new(&format!("响应体的长度超过了最大长度{}", limit)));
+        }
+        String::from_utf8(body).map_err(|e| Error::new(&format!("响应体不是合法的UTF-8，{}", e)).with_source(e))
+    }
+}
+
+/// 异步的HTTP客户端，与`HttpClient`发送相同的json-rpc请求，不会阻塞异步运行时
+#[derive(Debug, Clone)]
+pub struct AsyncHttpClient {
+    client: reqwest::Client,
+    pub ip: String,
+    pub port: u16,
+    url: String,
+    /// 响应体的最大字节数，超过时返回错误
+    max_response_size: usize,
+}
+
+impl AsyncHttpClient {
+    pub fn new(ip: &str, port: u16) -> Self {
+        AsyncHttpClient {
+            client: reqwest::Client::builder()
+                .gzip(true)
+                .deflate(true)
+                .brotli(true)
+                .build()
+                .expect("failed to build http client"),
+            ip: ip.to_string(),
+            port,
+            url: format!("http://{}:{}", ip, port),
+            max_response_size: DEFAULT_MAX_RESPONSE_SIZE,
         }
-        let res = self
+    }
+
+    /// # 设置响应体的最大字节数
+    ///
+    /// ## 入参
+    /// + `max_response_size: usize`: 最大字节数，默认16MiB
+    pub fn set_max_response_size(&mut self, max_response_size: usize) {
+        self.max_response_size = max_response_size;
+    }
+
+    /// # 发送Http请求
+    ///
+    /// ## 入参
+    /// + `message: &str`: 请求体
+    /// + `headers: HashMap<String, String>`: 请求头
+    ///
+    /// ## 出参
+    /// + `Result<String, Error>`
+    async fn send(&self, message: &str, headers: HashMap<String, String>) -> Result<String, Error> {
+        debug!("开始发送JsonRpc请求，url: {}, body: {}", &self.url, message);
+        let mut response = self
             .client
             .post(&self.url)
             .body(message.to_string())
-            .headers(header_map)
-            .send()?
-            .text()?;
-        Ok(res)
+            .headers(header_map(headers))
+            .send()
+            .await?;
+        let limit = self.max_response_size;
+        if let Some(len) = response.content_length() {
+            if len > limit as u64 {
+                return Err(Error::new(&format!("响应体的长度{}超过了最大长度{}", len, limit)));
+            }
+        }
+        let mut body = Vec::new();
+        while let Some(chunk) = response.chunk().await? {
+            body.extend_from_slice(&chunk);
+            if body.len() > limit {
+                return Err(Error::new(&format!("响应体的长度超过了最大长度{}", limit)));
+            }
+        }
+        String::from_utf8(body).map_err(|e| Error::new(&format!("响应体不是合法的UTF-8，{}", e)).with_source(e))
+    }
+
+    async fn send_json_rpc_request<T>(&self, body: &JsonRpcBody, chain_id: u64) -> Result<T, Error>
+    where
+        T: for<'a> Deserialize<'a>,
+    {
+        let message = serde_json::to_string(&body)?;
+        let response = self.send(message.as_str(), HttpClient::new_headers(chain_id)).await?;
+        json_rpc_result(&response)
+    }
+
+    /// # 查询最新的区块（包括账户和守护区块的信息）
+    ///
+    /// ## 入参
+    /// + `chain_id: u64`: 链ID
+    /// + `addr: &Address`: 账户地址
+    ///
+    /// ## 出参
+    /// + `Result<LatestBlock, Error>`
+    pub async fn get_latest_block(&self, chain_id: u64, addr: &Address) -> Result<LatestBlock, Error> {
+        self.send_json_rpc_request(&JsonRpcBody::new_latest_block(addr), chain_id).await
+    }
+
+    /// # 发送已签名的交易
+    ///
+    /// ## 入参
+    /// + `chain_id: u64`: 链ID
+    /// + `signed_tx: Transaction`: 已签名的交易
+    ///
+    /// ## 出参
+    /// + `Result<String, Error>`: 交易哈希，交易的字段不满足交易类型的要求时返回`Err`
+    pub async fn send_raw_tx(&self, chain_id: u64, signed_tx: Transaction) -> Result<String, Error> {
+        signed_tx.validate_for_type()?;
+        self.send_json_rpc_request(&JsonRpcBody::new_send_raw_tx(signed_tx), chain_id).await
+    }
+
+    /// # 预执行合约
+    ///
+    /// ## 入参
+    /// + `chain_id: u64`: 链ID
+    /// + `unsigned_tx: Transaction`: 未签名的交易
+    ///
+    /// ## 出参
+    /// + `Result<Receipt, Error>`
+    pub async fn pre_call_contract(&self, chain_id: u64, unsigned_tx: Transaction) -> Result<Receipt, Error> {
+        self.send_json_rpc_request(&JsonRpcBody::new_pre_call_contract(unsigned_tx), chain_id).await
+    }
+
+    /// # 查询交易回执
+    ///
+    /// ## 入参
+    /// + `chain_id: u64`: 链ID
+    /// + `hash: &str`: 交易哈希
+    ///
+    /// ## 出参
+    /// + `Result<Receipt, Error>`
+    pub async fn get_receipt(&self, chain_id: u64, hash: &str) -> Result<Receipt, Error> {
+        self.send_json_rpc_request(&JsonRpcBody::new_receipt(hash), chain_id).await
+    }
+}
+
+impl From<&HttpClient> for AsyncHttpClient {
+    /// 连接同一个节点，沿用响应体的最大字节数，自定义的传输层不会被沿用
+    fn from(http_client: &HttpClient) -> Self {
+        let mut client = AsyncHttpClient::new(&http_client.ip, http_client.port);
+        client.max_response_size = http_client.max_response_size;
+        client
+    }
+}
+
+/// # 创建请求头，始终包含`Content-Type: application/json`
+fn header_map(headers: HashMap<String, String>) -> HeaderMap {
+    let mut header_map = HeaderMap::new();
+    header_map.insert(
+        HeaderName::from_str(CONTENT_TYPE.as_str()).unwrap(),
+        HeaderValue::from_str("application/json").unwrap(),
+    );
+    for (k, v) in headers {
+        let key = HeaderName::from_str(&k).unwrap();
+        let value = HeaderValue::from_str(&v).unwrap();
+        header_map.insert(key, value);
+    }
+    header_map
+}
+
+/// # 解析json-rpc的响应，节点返回错误或结果为空时返回`Err`
+fn json_rpc_result<T>(response: &str) -> Result<T, Error>
+where
+    T: for<'a> Deserialize<'a>,
+{
+    let response: Response<T> = serde_json::from_str(response).map_err(|e| {
+        Error::new(&format!("解析响应失败，{}，响应体：{}", e, response_snippet(response))).with_source(e)
+    })?;
+    response.into_result()
+}
+
+/// # 截取响应体的开头，用于错误信息
+fn response_snippet(response: &str) -> String {
+    match response.char_indices().nth(RESPONSE_SNIPPET_CHARS) {
+        None => response.to_string(),
+        Some((end, _)) => format!("{}...(共{}字节)", &response[..end], response.len()),
     }
 }
 
@@ -334,6 +702,67 @@ impl<'a> WsClient<'a> {
         (write, read)
     }
 
+    /// # 订阅消息，建立连接失败或连接断开后间隔`WS_RECONNECT_INTERVAL_MILLIS`重新连接并重新订阅
+    ///
+    /// 需要在tokio运行时中调用
+    ///
+    /// ## Parameters
+    /// + `message: &str`: 订阅消息，示例：`JsonRpcBody::new_ws_transaction_block()`
+    ///
+    /// ## Returns
+    /// + `impl Stream<Item = String>`: 收到的文本消息，流被丢弃后不再重连
+    pub fn subscribe(&self, message: &str) -> impl Stream<Item = String> {
+        self.subscribe_with_handle(message).0
+    }
+
+    /// # 订阅消息，同时返回后台订阅任务的句柄，通过句柄停止任务后不再重连，消息流随之结束
+    ///
+    /// 需要在tokio运行时中调用
+    ///
+    /// ## Parameters
+    /// + `message: &str`: 订阅消息，示例：`JsonRpcBody::new_ws_transaction_block()`
+    ///
+    /// ## Returns
+    /// + `(impl Stream<Item = String>, AbortHandle)`: 收到的文本消息和后台订阅任务的句柄
+    pub fn subscribe_with_handle(&self, message: &str) -> (impl Stream<Item = String>, AbortHandle) {
+        let url = self.get_ws_conn_url();
+        let message = message.to_string();
+        let (sender, receiver) = mpsc::channel(WS_CHANNEL_CAPACITY);
+        let task = tokio::spawn(async move {
+            while !sender.is_closed() {
+                match connect_async(url.as_str()).await {
+                    Ok((ws_stream, _)) => {
+                        let (mut write, mut read) = ws_stream.split();
+                        match write.send(Message::Text(message.clone())).await {
+                            Ok(_) => {
+                                while let Some(msg) = read.next().await {
+                                    match msg {
+                                        Ok(Message::Text(text)) => {
+                                            if sender.send(text).await.is_err() {
+                                                return;
+                                            }
+                                        }
+                                        Ok(Message::Close(_)) => break,
+                                        Ok(_) => {}
+                                        Err(e) => {
+                                            error!("接收websocket消息失败，{}", e);
+                                            break;
+                                        }
+                                    }
+                                }
+                            }
+                            Err(e) => error!("发送订阅消息失败，{}", e),
+                        }
+                    }
+                    Err(e) => error!("建立websocket连接{}失败，{}", url, e),
+                }
+                tokio::time::sleep(Duration::from_millis(WS_RECONNECT_INTERVAL_MILLIS)).await;
+            }
+        });
+        let stream = stream::unfold(receiver, |mut receiver| async move { receiver.recv().await.map(|msg| (msg, receiver)) });
+        (stream, task.abort_handle())
+    }
+
     /// # 接收消息流
     /// ## Parameters
     /// + `mut read: WsRead`
@@ -405,16 +834,94 @@ impl<'a> WsRequest for WsClient<'a> {
 
 #[cfg(test)]
 mod tests {
+    use std::collections::HashMap;
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+    use std::sync::{Arc, Mutex};
+    use std::thread;
     use std::time::Duration;
 
+    use flate2::write::GzEncoder;
+    use flate2::Compression;
+    use serde_json::json;
     use tokio::sync::mpsc;
 
+    use crypto::transaction::TxType;
+    use crypto::Transaction;
     use model::common::Address;
+    use model::{Curve, Error};
 
-    use crate::client::{HttpClient, JsonRpcBody, WsClient, WsRequest};
+    use crate::client::{AsyncHttpClient, HttpClient, HttpRequest, JsonRpcBody, WsClient, WsRequest};
+    use crate::mock::mock_node;
 
     const CHAIN_ID: u64 = 1;
 
+    #[test]
+    fn test_max_response_size() {
+        let mut client = mock_node(|_, params| json!("a".repeat(params[0].as_u64().unwrap() as usize)));
+        client.set_max_response_size(1024);
+        let request = |len: u64| serde_json::to_string(&JsonRpcBody::new("latc_echo".to_string(), vec![json!(len)])).unwrap();
+
+        let response = client.send(&request(10), HttpClient::new_headers(CHAIN_ID)).unwrap();
+        assert!(response.contains(&"a".repeat(10)));
+        let err = client.send(&request(2048), HttpClient::new_headers(CHAIN_ID)).unwrap_err();
+        assert!(err.to_string().contains("超过了最大长度1024"), "{}", err);
+    }
+
+    #[test]
+    fn test_unexpected_response_body() {
+        struct HtmlTransport(String);
+
+        impl HttpRequest for HtmlTransport {
+            fn send(&self, _message: &str, _headers: HashMap<String, String>) -> Result<String, Error> {
+                Ok(self.0.clone())
+            }
+        }
+
+        let mut client = HttpClient::new("127.0.0.1", 0);
+        client.set_transport(Arc::new(HtmlTransport(String::from("<html>502 Bad Gateway</html>"))));
+        let err = client.get_latest_daemon_block(CHAIN_ID).unwrap_err();
+        assert!(err.to_string().contains("<html>502 Bad Gateway</html>"), "{}", err);
+
+        client.set_transport(Arc::new(HtmlTransport("<html>".repeat(1000))));
+        let err = client.get_latest_daemon_block(CHAIN_ID).unwrap_err();
+        assert!(err.to_string().contains("(共6000字节)"), "{}", err);
+        assert!(err.to_string().len() < 1000);
+    }
+
+    #[test]
+    fn test_send_raw_json() {
+        struct RecordingTransport(Mutex<Vec<String>>);
+
+        impl HttpRequest for RecordingTransport {
+            fn send(&self, message: &str, _headers: HashMap<String, String>) -> Result<String, Error> {
+                self.0.lock().unwrap().push(message.to_string());
+                Ok(json!({"jsonRpc": "2.0", "id": 1, "result": "0x01"}).to_string())
+            }
+        }
+
+        let mut transaction = crypto::Transaction::empty_tx();
+        transaction.height = 1;
+        transaction.tx_type = TxType::Send;
+        transaction.owner = String::from("zltc_Z1pnS94bP4hQSYLs4aP4UwBP9pH8bEvhi");
+        transaction.linker = Some(String::from("zltc_Z1pnS94bP4hQSYLs4aP4UwBP9pH8bEvhi"));
+        transaction.parent_hash = String::from("0x0000000000000000000000000000000000000000000000000000000000000000");
+        transaction.daemon_hash = String::from("0x0000000000000000000000000000000000000000000000000000000000000000");
+        transaction.sign(CHAIN_ID, &[0x23; 32], Curve::Sm2p256v1);
+
+        let transport = Arc::new(RecordingTransport(Mutex::new(vec![])));
+        let mut client = HttpClient::new("127.0.0.1", 0);
+        client.set_transport(transport.clone());
+        let raw_tx_json = serde_json::to_string(&transaction.clone().to_raw_tx()).unwrap();
+        assert_eq!("0x01", client.send_raw_json(CHAIN_ID, &raw_tx_json).unwrap());
+        assert_eq!("0x01", client.send_raw_tx(CHAIN_ID, transaction).unwrap());
+        let messages = transport.0.lock().unwrap().clone();
+        assert_eq!(messages[0], messages[1]);
+
+        assert!(client.send_raw_json(CHAIN_ID, r#"{"number": 1}"#).is_err());
+        assert_eq!(2, transport.0.lock().unwrap().len());
+    }
+
     #[test]
     fn test_get_current_daemon_block() {
         let client = HttpClient::new("192.168.1.185", 13000);
@@ -478,4 +985,212 @@ mod tests {
 
     #[tokio::test]
     async fn test_monitor_daemon_block() {}
+
+    #[test]
+    fn test_gzip_response() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let server = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut buffer = Vec::new();
+            let mut chunk = [0u8; 1024];
+            let head = loop {
+                let n = stream.read(&mut chunk).unwrap();
+                buffer.extend_from_slice(&chunk[..n]);
+                let request = String::from_utf8_lossy(&buffer).to_string();
+                if let Some(index) = request.find("\r\n\r\n") {
+                    break request[..index].to_lowercase();
+                }
+            };
+            let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+            encoder.write_all(json!({"jsonRpc": "2.0", "id": 1, "result": "x".repeat(4096)}).to_string().as_bytes()).unwrap();
+            let body = encoder.finish().unwrap();
+            let header = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Encoding: gzip\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                body.len()
+            );
+            stream.write_all(header.as_bytes()).unwrap();
+            stream.write_all(&body).unwrap();
+            head
+        });
+
+        let client = HttpClient::new("127.0.0.1", port);
+        let body = JsonRpcBody::new("latc_echo".to_string(), vec![]);
+        let result: String = client.send_json_rpc_request(&body, HashMap::new()).unwrap();
+        assert_eq!("x".repeat(4096), result);
+
+        let head = server.join().unwrap();
+        let accept_encoding = head.lines().find(|line| line.starts_with("accept-encoding:")).unwrap();
+        assert!(accept_encoding.contains("gzip"));
+        assert!(accept_encoding.contains("deflate"));
+        assert!(accept_encoding.contains("br"));
+    }
+
+    #[test]
+    fn test_send_batch() {
+        // 倒序返回响应，第二个请求返回错误
+        struct BatchTransport;
+
+        impl HttpRequest for BatchTransport {
+            fn send(&self, message: &str, _headers: HashMap<String, String>) -> Result<String, Error> {
+                let requests: Vec<serde_json::Value> = serde_json::from_str(message).unwrap();
+                let responses: Vec<serde_json::Value> = requests
+                    .iter()
+                    .rev()
+                    .map(|request| match request["id"].as_u64().unwrap() {
+                        2 => json!({"jsonRpc": "2.0", "id": 2, "error": {"code": -32000, "message": "receipt not found"}}),
+                        id => json!({"jsonRpc": "2.0", "id": id, "result": request["params"][0]}),
+                    })
+                    .collect();
+                Ok(serde_json::to_string(&responses).unwrap())
+            }
+        }
+
+        let mut client = HttpClient::new("127.0.0.1", 0);
+        client.set_transport(Arc::new(BatchTransport));
+        let bodies = (0..3)
+            .map(|i| JsonRpcBody::new("latc_getReceipt".to_string(), vec![json!(format!("0x0{}", i))]))
+            .collect();
+        let responses = client.send_batch(CHAIN_ID, bodies).unwrap();
+
+        assert_eq!(responses.iter().map(|r| r.id()).collect::<Vec<_>>(), vec![1, 2, 3]);
+        let results: Vec<_> = responses.into_iter().map(|r| r.into_result()).collect();
+        assert_eq!(results[0].as_ref().unwrap(), &json!("0x00"));
+        assert!(results[1].as_ref().unwrap_err().to_string().contains("receipt not found"));
+        assert_eq!(results[2].as_ref().unwrap(), &json!("0x02"));
+        assert!(client.send_batch(CHAIN_ID, vec![]).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_send_batch_missing_response() {
+        struct DroppingTransport;
+
+        impl HttpRequest for DroppingTransport {
+            fn send(&self, _message: &str, _headers: HashMap<String, String>) -> Result<String, Error> {
+                Ok(json!([{"jsonRpc": "2.0", "id": 1, "result": "0x01"}]).to_string())
+            }
+        }
+
+        let mut client = HttpClient::new("127.0.0.1", 0);
+        client.set_transport(Arc::new(DroppingTransport));
+        let bodies = vec![
+            JsonRpcBody::new("latc_getReceipt".to_string(), vec![json!("0x01")]),
+            JsonRpcBody::new("latc_getReceipt".to_string(), vec![json!("0x02")]),
+        ];
+        let err = client.send_batch(CHAIN_ID, bodies).unwrap_err();
+        assert!(err.to_string().contains("缺少ID为2的响应"), "{}", err);
+    }
+
+    #[test]
+    fn test_send_batch_duplicate_response() {
+        struct DuplicatingTransport;
+
+        impl HttpRequest for DuplicatingTransport {
+            fn send(&self, _message: &str, _headers: HashMap<String, String>) -> Result<String, Error> {
+                Ok(json!([
+                    {"jsonRpc": "2.0", "id": 1, "result": "0x01"},
+                    {"jsonRpc": "2.0", "id": 1, "result": "0x02"},
+                    {"jsonRpc": "2.0", "id": 2, "result": "0x02"}
+                ])
+                .to_string())
+            }
+        }
+
+        let mut client = HttpClient::new("127.0.0.1", 0);
+        client.set_transport(Arc::new(DuplicatingTransport));
+        let bodies = vec![
+            JsonRpcBody::new("latc_getReceipt".to_string(), vec![json!("0x01")]),
+            JsonRpcBody::new("latc_getReceipt".to_string(), vec![json!("0x02")]),
+        ];
+        let err = client.send_batch(CHAIN_ID, bodies).unwrap_err();
+        assert!(err.to_string().contains("重复的ID1"), "{}", err);
+    }
+
+    #[test]
+    fn test_async_http_client() {
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let recorder = requests.clone();
+        let http_client = mock_node(move |method, params| {
+            recorder.lock().unwrap().push((method.to_string(), params.clone()));
+            match method {
+                "latc_getCurrentTBDB" => json!({
+                    "currentTBlockNumber": 3,
+                    "currentTBlockHash": "0x616bf03baa685df9fddeff4701f170b30176e54120df726142a534f8f2b51873",
+                    "currentDBlockHash": "0xe8df1f1e250cd0eac75eee3f8733e26e9422ef5ea88650ab54498cd8e4928144"
+                }),
+                "wallet_sendRawTBlock" => json!("0x616bf03baa685df9fddeff4701f170b30176e54120df726142a534f8f2b51873"),
+                _ => json!({
+                    "contractAddress": "zltc_QLbz7JHiBTspS962RLKV8GndWFwjA5K66",
+                    "contractRet": "0x2a",
+                    "dblockHash": "0xe8df1f1e250cd0eac75eee3f8733e26e9422ef5ea88650ab54498cd8e4928144",
+                    "dblockNumber": 7,
+                    "jouleUsed": 0,
+                    "receiptIndex": 0,
+                    "success": true,
+                    "tblockHash": "0x616bf03baa685df9fddeff4701f170b30176e54120df726142a534f8f2b51873",
+                    "version": 1
+                }),
+            }
+        });
+        let client = AsyncHttpClient::from(&http_client);
+        let addr = Address::new("zltc_Z1pnS94bP4hQSYLs4aP4UwBP9pH8bEvhi");
+        let mut tx = Transaction::empty_tx();
+        tx.height = 1;
+        tx.tx_type = TxType::Send;
+        tx.owner = addr.to_zltc_address();
+        tx.linker = Some(String::from("zltc_QLbz7JHiBTspS962RLKV8GndWFwjA5K66"));
+
+        // 阻塞的mock节点在运行时之外创建，运行时内只使用异步客户端
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let hash = runtime.block_on(async {
+            let block = client.get_latest_block(CHAIN_ID, &addr).await.unwrap();
+            assert_eq!(block.height, 3);
+            let hash = client.send_raw_tx(CHAIN_ID, tx.clone()).await.unwrap();
+            assert_eq!(hash, block.hash);
+            let receipt = client.pre_call_contract(CHAIN_ID, tx.clone()).await.unwrap();
+            assert_eq!(receipt.contract_return(), "0x2a");
+            let receipt = client.get_receipt(CHAIN_ID, &hash).await.unwrap();
+            assert_eq!(receipt.dblock_height(), 7);
+            hash
+        });
+
+        // 同步和异步客户端发送的请求体相同
+        let async_requests = std::mem::take(&mut *requests.lock().unwrap());
+        http_client.get_latest_block(CHAIN_ID, &addr).unwrap();
+        http_client.send_raw_tx(CHAIN_ID, tx.clone()).unwrap();
+        http_client.pre_call_contract(CHAIN_ID, tx).unwrap();
+        http_client.get_receipt(CHAIN_ID, &hash).unwrap();
+        assert_eq!(async_requests.len(), 4);
+        assert_eq!(async_requests, *requests.lock().unwrap());
+    }
+
+    #[test]
+    fn test_async_http_client_max_response_size() {
+        let http_client = mock_node(|_, _| json!("a".repeat(2048)));
+        let mut client = AsyncHttpClient::from(&http_client);
+        client.set_max_response_size(1024);
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let err = runtime.block_on(client.get_receipt(CHAIN_ID, "0x01")).unwrap_err();
+        assert!(err.to_string().contains("超过了最大长度1024"), "{}", err);
+    }
+
+    #[test]
+    fn test_is_healthy_reuses_dial_within_window() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        listener.set_nonblocking(true).unwrap();
+        let port = listener.local_addr().unwrap().port();
+        // 每次探测都会建立一个新的连接，统计监听端口上已完成的连接数
+        let dials = || std::iter::from_fn(|| listener.accept().ok()).count();
+        let client = HttpClient::new("127.0.0.1", port);
+
+        assert!(client.is_healthy());
+        assert!(client.clone().is_healthy());
+        assert_eq!(dials(), 1);
+
+        let mut client = HttpClient::new("127.0.0.1", port);
+        client.set_health_check_window(Duration::ZERO);
+        assert!(client.is_healthy());
+        assert!(client.is_healthy());
+        assert_eq!(dials(), 2);
+    }
 }
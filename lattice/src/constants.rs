@@ -1,6 +1,20 @@
 /// json-rpc version number, fixed to 2.0
 pub const JSON_RPC_VERSION: &str = "2.0";
 /// 私钥的正则表达式校验
-pub(crate) const REGEX_PRIVATE_KEY: &str = r"^(0x)?[a-zA-Z0-9]{64}$";
+pub(crate) const REGEX_PRIVATE_KEY: &str = r"^(0x)?[0-9a-fA-F]{64}$";
 /// ZLTC地址的正则表达式校验
 pub(crate) const REGEX_ZLTC_ADDRESS: &str = r#"^zltc_[a-zA-Z0-9]{33}$"#;
+/// 等待交易回执时轮询的间隔，单位毫秒
+pub(crate) const RECEIPT_POLL_INTERVAL_MILLIS: u64 = 200;
+/// 等待多笔交易的回执时，同时查询回执的最大线程数
+pub(crate) const RECEIPT_QUERY_CONCURRENCY: usize = 8;
+/// websocket连接断开后重新连接的间隔，单位毫秒
+pub(crate) const WS_RECONNECT_INTERVAL_MILLIS: u64 = 1000;
+/// websocket订阅消息的缓冲区大小
+pub(crate) const WS_CHANNEL_CAPACITY: usize = 64;
+/// 幂等缓存最多记录的已发送交易数
+pub(crate) const IDEMPOTENCY_CACHE_CAPACITY: u64 = 1024;
+/// WIF私钥的版本字节，主网为0x80，测试网为0xef
+pub(crate) const WIF_VERSIONS: [u8; 2] = [0x80, 0xef];
+/// WIF私钥表示压缩公钥时，在私钥之后追加的标识字节
+pub(crate) const WIF_COMPRESSED_FLAG: u8 = 0x01;
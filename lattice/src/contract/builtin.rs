@@ -12,7 +12,7 @@ macro_rules! impl_builtin_contract {
 
         impl $builtin_contract {
             /// # 初始化一个内置合约
-            fn new() -> Self {
+            pub fn new() -> Self {
                 $builtin_contract {
                     abi: $abi.to_string(),
                     address: $address.to_string(),
@@ -26,10 +26,16 @@ macro_rules! impl_builtin_contract {
             /// + `args: Vec<Box<dyn std::any::Any>>`
             ///
             /// ## 出参
-            /// + `String`: data
-            fn encode_args(&self, fn_name: &str, args: Vec<Box<dyn std::any::Any>>) -> String {
+            /// + `Result<String, model::Error>`: data
+            fn encode_args(&self, fn_name: &str, args: Vec<Box<dyn std::any::Any>>) -> Result<String, model::Error> {
                 let abi = abi::Abi::new(&self.abi);
-                abi.encode(fn_name, args)
+                abi.try_encode_hex(fn_name, args)
+            }
+        }
+
+        impl Default for $builtin_contract {
+            fn default() -> Self {
+                Self::new()
             }
         }
     };
@@ -1,5 +1,5 @@
 use model::convert::string_to_bytes32_array;
-use model::HexString;
+use model::{Error, HexString};
 
 use crate::impl_builtin_contract;
 
@@ -203,10 +203,89 @@ impl LedgerBuiltinContract {
     /// + `trade_number`: 行业号
     ///
     /// ## 出参
-    /// + `String`: encoded code
-    pub fn create_protocol(&self, trade_number: u64, proto: &str) -> String {
+    /// + `Result<String, Error>`: encoded code
+    pub fn create_protocol(&self, trade_number: u64, proto: &str) -> Result<String, Error> {
         self.encode_args("addProtocol", vec![Box::new(trade_number.to_string()), Box::new(string_to_bytes32_array(proto))])
     }
+
+    /// # 更新协议
+    ///
+    /// ## 入参
+    /// + `protocol_uri: u64`: 协议号
+    /// + `proto: &str`: 协议内容
+    ///
+    /// ## 出参
+    /// + `Result<String, Error>`: encoded code
+    pub fn update_protocol(&self, protocol_uri: u64, proto: &str) -> Result<String, Error> {
+        self.encode_args("updateProtocol", vec![Box::new(protocol_uri.to_string()), Box::new(string_to_bytes32_array(proto))])
+    }
+
+    /// # 写入溯源数据
+    ///
+    /// ## 入参
+    /// + `protocol_uri: u64`: 协议号
+    /// + `hash: &str`: 溯源数据的哈希，例如文档的摘要
+    /// + `data: &str`: 溯源数据
+    /// + `address: &str`: 业务合约地址
+    ///
+    /// ## 出参
+    /// + `Result<String, Error>`: encoded code
+    pub fn write_traceability(&self, protocol_uri: u64, hash: &str, data: &str, address: &str) -> Result<String, Error> {
+        self.encode_args("writeTraceability", vec![
+            Box::new(protocol_uri.to_string()),
+            Box::new(hash.to_string()),
+            Box::new(string_to_bytes32_array(data)),
+            Box::new(address.to_string()),
+        ])
+    }
+
+    /// # 读取溯源数据
+    ///
+    /// ## 入参
+    /// + `hash: &str`: 溯源数据的哈希
+    /// + `address: &str`: 业务合约地址
+    ///
+    /// ## 出参
+    /// + `Result<String, Error>`: encoded code
+    pub fn get_traceability(&self, hash: &str, address: &str) -> Result<String, Error> {
+        self.encode_args("getTraceability", vec![Box::new(hash.to_string()), Box::new(address.to_string())])
+    }
+
+    /// # 批量写入溯源数据
+    ///
+    /// ## 入参
+    /// + `items: Vec<TraceabilityItem>`: 溯源数据
+    ///
+    /// ## 出参
+    /// + `Result<String, Error>`: encoded code
+    pub fn write_traceability_batch(&self, items: Vec<TraceabilityItem>) -> Result<String, Error> {
+        let items: Vec<Vec<Box<dyn std::any::Any>>> = items
+            .into_iter()
+            .map(|item| {
+                let fields: Vec<Box<dyn std::any::Any>> = vec![
+                    Box::new(item.protocol_uri.to_string()),
+                    Box::new(item.hash),
+                    Box::new(string_to_bytes32_array(&item.data)),
+                    Box::new(item.address),
+                ];
+                fields
+            })
+            .collect();
+        self.encode_args("writeTraceabilityBatch", vec![Box::new(items)])
+    }
+}
+
+/// 一条溯源数据
+#[derive(Debug, Clone)]
+pub struct TraceabilityItem {
+    /// 协议号
+    pub protocol_uri: u64,
+    /// 溯源数据的哈希
+    pub hash: String,
+    /// 溯源数据
+    pub data: String,
+    /// 业务合约地址
+    pub address: String,
 }
 
 #[cfg(test)]
@@ -224,8 +303,30 @@ mod test {
     #[test]
     fn test_create_protocol() {
         let contract = LedgerBuiltinContract::new();
-        let actual = contract.create_protocol(1, "syntax = \"proto3\";\n\nmessage Student {\n\tstring id = 1;\n\tstring name = 2;\n}");
+        let actual = contract.create_protocol(1, "syntax = \"proto3\";\n\nmessage Student {\n\tstring id = 1;\n\tstring name = 2;\n}").unwrap();
         let expected = "0xef7e985800000000000000000000000000000000000000000000000000000000000000010000000000000000000000000000000000000000000000000000000000000040000000000000000000000000000000000000000000000000000000000000000373796e746178203d202270726f746f33223b0a0a6d6573736167652053747564656e74207b0a09737472696e67206964203d20313b0a09737472696e67206e616d65203d20323b0a7d0000000000000000000000000000000000000000000000";
         assert_eq!(expected, actual);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_write_traceability() {
+        let contract = LedgerBuiltinContract::new();
+        let actual = contract.write_traceability(1, "0x01", "hello", "zltc_Z1pnS94bP4hQSYLs4aP4UwBP9pH8bEvhi").unwrap();
+        let expected = "0x4131ff530000000000000000000000000000000000000000000000000000000000000001000000000000000000000000000000000000000000000000000000000000008000000000000000000000000000000000000000000000000000000000000000c00000000000000000000000005f2be9a02b43f748ee460bf36eed24fafa10992000000000000000000000000000000000000000000000000000000000000000043078303100000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000168656c6c6f000000000000000000000000000000000000000000000000000000";
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn test_write_traceability_batch() {
+        let contract = LedgerBuiltinContract::new();
+        let item = TraceabilityItem {
+            protocol_uri: 1,
+            hash: String::from("0x01"),
+            data: String::from("hello"),
+            address: String::from("zltc_Z1pnS94bP4hQSYLs4aP4UwBP9pH8bEvhi"),
+        };
+        let actual = contract.write_traceability_batch(vec![item]).unwrap();
+        let expected = "0x77b34b730000000000000000000000000000000000000000000000000000000000000020000000000000000000000000000000000000000000000000000000000000000100000000000000000000000000000000000000000000000000000000000000200000000000000000000000000000000000000000000000000000000000000001000000000000000000000000000000000000000000000000000000000000008000000000000000000000000000000000000000000000000000000000000000c00000000000000000000000005f2be9a02b43f748ee460bf36eed24fafa10992000000000000000000000000000000000000000000000000000000000000000043078303100000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000168656c6c6f000000000000000000000000000000000000000000000000000000";
+        assert_eq!(expected, actual);
+    }
+}
@@ -1,4 +1,5 @@
-use crate::builder::TransactionBuilder;
+use model::Error;
+
 use crate::impl_builtin_contract;
 
 /// 内置的投票合约
@@ -79,8 +80,8 @@ impl ProposalBuiltinContract {
     /// + `approve: bool`: false:反对票、true:同意票
     ///
     /// ## 出参
-    /// + `String`: encoded data
-    pub fn vote(&self, proposal_id: &str, approve: bool) -> String {
+    /// + `Result<String, Error>`: encoded data
+    pub fn vote(&self, proposal_id: &str, approve: bool) -> Result<String, Error> {
         let approve: String = if approve { String::from("1") } else { String::from("0") };
         let data = self.encode_args("vote", vec![Box::new(proposal_id.to_string()), Box::new(approve)]);
         data
@@ -92,8 +93,8 @@ impl ProposalBuiltinContract {
     /// + `proposal_id: &str`: 提案ID
     ///
     /// ## 出参
-    /// + `String`: encoded data
-    pub fn cancel(&self, proposal_id: &str) -> String {
+    /// + `Result<String, Error>`: encoded data
+    pub fn cancel(&self, proposal_id: &str) -> Result<String, Error> {
         let data = self.encode_args("cancel", vec![Box::new(proposal_id.to_string())]);
         data
     }
@@ -104,8 +105,8 @@ impl ProposalBuiltinContract {
     /// + `proposal_id: &str`: 提案ID
     ///
     /// ## 出参
-    /// + `String`: encoded data
-    pub fn refresh(&self, proposal_id: &str) -> String {
+    /// + `Result<String, Error>`: encoded data
+    pub fn refresh(&self, proposal_id: &str) -> Result<String, Error> {
         let data = self.encode_args("refresh", vec![Box::new(proposal_id.to_string())]);
         data
     }
@@ -119,21 +120,21 @@ mod test {
 
     #[test]
     fn test_encode_vote() {
-        let data = ProposalBuiltinContract::new().vote(PROPOSAL_ID, true);
+        let data = ProposalBuiltinContract::new().vote(PROPOSAL_ID, true).unwrap();
         let expect_data = "0x90ca27f300000000000000000000000000000000000000000000000000000000000000400000000000000000000000000000000000000000000000000000000000000001000000000000000000000000000000000000000000000000000000000000003c30783031323632396166343361326537636630323463646165623863313038303738623362363261396631373133303030303030303030303030303000000000";
         assert_eq!(data, expect_data)
     }
 
     #[test]
     fn test_encode_cancel() {
-        let data = ProposalBuiltinContract::new().cancel(PROPOSAL_ID);
+        let data = ProposalBuiltinContract::new().cancel(PROPOSAL_ID).unwrap();
         let expect_data = "0x0b4f3f3d0000000000000000000000000000000000000000000000000000000000000020000000000000000000000000000000000000000000000000000000000000003c30783031323632396166343361326537636630323463646165623863313038303738623362363261396631373133303030303030303030303030303000000000";
         assert_eq!(data, expect_data);
     }
 
     #[test]
     fn test_encode_refresh() {
-        let data = ProposalBuiltinContract::new().refresh(PROPOSAL_ID);
+        let data = ProposalBuiltinContract::new().refresh(PROPOSAL_ID).unwrap();
         let expect_data = "0x6de8a6090000000000000000000000000000000000000000000000000000000000000020000000000000000000000000000000000000000000000000000000000000003c30783031323632396166343361326537636630323463646165623863313038303738623362363261396631373133303030303030303030303030303000000000";
         assert_eq!(data, expect_data);
     }
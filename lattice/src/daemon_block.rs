@@ -0,0 +1,111 @@
+use futures_util::stream::{self, Stream, StreamExt};
+use serde_json::Value;
+
+use model::block::DBlock;
+use model::Error;
+
+/// # 将守护区块的订阅消息转换为按高度递增、不重复的守护区块
+///
+/// 记录已产生的最大高度，高度不大于它的区块视为重复并丢弃（例如websocket重连前后各收到一次的区块），
+/// 订阅确认等不含区块的消息会被忽略。收到的区块高度跳跃时（例如重连期间产生的区块）先产生一个`Err`说明缺失的高度，再产生该区块
+///
+/// ## 入参
+/// + `notifications: S`: `newDBlock`订阅收到的消息
+///
+/// ## 出参
+/// + `impl Stream<Item = Result<DBlock, Error>>`: 高度跳跃时产生`Err`，不会中断订阅
+pub(crate) fn deduplicated_daemon_blocks<S>(notifications: S) -> impl Stream<Item = Result<DBlock, Error>>
+where
+    S: Stream<Item = String>,
+{
+    let mut last_height: Option<u64> = None;
+    notifications
+        .flat_map(move |notification| {
+            let block = match daemon_block_of(&notification) {
+                None => return stream::iter(vec![]),
+                Some(block) => block,
+            };
+            let last = last_height.replace(block.height);
+            match last {
+                Some(last) if block.height <= last => {
+                    last_height = Some(last);
+                    stream::iter(vec![])
+                }
+                Some(last) if block.height > last + 1 => {
+                    let e = Error::new(&format!(
+                        "守护区块高度从{}跳跃到{}，缺失了{}个区块",
+                        last, block.height, block.height - last - 1
+                    ));
+                    stream::iter(vec![Err(e), Ok(block)])
+                }
+                _ => stream::iter(vec![Ok(block)]),
+            }
+        })
+}
+
+/// # 从`newDBlock`的订阅消息中取出守护区块
+///
+/// 消息示例：`{"jsonrpc":"2.0","method":"latc_subscription","params":{"subscription":"0x1","result":{"hash":"0x...","number":1,...}}}`
+fn daemon_block_of(notification: &str) -> Option<DBlock> {
+    let value: Value = serde_json::from_str(notification).ok()?;
+    serde_json::from_value(value["params"]["result"].clone()).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use futures_util::stream::{self, StreamExt};
+    use serde_json::json;
+
+    use model::constants::ZERO_HASH_STRING;
+
+    use super::*;
+
+    fn notification(height: u64) -> String {
+        let block = json!({
+            "hash": format!("0x{:064x}", height),
+            "parentHash": ZERO_HASH_STRING,
+            "number": height,
+            "timestamp": 1700000000 + height,
+            "version": 1
+        });
+        json!({"jsonrpc": "2.0", "method": "latc_subscription", "params": {"subscription": "0x1", "result": block}}).to_string()
+    }
+
+    fn collect_blocks(notifications: Vec<String>) -> Vec<Result<DBlock, Error>> {
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        runtime.block_on(deduplicated_daemon_blocks(stream::iter(notifications)).collect())
+    }
+
+    #[test]
+    fn test_deduplicated_daemon_blocks() {
+        // 高度2之后连接断开，重连后高度2的区块又收到一次
+        let notifications = vec![
+            json!({"jsonrpc": "2.0", "id": 1, "result": "0x1"}).to_string(),
+            notification(1),
+            notification(2),
+            json!({"jsonrpc": "2.0", "id": 1, "result": "0x2"}).to_string(),
+            notification(2),
+            notification(1),
+            notification(3),
+        ];
+        let heights: Vec<u64> = collect_blocks(notifications)
+            .into_iter()
+            .map(|block| block.unwrap().height)
+            .collect();
+
+        assert_eq!(heights, vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn test_deduplicated_daemon_blocks_gap() {
+        // 重连期间产生了高度3和4的区块，重连后直接收到高度5
+        let blocks = collect_blocks(vec![notification(2), notification(5), notification(5), notification(6)]);
+
+        assert_eq!(blocks.len(), 4);
+        assert_eq!(blocks[0].as_ref().unwrap().height, 2);
+        let err = blocks[1].as_ref().unwrap_err();
+        assert!(err.to_string().contains("缺失了2个区块"), "{}", err);
+        assert_eq!(blocks[2].as_ref().unwrap().height, 5);
+        assert_eq!(blocks[3].as_ref().unwrap().height, 6);
+    }
+}
@@ -0,0 +1,232 @@
+use std::sync::Arc;
+
+use alloy_dyn_abi::DynSolValue;
+use futures_util::stream::{self, Stream, StreamExt};
+use serde_json::Value;
+
+use abi::Abi;
+use crypto::address::lattice_to_eth_with;
+use model::common::Address;
+use model::receipt::Event;
+use model::{Error, HexString};
+
+use crate::client::HttpClient;
+
+/// 解码后的合约事件
+#[derive(Debug, Clone, PartialEq)]
+pub struct DecodedEvent {
+    /// 事件名
+    pub name: String,
+    /// 产生事件的合约地址
+    pub address: String,
+    /// 产生事件的交易哈希
+    pub tblock_hash: String,
+    /// indexed参数的值
+    pub indexed: Vec<DynSolValue>,
+    /// 非indexed参数的值
+    pub body: Vec<DynSolValue>,
+}
+
+/// 按照合约地址和事件选择器过滤回执中的事件
+pub(crate) struct EventFilter {
+    /// 以太坊格式的合约地址，小写
+    address: String,
+    abi: String,
+    event_name: String,
+    /// 事件选择器，匿名事件为None
+    selector: Option<String>,
+}
+
+impl EventFilter {
+    /// # 创建事件过滤器
+    ///
+    /// ## 入参
+    /// + `address: &str`: 合约地址
+    /// + `abi: &str`: 合约abi
+    /// + `event_name: &str`: 事件名
+    ///
+    /// ## 出参
+    /// + `Result<EventFilter, Error>`: 合约地址不合法、abi不合法或事件不存在时返回`Err`
+    pub(crate) fn new(address: &str, abi: &str, event_name: &str) -> Result<Self, Error> {
+        let event = Abi::new(abi).event(event_name)?;
+        Ok(EventFilter {
+            address: normalize_address(address)?,
+            abi: abi.to_string(),
+            event_name: event_name.to_string(),
+            selector: (!event.anonymous).then(|| normalize_topic(&event.selector().to_string())),
+        })
+    }
+
+    /// 地址不合法的事件视为不匹配
+    fn matches(&self, event: &Event) -> bool {
+        if normalize_address(event.address()).ok().as_ref() != Some(&self.address) {
+            return false;
+        }
+        match &self.selector {
+            None => true,
+            Some(selector) => event.topics().first().is_some_and(|topic| normalize_topic(topic) == *selector),
+        }
+    }
+
+    fn decode(&self, tblock_hash: &str, event: &Event) -> Result<DecodedEvent, Error> {
+        let decoded = Abi::new(&self.abi).decode_event(&self.event_name, event.topics(), &event.data()?)?;
+        Ok(DecodedEvent {
+            name: self.event_name.clone(),
+            address: event.address().to_string(),
+            tblock_hash: tblock_hash.to_string(),
+            indexed: decoded.indexed,
+            body: decoded.body,
+        })
+    }
+}
+
+/// # 转为小写的以太坊地址，非Lattice格式的地址原样保留
+fn normalize_address(address: &str) -> Result<String, Error> {
+    let address = Address::new(address);
+    if !address.addr.starts_with(address.config().title.as_str()) {
+        return Ok(address.addr.to_lowercase());
+    }
+    Ok(format!("0x{}", lattice_to_eth_with(&address.addr, address.config())?).to_lowercase())
+}
+
+fn normalize_topic(topic: &str) -> String {
+    HexString::new(topic).clean_hex_string().to_lowercase()
+}
+
+/// # 将账户区块的订阅消息转换为解码后的合约事件
+///
+/// 对每个新的账户区块查询回执，按照过滤器筛选并解码其中的事件，订阅确认等不含区块哈希的消息会被忽略
+///
+/// ## 入参
+/// + `notifications: S`: `newTBlock`订阅收到的消息
+/// + `http_client: HttpClient`: 查询回执的客户端
+/// + `chain_id: u64`: 链ID
+/// + `filter: EventFilter`: 事件过滤器
+///
+/// ## 出参
+/// + `impl Stream<Item = Result<DecodedEvent, Error>>`: 查询回执或解码失败时产生`Err`，不会中断订阅
+pub(crate) fn decoded_events<S>(
+    notifications: S,
+    http_client: HttpClient,
+    chain_id: u64,
+    filter: EventFilter,
+) -> impl Stream<Item = Result<DecodedEvent, Error>>
+where
+    S: Stream<Item = String>,
+{
+    let filter = Arc::new(filter);
+    notifications
+        .then(move |notification| {
+            let http_client = http_client.clone();
+            let filter = filter.clone();
+            async move {
+                let hash = match tblock_hash_of(&notification) {
+                    None => return vec![],
+                    Some(hash) => hash,
+                };
+                let receipt = tokio::task::spawn_blocking(move || http_client.get_receipt(chain_id, &hash))
+                    .await
+                    .map_err(|e| Error::new(&format!("查询回执的任务失败，{}", e)).with_source(e))
+                    .and_then(|receipt| receipt);
+                match receipt {
+                    Err(e) => vec![Err(e)],
+                    Ok(receipt) => receipt
+                        .events()
+                        .iter()
+                        .filter(|event| filter.matches(event))
+                        .map(|event| filter.decode(receipt.tblock_hash(), event))
+                        .collect(),
+                }
+            }
+        })
+        .flat_map(stream::iter)
+}
+
+/// # 从`newTBlock`的订阅消息中取出账户区块哈希
+///
+/// 消息示例：`{"jsonrpc":"2.0","method":"latc_subscription","params":{"subscription":"0x1","result":{"hash":"0x..."}}}`
+fn tblock_hash_of(notification: &str) -> Option<String> {
+    let value: Value = serde_json::from_str(notification).ok()?;
+    value["params"]["result"]["hash"].as_str().map(str::to_string)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use alloy_primitives::U256;
+    use futures_util::stream::{self, StreamExt};
+    use serde_json::json;
+
+    use abi::Abi;
+
+    use crate::client::HttpClient;
+    use crate::mock_node::MockNode;
+
+    use super::*;
+
+    const CONTRACT_ADDRESS: &str = "zltc_QLbz7JHiBTspUvTPzLHy5biDS9mu53mmv";
+    const EVENT_ABI: &str = r#"[{"anonymous": false, "inputs": [{"indexed": true, "internalType": "uint64", "name": "protocolUri", "type": "uint64"}, {"indexed": false, "internalType": "address", "name": "updater", "type": "address"}], "name": "ProtocolAdded", "type": "event"}]"#;
+    const TBLOCK_HASH: &str = "0x616bf03baa685df9fddeff4701f170b30176e54120df726142a534f8f2b51873";
+
+    #[test]
+    fn test_decoded_events() {
+        let selector = Abi::new(EVENT_ABI).event("ProtocolAdded").unwrap().selector().to_string();
+        let event = |address: &str| {
+            json!({
+                "address": address,
+                "topics": [selector, format!("0x{:064x}", 7)],
+                "data": "0x0000000000000000000000005f2be9a02b43f748ee460bf36eed24fafa109920",
+                "logIndex": 0,
+                "dblockNumber": 1,
+                "removed": false,
+                "dataHex": "0x0000000000000000000000005f2be9a02b43f748ee460bf36eed24fafa109920"
+            })
+        };
+        let receipt = json!({
+            "contractAddress": CONTRACT_ADDRESS,
+            "contractRet": "0x",
+            "dblockHash": TBLOCK_HASH,
+            "dblockNumber": 1,
+            // 其它合约产生的同名事件会被过滤
+            // 地址不合法的事件会被跳过
+            "events": [event(CONTRACT_ADDRESS), event("zltc_Z1pnS94bP4hQSYLs4aP4UwBP9pH8bEvhi"), event("zltc_Z1pnS94bP4hQSYLs4aP4UwBP9pH8bEvhX")],
+            "jouleUsed": 0,
+            "receiptIndex": 0,
+            "success": true,
+            "tblockHash": TBLOCK_HASH,
+            "version": 3
+        });
+        let node = Arc::new(MockNode::new());
+        node.register("latc_getReceipt", move |params| {
+            assert_eq!(params[0], TBLOCK_HASH);
+            receipt.clone()
+        });
+        let mut http_client = HttpClient::new("127.0.0.1", 0);
+        http_client.set_transport(node);
+
+        let notifications = stream::iter(vec![
+            json!({"jsonrpc": "2.0", "id": 1, "result": "0x1"}).to_string(),
+            json!({"jsonrpc": "2.0", "method": "latc_subscription", "params": {"subscription": "0x1", "result": {"hash": TBLOCK_HASH}}}).to_string(),
+        ]);
+        let filter = EventFilter::new(CONTRACT_ADDRESS, EVENT_ABI, "ProtocolAdded").unwrap();
+        // 阻塞的HttpClient在运行时之外创建和释放，运行时内只持有它的克隆
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let events: Vec<_> = runtime.block_on(decoded_events(notifications, http_client.clone(), 1, filter).collect());
+        drop(runtime);
+
+        assert_eq!(1, events.len());
+        let event = events[0].as_ref().unwrap();
+        assert_eq!("ProtocolAdded", event.name);
+        assert_eq!(CONTRACT_ADDRESS, event.address);
+        assert_eq!(TBLOCK_HASH, event.tblock_hash);
+        assert_eq!(vec![DynSolValue::Uint(U256::from(7), 64)], event.indexed);
+        assert_eq!(1, event.body.len());
+    }
+
+    #[test]
+    fn test_event_filter_unknown_event() {
+        assert!(EventFilter::new(CONTRACT_ADDRESS, EVENT_ABI, "ProtocolRemoved").is_err());
+        assert!(EventFilter::new("zltc_Z1pnS94bP4hQSYLs4aP4UwBP9pH8bEvhX", EVENT_ABI, "ProtocolAdded").is_err());
+    }
+}
@@ -1,23 +1,43 @@
-use std::sync::Arc;
-use std::time::Duration;
+use std::any::Any;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::fmt;
+use std::io::Write;
+use std::time::{Duration, Instant};
 
+use abi::Abi;
+use alloy_dyn_abi::DynSolValue;
+use alloy_primitives::hex;
+use futures_util::Stream;
 use log::{debug, error};
+use moka::sync::Cache;
 use regex::Regex;
+use tokio::task::AbortHandle;
 
+use crypto::address::predict_contract_address;
+use crypto::base58check;
+use crypto::sign::KeyPair;
 use crypto::Transaction;
-use model::block::LatestBlock;
-use model::constants::{PREFIX_OF_HEX, ZERO_HASH_STRING, ZERO_ZLTC_ADDRESS};
+use model::block::{DBlock, LatestBlock};
+use model::constants::{PREFIX_OF_HEX, PRIVATE_KEY_LENGTH, ZERO_HASH_STRING, ZERO_ZLTC_ADDRESS};
+use model::common::Address;
 use model::receipt::Receipt;
-use model::{Curve, Error, HexString};
+use model::{Curve, Error, ErrorKind, HexString};
 use wallet::file_key::FileKey;
 
 use crate::account_cache::{AccountCacheTrait, DefaultAccountCache};
 use crate::account_lock::{AccountLockTrait, DefaultAccountLock};
 use crate::builder::{
-    CallContractBuilder, DeployContractBuilder, TransactionBuilder, TransferBuilder,
+    CallContractBuilder, DeployContractBuilder, ReceiveBuilder, TransactionBuilder,
+    TransferBuilder,
 };
-use crate::client::HttpClient;
-use crate::constants::REGEX_PRIVATE_KEY;
+use crate::client::{HttpClient, JsonRpcBody, WsClient};
+use crate::daemon_block::deduplicated_daemon_blocks;
+use crate::constants::{
+    IDEMPOTENCY_CACHE_CAPACITY, RECEIPT_POLL_INTERVAL_MILLIS, RECEIPT_QUERY_CONCURRENCY, REGEX_PRIVATE_KEY, WIF_COMPRESSED_FLAG, WIF_VERSIONS,
+};
+use crate::event::{decoded_events, DecodedEvent, EventFilter};
 
 /// 链配置
 #[derive(Debug, Clone, Copy)]
@@ -40,6 +60,38 @@ pub struct ConnectingNodeConfig {
 }
 
 impl ConnectingNodeConfig {
+    /// # 从连接地址解析节点配置
+    ///
+    /// ## 入参
+    /// + `url: &str`: 节点的http地址，示例：`http://node.example.com:13800`、`192.168.1.20:13800`，
+    ///   websocket端口可以通过查询参数指定，示例：`http://192.168.1.20:13800?ws=13801`
+    ///
+    /// ## 出参
+    /// + `Result<ConnectingNodeConfig, Error>`: 地址不合法、协议不是http(s)或端口不合法时返回`Err`，未指定websocket端口时为0
+    pub fn from_url(url: &str) -> Result<ConnectingNodeConfig, Error> {
+        let url = url.trim();
+        let with_scheme = if url.contains("://") { url.to_string() } else { format!("http://{}", url) };
+        let parsed = reqwest::Url::parse(&with_scheme)
+            .map_err(|e| Error::new(&format!("invalid node url {}", url)).with_source(e))?;
+        if parsed.scheme() != "http" && parsed.scheme() != "https" {
+            return Err(Error::new(&format!("unsupported scheme {} of node url {}", parsed.scheme(), url)));
+        }
+        let ip = parsed
+            .host_str()
+            .ok_or_else(|| Error::new(&format!("missing host of node url {}", url)))?
+            .to_string();
+        let http_port = parsed
+            .port_or_known_default()
+            .ok_or_else(|| Error::new(&format!("missing port of node url {}", url)))?;
+        let websocket_port = match parsed.query_pairs().find(|(k, _)| k == "ws") {
+            None => 0,
+            Some((_, v)) => v
+                .parse::<u16>()
+                .map_err(|e| Error::new(&format!("invalid websocket port {} of node url {}", v, url)).with_source(e))?,
+        };
+        Ok(ConnectingNodeConfig { ip, http_port, websocket_port })
+    }
+
     /// # 初始化一个节点的http客户端
     ///
     /// ## 入参
@@ -66,22 +118,55 @@ pub struct Credentials {
 }
 
 impl Credentials {
-    /// # 获取私钥
+    /// # 从WIF格式（base58check编码）的私钥导入凭证
+    ///
+    /// ## 入参
+    /// + `account_address: &str`: 账户地址，示例：zltc_Z1pnS94bP4hQSYLs4aP4UwBP9pH8bEvhi
+    /// + `wif: &str`: WIF格式的私钥，版本字节为0x80或0xef，可带压缩公钥的标识字节0x01
     ///
     /// ## 出参
-    /// + `String`: 示例，0x23d5b2a2eb0a9c8b86d62cbc3955cfd1fb26ec576ecc379f402d0f5d2b27a7bb
-    fn get_sk(&self) -> String {
-        let regex = Regex::new(REGEX_PRIVATE_KEY).unwrap();
-        if regex.is_match(&self.sk) {
-            return self.sk.clone();
-        } else {
-            let passphrase = self.passphrase.as_ref().expect("身份密码不能为空");
-            let file_key_json = self.file_key.as_ref().expect("FileKey不能为空");
-            let file_key = FileKey::new(file_key_json);
-            let keypair = file_key.decrypt(passphrase).unwrap();
-            let sk_bytes = keypair.secret_key.to_bytes_be();
-            HexString::from(&sk_bytes).hex_string
+    /// + `Result<Credentials, Error>`: base58check不合法、版本字节或长度不正确时返回`Err`
+    pub fn from_wif(account_address: &str, wif: &str) -> Result<Credentials, Error> {
+        let (version, payload) = base58check::decode(wif.trim(), 1, base58check::DEFAULT_CHECKSUM_LENGTH)?;
+        if !WIF_VERSIONS.contains(&version[0]) {
+            return Err(Error::new(&format!("WIF私钥的版本字节【0x{:02x}】不正确", version[0])));
         }
+        let sk = match payload.as_slice() {
+            [sk @ .., flag] if sk.len() == PRIVATE_KEY_LENGTH && *flag == WIF_COMPRESSED_FLAG => sk,
+            sk if sk.len() == PRIVATE_KEY_LENGTH => sk,
+            _ => return Err(Error::new(&format!("WIF私钥的长度【{}】不正确", payload.len()))),
+        };
+        Ok(Credentials {
+            account_address: account_address.to_string(),
+            sk: HexString::from(sk).hex_string,
+            passphrase: None,
+            file_key: None,
+        })
+    }
+
+    /// # 获取私钥，`sk`不为空时使用`sk`，否则使用身份密码解密FileKey
+    ///
+    /// ## 出参
+    /// + `Result<String, Error>`: 带0x前缀的私钥，示例：0x23d5b2a2eb0a9c8b86d62cbc3955cfd1fb26ec576ecc379f402d0f5d2b27a7bb；
+    ///   `sk`不是64位的hex字符串（可带0x前缀），或者`sk`为空且缺少身份密码、FileKey或解密失败时返回`Err`
+    fn get_sk(&self) -> Result<String, Error> {
+        let sk = self.sk.trim();
+        if !sk.is_empty() {
+            let regex = Regex::new(REGEX_PRIVATE_KEY).unwrap();
+            if !regex.is_match(sk) {
+                return Err(Error::new("私钥格式不正确，应为64位的hex字符串，可带0x前缀"));
+            }
+            return Ok(format!("{}{}", PREFIX_OF_HEX, sk.strip_prefix(PREFIX_OF_HEX).unwrap_or(sk)));
+        }
+        let (passphrase, file_key_json) = match (&self.passphrase, &self.file_key) {
+            (Some(passphrase), Some(file_key)) => (passphrase, file_key),
+            _ => return Err(Error::new("未设置私钥时，身份密码和FileKey都不能为空")),
+        };
+        let keypair = FileKey::new(file_key_json).decrypt(passphrase)?;
+        let bytes = keypair.secret_key.to_bytes_be();
+        let mut sk_bytes = [0u8; PRIVATE_KEY_LENGTH];
+        sk_bytes[PRIVATE_KEY_LENGTH - bytes.len()..].copy_from_slice(&bytes);
+        Ok(HexString::from(&sk_bytes).hex_string)
     }
 }
 
@@ -89,6 +174,7 @@ impl Credentials {
 pub struct RetryPolicy {}
 
 /// 重试类型枚举
+#[derive(Debug, Clone, Copy)]
 pub enum Retry {
     /// 退避算法
     BackOff,
@@ -117,14 +203,197 @@ pub struct LatticeClient {
 
     /// 账户缓存
     account_cache: Arc<dyn AccountCacheTrait + Sync + Send>,
+
+    /// 是否已经关闭
+    shutdown: AtomicBool,
+
+    /// websocket订阅任务，关闭时停止
+    subscriptions: Mutex<Vec<AbortHandle>>,
+
+    /// 幂等窗口内已发送的交易，key为调用方提供的幂等key，未启用时为None
+    submitted: Option<Cache<String, Submission>>,
+}
+
+/// 发送交易的结果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SendResult {
+    /// 交易哈希
+    pub hash: String,
+    /// 交易的高度
+    pub height: u64,
+    /// 交易的时间戳
+    pub timestamp: u64,
+}
+
+/// 调用合约的可选参数
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CallContractOptions<'a> {
+    /// 转账金额
+    pub amount: Option<u128>,
+    /// 交易的joule
+    pub joule: Option<u128>,
+    /// 交易的payload，为空时使用`0x`
+    pub payload: Option<&'a str>,
+}
+
+/// 幂等窗口内记录的交易
+#[derive(Debug, Clone)]
+enum Submission {
+    /// 已签名并开始发送，但还不确定节点是否收到，重试时重新发送同一笔交易
+    Pending(Box<Transaction>),
+    /// 节点已经接受的交易
+    Sent(SendResult),
+}
+
+/// 部署合约的结果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeployResult {
+    /// 交易哈希
+    pub hash: String,
+    /// 交易的高度
+    pub height: u64,
+    /// 预测的合约地址，可在交易上链前使用
+    pub predicted_address: String,
+}
+
+/// 导出回执的格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    /// 每行一个json对象
+    Jsonl,
+    /// 首行为表头的csv
+    Csv,
+}
+
+/// 等待交易回执超时，作为`Error`的`source`携带已经查询到的回执
+#[derive(Debug, Clone)]
+pub struct WaitReceiptsTimeout {
+    /// 与入参的交易哈希一一对应，未查询到的为None
+    pub receipts: Vec<Option<Receipt>>,
 }
 
+impl WaitReceiptsTimeout {
+    /// 未查询到回执的数量
+    pub fn pending(&self) -> usize {
+        self.receipts.iter().filter(|receipt| receipt.is_none()).count()
+    }
+}
+
+impl fmt::Display for WaitReceiptsTimeout {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} of {} receipts are still pending", self.pending(), self.receipts.len())
+    }
+}
+
+impl std::error::Error for WaitReceiptsTimeout {}
+
 /// 可选项
-pub struct Options {}
+#[derive(Debug, Clone)]
+pub struct Options {
+    /// 是否启用账户缓存
+    pub enable_cache: bool,
+    /// 账户缓存的过期时长，单位秒，每次访问会续期
+    pub cache_expiration_seconds: u64,
+    /// 守护区块哈希的过期时长，单位秒
+    pub daemon_hash_expiration_seconds: u64,
+    /// 每发送N笔交易后与节点对账一次账户的缓存高度，为0时不对账
+    pub cache_reconcile_interval: u64,
+    /// 从节点查询账户的最新区块时，是否包括pending中的交易
+    pub use_pending: bool,
+    /// 单笔交易允许的最大手续费，为None时不限制
+    pub max_joule: Option<u128>,
+    /// 重试策略，为None时不重试
+    pub retry: Option<Retry>,
+    /// 幂等窗口，单位秒，窗口内使用相同的幂等key重复发送时直接返回第一次的结果，为0时不启用
+    pub idempotency_window_seconds: u64,
+    /// 签名交易前计算pow的难度，为None时不计算pow，用于要求pow的链
+    pub pow_difficulty: Option<usize>,
+}
 
-impl Options {
+impl Default for Options {
     fn default() -> Self {
-        Options {}
+        Options {
+            enable_cache: true,
+            cache_expiration_seconds: 5 * 60,
+            daemon_hash_expiration_seconds: 10,
+            cache_reconcile_interval: 10,
+            use_pending: false,
+            max_joule: None,
+            retry: None,
+            idempotency_window_seconds: 0,
+            pow_difficulty: None,
+        }
+    }
+}
+
+/// 可选项的构造器
+pub struct OptionsBuilder {
+    options: Options,
+}
+
+impl OptionsBuilder {
+    pub fn builder() -> Self {
+        OptionsBuilder {
+            options: Options::default(),
+        }
+    }
+
+    /// # 设置是否启用账户缓存
+    pub fn set_enable_cache(mut self, enable_cache: bool) -> Self {
+        self.options.enable_cache = enable_cache;
+        self
+    }
+
+    /// # 设置账户缓存的过期时长，单位秒
+    pub fn set_cache_expiration_seconds(mut self, seconds: u64) -> Self {
+        self.options.cache_expiration_seconds = seconds;
+        self
+    }
+
+    /// # 设置守护区块哈希的过期时长，单位秒
+    pub fn set_daemon_hash_expiration_seconds(mut self, seconds: u64) -> Self {
+        self.options.daemon_hash_expiration_seconds = seconds;
+        self
+    }
+
+    /// # 设置每发送多少笔交易后与节点对账一次账户的缓存高度，为0时不对账
+    pub fn set_cache_reconcile_interval(mut self, interval: u64) -> Self {
+        self.options.cache_reconcile_interval = interval;
+        self
+    }
+
+    /// # 设置从节点查询账户的最新区块时，是否包括pending中的交易
+    pub fn set_use_pending(mut self, use_pending: bool) -> Self {
+        self.options.use_pending = use_pending;
+        self
+    }
+
+    /// # 设置单笔交易允许的最大手续费
+    pub fn set_max_joule(mut self, max_joule: Option<u128>) -> Self {
+        self.options.max_joule = max_joule;
+        self
+    }
+
+    /// # 设置重试策略
+    pub fn set_retry(mut self, retry: Option<Retry>) -> Self {
+        self.options.retry = retry;
+        self
+    }
+
+    /// # 设置幂等窗口，单位秒，为0时不启用
+    pub fn set_idempotency_window_seconds(mut self, seconds: u64) -> Self {
+        self.options.idempotency_window_seconds = seconds;
+        self
+    }
+
+    /// # 设置签名交易前计算pow的难度，为None时不计算pow
+    pub fn set_pow_difficulty(mut self, difficulty: Option<usize>) -> Self {
+        self.options.pow_difficulty = difficulty;
+        self
+    }
+
+    pub fn build(self) -> Options {
+        self.options
     }
 }
 
@@ -147,17 +416,50 @@ impl LatticeClient {
         account_lock: Option<Arc<dyn AccountLockTrait + Sync + Send>>,
         account_cache: Option<Arc<dyn AccountCacheTrait + Sync + Send>>,
     ) -> Self {
-        let options: Options = options.unwrap_or_else(|| Options::default());
         let http_client = connecting_node_config.new_http_client();
+        Self::new_with_http_client(
+            chain_config,
+            connecting_node_config,
+            http_client,
+            options,
+            account_lock,
+            account_cache,
+        )
+    }
+
+    /// # 使用指定的http客户端初始化，例如设置了自定义传输层的客户端
+    ///
+    /// ## 入参
+    /// + `chain_config: ChainConfig`: 链配置
+    /// + `connecting_node_config: ConnectingNodeConfig`: 连接节点配置
+    /// + `http_client: HttpClient`: 节点的http客户端
+    /// + `options: Option<Options>`: 可选项
+    /// + `account_lock: Option<Arc<dyn AccountLockTrait + Sync + Send>>`: 账户锁
+    /// + `account_cache: Option<Arc<dyn AccountCacheTrait + Sync + Send>>`: 账户缓存
+    ///
+    /// ## 出参
+    /// + `LatticeClient`: Lattice客户端
+    pub fn new_with_http_client(
+        chain_config: ChainConfig,
+        connecting_node_config: ConnectingNodeConfig,
+        http_client: HttpClient,
+        options: Option<Options>,
+        account_lock: Option<Arc<dyn AccountLockTrait + Sync + Send>>,
+        account_cache: Option<Arc<dyn AccountCacheTrait + Sync + Send>>,
+    ) -> Self {
+        let options: Options = options.unwrap_or_default();
         let default_account_lock =
             Arc::new(DefaultAccountLock::new()) as Arc<dyn AccountLockTrait + Sync + Send>;
-        let default_account_cache = Arc::new(DefaultAccountCache::new(
-            true,
-            Duration::from_secs(10),
-            http_client.clone(),
-        )) as Arc<dyn AccountCacheTrait + Sync + Send>;
+        let default_account_cache = Arc::new(DefaultAccountCache::from_options(&options, http_client.clone()))
+            as Arc<dyn AccountCacheTrait + Sync + Send>;
         let account_lock = account_lock.unwrap_or_else(|| default_account_lock);
         let account_cache = account_cache.unwrap_or_else(|| default_account_cache);
+        let submitted = (options.idempotency_window_seconds > 0).then(|| {
+            Cache::builder()
+                .max_capacity(IDEMPOTENCY_CACHE_CAPACITY)
+                .time_to_live(Duration::from_secs(options.idempotency_window_seconds))
+                .build()
+        });
 
         LatticeClient {
             chain_config,
@@ -166,7 +468,72 @@ impl LatticeClient {
             http_client,
             account_lock,
             account_cache,
+            shutdown: AtomicBool::new(false),
+            subscriptions: Mutex::new(Vec::new()),
+            submitted,
+        }
+    }
+
+    /// # 关闭客户端：停止所有websocket订阅任务，然后清空账户缓存
+    pub fn shutdown(self) {
+        self.close();
+    }
+
+    /// # 停止所有websocket订阅任务并清空账户缓存，重复调用时不做任何事
+    fn close(&self) {
+        if self.shutdown.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        for subscription in self.subscriptions.lock().unwrap().drain(..) {
+            subscription.abort();
         }
+        self.account_cache.clear();
+    }
+
+    /// # 订阅websocket消息，订阅任务由客户端记录，关闭客户端时停止
+    ///
+    /// ## 入参
+    /// + `ws_config: &ConnectingNodeConfig`: 订阅的节点，使用其中的websocket端口
+    /// + `message: &str`: 订阅消息
+    ///
+    /// ## 出参
+    /// + `impl Stream<Item = String>`: 收到的文本消息
+    fn subscribe(&self, ws_config: &ConnectingNodeConfig, message: &str) -> impl Stream<Item = String> {
+        let (notifications, handle) = WsClient::new(&ws_config.ip, ws_config.websocket_port).subscribe_with_handle(message);
+        let mut subscriptions = self.subscriptions.lock().unwrap();
+        subscriptions.retain(|subscription| !subscription.is_finished());
+        subscriptions.push(handle);
+        notifications
+    }
+
+    /// # 校验与节点的连接并预热账户缓存，返回可以直接使用的客户端，`new`只构造客户端，不访问节点
+    ///
+    /// 依次探测节点能否连接、查询账户的最新区块并写入账户缓存
+    ///
+    /// ## 入参
+    /// + `chain_id: u64`: 链ID
+    /// + `account_addresses: &[&str]`: 需要预热缓存的账户地址
+    ///
+    /// ## 出参
+    /// + `Result<LatticeClient, Error>`: 节点无法连接或查询账户失败时返回`Err`
+    pub fn connect(self, chain_id: u64, account_addresses: &[&str]) -> Result<Self, Error> {
+        if !self.http_client.can_dial() {
+            return Err(Error::new(&format!(
+                "无法连接节点【{}:{}】",
+                self.http_client.ip, self.http_client.port
+            )));
+        }
+
+        for account_address in account_addresses {
+            let address = Address::new(account_address);
+            let block = if self.options.use_pending {
+                self.http_client.get_latest_block_with_pending(chain_id, &address)
+            } else {
+                self.http_client.get_latest_block(chain_id, &address)
+            }?;
+            self.account_cache.set(chain_id, account_address, block);
+        }
+        Ok(self)
     }
 
     /// # 处理交易
@@ -175,41 +542,162 @@ impl LatticeClient {
     /// + `credentials: Credentials`:
     /// + `chain_id: u64`:
     /// + `mut transaction: Transaction`:
-    /// + `mut block: LatestBlock`:
+    /// + `block: LatestBlock`:
+    /// + `idempotency_key: Option<&str>`: 调用方提供的幂等key，和已签名的交易一起在发送前记录，为None时不去重
     ///
     /// ## 出参
-    /// + `Result<String, Error>`: 交易哈希
+    /// + `Result<SendResult, Error>`: 交易哈希、高度和时间戳；提供了幂等key但未启用幂等窗口时返回`Err`
     fn handle_transaction(
         &self,
         credentials: Credentials,
         chain_id: u64,
         mut transaction: Transaction,
-        mut block: LatestBlock,
-    ) -> Result<String, Error> {
+        block: LatestBlock,
+        idempotency_key: Option<&str>,
+    ) -> Result<SendResult, Error> {
+        if self.chain_config.token_less {
+            let amount = transaction.amount.unwrap_or(0);
+            let joule = transaction.joule.unwrap_or(0);
+            if amount != 0 || joule != 0 {
+                return Err(Error::new(&format!(
+                    "无通证的链不支持转账数量【{}】和手续费【{}】",
+                    amount, joule
+                )));
+            }
+        }
+        if let (Some(max_joule), Some(joule)) = (self.options.max_joule, transaction.joule) {
+            if joule > max_joule {
+                return Err(Error::new(&format!(
+                    "交易的手续费【{}】超过了允许的最大手续费【{}】",
+                    joule, max_joule
+                )));
+            }
+        }
+
+        let idempotency = match (&self.submitted, idempotency_key) {
+            (_, None) => None,
+            (None, Some(_)) => return Err(Error::new("未启用幂等窗口，不能使用幂等key")),
+            (Some(submitted), Some(key)) => {
+                let key = format!("{}_{}_{}", chain_id, credentials.account_address, key);
+                match submitted.get(&key) {
+                    Some(Submission::Sent(result)) => {
+                        debug!("幂等窗口内重复发送的交易，直接返回第一次的交易哈希：{}", result.hash);
+                        return Ok(result);
+                    }
+                    Some(Submission::Pending(transaction)) => {
+                        debug!("幂等窗口内未确认的交易，重新发送高度为【{}】的同一笔交易", transaction.height);
+                        return self.send_signed_transaction(&credentials, chain_id, *transaction, block, Some((submitted, key)), true);
+                    }
+                    None => Some((submitted, key)),
+                }
+            }
+        };
+
         // Step1 sign transaction
-        let sk = HexString::new(credentials.get_sk().as_str()).decode();
-        let (_, signature) = transaction.sign(chain_id, &sk, self.chain_config.curve);
-        transaction.sign = signature;
+        let sk = HexString::new(credentials.get_sk()?.as_str()).decode();
+        let key_pair = KeyPair::try_from_secret_key(&sk, self.chain_config.curve)?;
+        self.sign_transaction(chain_id, &mut transaction, &key_pair);
 
-        // Step2 send transaction
+        // Step2 send transaction，发送前记录已签名的交易，响应丢失后重试时重新发送同一笔交易而不是重新签名
+        if let Some((submitted, key)) = &idempotency {
+            submitted.insert(key.clone(), Submission::Pending(Box::new(transaction.clone())));
+        }
+        self.send_signed_transaction(&credentials, chain_id, transaction, block, idempotency, false)
+    }
+
+    /// # 发送已签名的交易并更新账户缓存和幂等记录
+    ///
+    /// 节点返回json-rpc错误说明第一次发送的交易被拒绝，删除幂等记录，允许使用同一个key重新发送；
+    /// 重新发送时节点可能因为已经收到过该交易而拒绝，此时保留记录，避免再签名一笔新的交易。
+    /// 其它错误（如连接失败、响应丢失）无法确定节点是否收到，同样保留记录，重试时重新发送同一笔交易
+    ///
+    /// ## 入参
+    /// + `credentials: &Credentials`: 身份凭证
+    /// + `chain_id: u64`: 链ID
+    /// + `transaction: Transaction`: 已签名的交易
+    /// + `block: LatestBlock`: 账户缓存中的区块
+    /// + `idempotency: Option<(&Cache<String, Submission>, String)>`: 幂等记录和key
+    /// + `resend: bool`: 是否为重新发送
+    ///
+    /// ## 出参
+    /// + `Result<SendResult, Error>`
+    fn send_signed_transaction(
+        &self,
+        credentials: &Credentials,
+        chain_id: u64,
+        transaction: Transaction,
+        mut block: LatestBlock,
+        idempotency: Option<(&Cache<String, Submission>, String)>,
+        resend: bool,
+    ) -> Result<SendResult, Error> {
+        let (height, timestamp) = (transaction.height, transaction.timestamp);
         let result = self.http_client.send_raw_tx(chain_id, transaction);
 
         // Step3 handle cache
         match result {
             Ok(hash) => {
-                block.hash = hash.clone();
-                block.height = block.height + 1;
-                self.account_cache
-                    .set(chain_id, credentials.account_address.as_str(), block);
-                Ok(hash)
+                // 重新发送时账户缓存可能已经被校正到更高的高度，不能回退
+                if height > block.height {
+                    block.hash = hash.clone();
+                    block.height = height;
+                    self.account_cache
+                        .set(chain_id, credentials.account_address.as_str(), block);
+                }
+                let result = SendResult {
+                    hash,
+                    height,
+                    timestamp,
+                };
+                if let Some((submitted, key)) = idempotency {
+                    submitted.insert(key, Submission::Sent(result.clone()));
+                }
+                Ok(result)
             }
             Err(e) => {
                 error!("向链【{}】发送交易失败：{}", chain_id, e);
+                if let Some((submitted, key)) = idempotency {
+                    if !resend && e.kind() == ErrorKind::JsonRpc {
+                        submitted.invalidate(&key);
+                    }
+                }
                 Err(e)
             }
         }
     }
 
+    /// # 批量签名同一账户的多笔交易，私钥只解密和派生一次
+    ///
+    /// ## 入参
+    /// + `credentials: Credentials`: 身份凭证
+    /// + `chain_id: u64`: 链ID
+    /// + `txs: Vec<Transaction>`: 待签名的交易，`owner`必须为凭证的账户地址
+    ///
+    /// ## 出参
+    /// + `Result<Vec<Transaction>, Error>`: 已签名的交易，顺序与入参一致；存在其它账户的交易时返回`Err`
+    pub fn sign_many(
+        &self,
+        credentials: Credentials,
+        chain_id: u64,
+        txs: Vec<Transaction>,
+    ) -> Result<Vec<Transaction>, Error> {
+        if let Some(tx) = txs.iter().find(|tx| tx.owner != credentials.account_address) {
+            return Err(Error::new(&format!(
+                "交易的所有者【{}】不是凭证的账户【{}】",
+                tx.owner, credentials.account_address
+            )));
+        }
+
+        let sk = HexString::new(credentials.get_sk()?.as_str()).decode();
+        let key_pair = KeyPair::try_from_secret_key(&sk, self.chain_config.curve)?;
+        Ok(txs
+            .into_iter()
+            .map(|mut tx| {
+                self.sign_transaction(chain_id, &mut tx, &key_pair);
+                tx
+            })
+            .collect())
+    }
+
     /// # 转账
     ///
     /// ## 入参
@@ -228,10 +716,139 @@ impl LatticeClient {
         payload: &str,
         amount: Option<u128>,
         joule: Option<u128>,
+    ) -> Result<String, Error> {
+        self.transfer_detailed(credentials, chain_id, payload, amount, joule)
+            .map(|result| result.hash)
+    }
+
+    /// # 转账，返回交易哈希以及签名时使用的高度和时间戳
+    ///
+    /// ## 入参
+    /// + `credentials: Credentials`: 身份凭证
+    /// + `chain_id: u64`:
+    /// + `payload: &str`:
+    /// + `amount: Option<u128>`:
+    /// + `joule: Option<u128>`:
+    ///
+    /// ## 出参
+    /// + `Result<SendResult, Error>`
+    pub fn transfer_detailed(
+        &self,
+        credentials: Credentials,
+        chain_id: u64,
+        payload: &str,
+        amount: Option<u128>,
+        joule: Option<u128>,
+    ) -> Result<SendResult, Error> {
+        debug!("开始发起转账交易，chain_id: {}, payload: {}, amount: {:?}, joule: {:?}", chain_id, payload, amount, joule);
+        self.send_transfer(credentials, chain_id, amount, joule, None, |builder| builder.set_payload(payload))
+    }
+
+    /// # 转账，交易备注为deflate压缩后的数据，适用于较大的备注
+    ///
+    /// ## 入参
+    /// + `credentials: Credentials`: 身份凭证
+    /// + `chain_id: u64`: 链ID
+    /// + `data: &[u8]`: 交易备注的原始数据，可用`model::convert::decompress_payload`从交易的`payload`解压
+    /// + `amount: Option<u128>`: 转账数量
+    /// + `joule: Option<u128>`: 手续费
+    ///
+    /// ## 出参
+    /// + `Result<SendResult, Error>`
+    pub fn transfer_compressed(
+        &self,
+        credentials: Credentials,
+        chain_id: u64,
+        data: &[u8],
+        amount: Option<u128>,
+        joule: Option<u128>,
+    ) -> Result<SendResult, Error> {
+        debug!("开始发起压缩备注的转账交易，chain_id: {}, 备注{}字节, amount: {:?}, joule: {:?}", chain_id, data.len(), amount, joule);
+        self.send_transfer(credentials, chain_id, amount, joule, None, |builder| builder.set_payload_compressed(data))
+    }
+
+    /// # 幂等的转账，幂等窗口内使用相同的幂等key重试时不会签名新的交易，节点已接受时直接返回第一次的结果
+    ///
+    /// 幂等key和已签名的交易在发送前记录。发送失败（例如连接失败、响应丢失）后重试时，重新发送同一笔交易，
+    /// 节点接受后才返回`Ok`；节点明确拒绝第一次发送的交易时记录会被删除，可以使用同一个key重新发送。
+    /// 需要通过`set_idempotency_window_seconds`启用幂等窗口
+    ///
+    /// ## 入参
+    /// + `credentials: Credentials`: 身份凭证
+    /// + `chain_id: u64`: 链ID
+    /// + `idempotency_key: &str`: 调用方提供的幂等key，同一账户的不同转账应使用不同的key，示例：订单号
+    /// + `payload: &str`: 交易备注
+    /// + `amount: Option<u128>`: 转账数量
+    /// + `joule: Option<u128>`: 手续费
+    ///
+    /// ## 出参
+    /// + `Result<SendResult, Error>`: 未启用幂等窗口时返回`Err`
+    pub fn transfer_idempotent(
+        &self,
+        credentials: Credentials,
+        chain_id: u64,
+        idempotency_key: &str,
+        payload: &str,
+        amount: Option<u128>,
+        joule: Option<u128>,
+    ) -> Result<SendResult, Error> {
+        debug!("开始发起幂等的转账交易，chain_id: {}, key: {}, payload: {}, amount: {:?}, joule: {:?}", chain_id, idempotency_key, payload, amount, joule);
+        self.send_transfer(credentials, chain_id, amount, joule, Some(idempotency_key), |builder| builder.set_payload(payload))
+    }
+
+    /// # 构造并发送转账交易
+    ///
+    /// ## 入参
+    /// + `idempotency_key: Option<&str>`: 幂等key，为None时不做幂等检查
+    /// + `set_payload: F`: 设置交易备注
+    fn send_transfer<F>(
+        &self,
+        credentials: Credentials,
+        chain_id: u64,
+        amount: Option<u128>,
+        joule: Option<u128>,
+        idempotency_key: Option<&str>,
+        set_payload: F,
+    ) -> Result<SendResult, Error>
+    where
+        F: FnOnce(TransferBuilder) -> TransferBuilder,
+    {
+        let account_lock = self
+            .account_lock
+            .obtain(chain_id, credentials.account_address.as_str());
+        let _guard = account_lock.lock().unwrap();
+
+        let block = self
+            .account_cache
+            .get(chain_id, credentials.account_address.as_str());
+
+        let builder = TransferBuilder::builder()
+            .set_current_block(block.clone())
+            .set_owner(credentials.account_address.as_str())
+            .set_linker(ZERO_ZLTC_ADDRESS);
+        let transaction = set_payload(builder).set_amount(amount).set_joule(joule).build();
+
+        self.handle_transaction(credentials, chain_id, transaction, block, idempotency_key)
+    }
+
+    /// # 接收转账，为一笔发送给当前账户的转账交易构造并发送对应的接收交易
+    ///
+    /// ## 入参
+    /// + `credentials: Credentials`: 身份凭证
+    /// + `chain_id: u64`: 链ID
+    /// + `send_hash: &str`: 转账交易的哈希
+    ///
+    /// ## 出参
+    /// + `Result<String, Error>`: 接收交易的哈希
+    pub fn receive(
+        &self,
+        credentials: Credentials,
+        chain_id: u64,
+        send_hash: &str,
     ) -> Result<String, Error> {
         debug!(
-            "开始发起转账交易，chain_id: {}, payload: {}, amount: {:?}, joule: {:?}",
-            chain_id, payload, amount, joule
+            "开始发起接收交易，chain_id: {}, send_hash: {}",
+            chain_id, send_hash
         );
         let account_lock = self
             .account_lock
@@ -242,16 +859,78 @@ impl LatticeClient {
             .account_cache
             .get(chain_id, credentials.account_address.as_str());
 
-        let transaction = TransferBuilder::builder()
+        let transaction = ReceiveBuilder::builder()
             .set_current_block(block.clone())
             .set_owner(credentials.account_address.as_str())
             .set_linker(ZERO_ZLTC_ADDRESS)
-            .set_payload(payload)
-            .set_amount(amount)
-            .set_joule(joule)
+            .set_hub(vec![send_hash.to_string()])
             .build();
 
-        self.handle_transaction(credentials, chain_id, transaction, block)
+        self.handle_transaction(credentials, chain_id, transaction, block, None)
+            .map(|result| result.hash)
+    }
+
+    /// # 取消账户在交易池中最早的pending交易，通过在同一高度发送一笔零金额的转给自己的交易来替换它
+    ///
+    /// 替换交易的父区块是账户已上链的最新区块，所以只能替换紧接着它的那笔pending交易
+    ///
+    /// ## 入参
+    /// + `credentials: Credentials`: 身份凭证
+    /// + `chain_id: u64`: 链ID
+    /// + `height: u64`: 待取消的交易的高度
+    ///
+    /// ## 出参
+    /// + `Result<String, Error>`: 替换交易的哈希；指定高度的pending交易不存在、不是最早的pending交易，或者高度为0时返回`Err`
+    pub fn cancel_pending(
+        &self,
+        credentials: Credentials,
+        chain_id: u64,
+        height: u64,
+    ) -> Result<String, Error> {
+        if height == 0 {
+            return Err(Error::new(&format!("高度为【{}】的交易没有父交易，不能取消", height)));
+        }
+        let account_lock = self
+            .account_lock
+            .obtain(chain_id, credentials.account_address.as_str());
+        let _guard = account_lock.lock().unwrap();
+
+        let address = Address::new(credentials.account_address.as_str());
+        let pending = self.http_client.get_latest_block_with_pending(chain_id, &address)?;
+        if pending.height < height {
+            return Err(Error::new(&format!("高度为【{}】的pending交易不存在", height)));
+        }
+        let block = self.http_client.get_latest_block(chain_id, &address)?;
+        if block.height + 1 != height {
+            return Err(Error::new(&format!(
+                "只能取消最早的pending交易，其高度为【{}】，不能取消高度为【{}】的交易",
+                block.height + 1,
+                height
+            )));
+        }
+        let transaction =
+            Self::new_cancel_transaction(credentials.account_address.as_str(), block.clone());
+
+        self.handle_transaction(credentials, chain_id, transaction, block, None)
+            .map(|result| result.hash)
+    }
+
+    /// # 构造一笔零金额的转给自己的交易，用于替换同一高度的pending交易
+    ///
+    /// ## 入参
+    /// + `owner: &str`: 账户地址
+    /// + `block: LatestBlock`: 待替换交易的父区块
+    ///
+    /// ## 出参
+    /// + `Transaction`
+    fn new_cancel_transaction(owner: &str, block: LatestBlock) -> Transaction {
+        TransferBuilder::builder()
+            .set_current_block(block)
+            .set_owner(owner)
+            .set_linker(owner)
+            .set_payload(PREFIX_OF_HEX)
+            .set_amount(None)
+            .build()
     }
 
     /// # 部署合约
@@ -275,6 +954,32 @@ impl LatticeClient {
         joule: Option<u128>,
         payload: Option<&str>,
     ) -> Result<String, Error> {
+        self.deploy_contract_detailed(credentials, chain_id, code, amount, joule, payload)
+            .map(|result| result.hash)
+    }
+
+    /// # 部署合约，并返回预测的合约地址，不需要等待回执即可引用新合约
+    ///
+    /// ## 入参
+    /// + `credentials: Credentials`: 上链的凭证
+    /// + `chain_id: u64`: 链ID
+    /// + `code: &str`: 合约代码
+    /// + `amount: Option<u128>`: 转账金额
+    /// + `joule: Option<u128>`: 手续费
+    /// + `payload: Option<&str>`: 交易备注
+    ///
+    /// ## 出参
+    /// + `Result<DeployResult, Error>`
+    pub fn deploy_contract_detailed(
+        &self,
+        credentials: Credentials,
+        chain_id: u64,
+        code: &str,
+        amount: Option<u128>,
+        joule: Option<u128>,
+        payload: Option<&str>,
+    ) -> Result<DeployResult, Error> {
+        let owner = credentials.account_address.clone();
         let account_lock = self
             .account_lock
             .obtain(chain_id, credentials.account_address.as_str());
@@ -294,7 +999,33 @@ impl LatticeClient {
             .set_joule(joule)
             .build();
 
-        self.handle_transaction(credentials, chain_id, transaction, block)
+        let result = self.handle_transaction(credentials, chain_id, transaction, block, None)?;
+        Ok(DeployResult {
+            predicted_address: predict_contract_address(&owner, result.height, self.chain_config.curve)?,
+            hash: result.hash,
+            height: result.height,
+        })
+    }
+
+    /// # 等待部署合约的回执，并确认回执中的合约地址与预测的一致
+    ///
+    /// ## 入参
+    /// + `chain_id: u64`: 链ID
+    /// + `deploy: &DeployResult`: 部署合约的结果
+    /// + `timeout: Duration`: 超时时长
+    ///
+    /// ## 出参
+    /// + `Result<Receipt, Error>`: 超时或合约地址与预测的不一致时返回`Err`
+    pub fn confirm_deployment(&self, chain_id: u64, deploy: &DeployResult, timeout: Duration) -> Result<Receipt, Error> {
+        let receipt = self.wait_for_receipts(chain_id, &[deploy.hash.as_str()], timeout)?.remove(0);
+        if receipt.contract_address() != deploy.predicted_address {
+            return Err(Error::new(&format!(
+                "合约地址{}与预测的合约地址{}不一致",
+                receipt.contract_address(),
+                deploy.predicted_address
+            )));
+        }
+        Ok(receipt)
     }
 
     /// # 调用合约
@@ -320,6 +1051,35 @@ impl LatticeClient {
         joule: Option<u128>,
         payload: Option<&str>,
     ) -> Result<String, Error> {
+        self.call_contract_detailed(
+            credentials,
+            chain_id,
+            contract_address,
+            code,
+            CallContractOptions { amount, joule, payload },
+        )
+        .map(|result| result.hash)
+    }
+
+    /// # 调用合约，返回交易哈希以及签名时使用的高度和时间戳
+    ///
+    /// ## 入参
+    /// + `credentials: Credentials`: 上链的凭证
+    /// + `chain_id: u64`: 链ID
+    /// + `contract_address: &str`: 合约地址
+    /// + `code: &str`:
+    /// + `options: CallContractOptions`: 金额、joule和payload，未设置的使用默认值
+    ///
+    /// ## 出参
+    /// + `Result<SendResult, Error>`
+    pub fn call_contract_detailed(
+        &self,
+        credentials: Credentials,
+        chain_id: u64,
+        contract_address: &str,
+        code: &str,
+        options: CallContractOptions,
+    ) -> Result<SendResult, Error> {
         let account_lock = self
             .account_lock
             .obtain(chain_id, credentials.account_address.as_str());
@@ -334,12 +1094,67 @@ impl LatticeClient {
             .set_owner(credentials.account_address.as_str())
             .set_linker(contract_address)
             .set_code(code)
-            .set_payload(payload.unwrap_or(PREFIX_OF_HEX))
-            .set_amount(amount)
-            .set_joule(joule)
+            .set_payload(options.payload.unwrap_or(PREFIX_OF_HEX))
+            .set_amount(options.amount)
+            .set_joule(options.joule)
             .build();
 
-        self.handle_transaction(credentials, chain_id, transaction, block)
+        self.handle_transaction(credentials, chain_id, transaction, block, None)
+    }
+
+    /// # 按照abi编码方法调用并调用合约
+    ///
+    /// ## 入参
+    /// + `credentials: Credentials`: 上链的凭证
+    /// + `chain_id: u64`: 链ID
+    /// + `contract_address: &str`: 合约地址
+    /// + `abi: &str`: 合约abi
+    /// + `function_name: &str`: 方法名，示例：`incrementCounter`
+    /// + `args: Vec<Box<dyn Any>>`: 实参
+    ///
+    /// ## 出参
+    /// + `Result<String, Error>`: 交易哈希，方法不存在或实参不匹配时返回`Err`
+    pub fn call_contract_fn(
+        &self,
+        credentials: Credentials,
+        chain_id: u64,
+        contract_address: &str,
+        abi: &str,
+        function_name: &str,
+        args: Vec<Box<dyn Any>>,
+    ) -> Result<String, Error> {
+        let code = Abi::new(abi).try_encode_hex(function_name, args)?;
+        self.call_contract(credentials, chain_id, contract_address, &code, None, None, None)
+    }
+
+    /// # 按照abi编码方法调用并预调用合约（不会上链），返回解码后的返回值
+    ///
+    /// ## 入参
+    /// + `chain_id: u64`: 链ID
+    /// + `owner: &str`: 调用者地址
+    /// + `contract_address: &str`: 合约地址
+    /// + `abi: &str`: 合约abi
+    /// + `function_name: &str`: 方法名，示例：`getCount`
+    /// + `args: Vec<Box<dyn Any>>`: 实参
+    ///
+    /// ## 出参
+    /// + `Result<Vec<DynSolValue>, Error>`: 方法的返回值，编码失败、执行失败或返回值解码失败时返回`Err`
+    pub fn pre_call_fn(
+        &self,
+        chain_id: u64,
+        owner: &str,
+        contract_address: &str,
+        abi: &str,
+        function_name: &str,
+        args: Vec<Box<dyn Any>>,
+    ) -> Result<Vec<DynSolValue>, Error> {
+        let abi = Abi::new(abi);
+        let code = abi.try_encode_hex(function_name, args)?;
+        let receipt = self.pre_call_contract(chain_id, owner, contract_address, &code, None)?;
+        receipt.ensure_success()?;
+        let data = hex::decode(receipt.contract_return())
+            .map_err(|e| Error::new(&format!("invalid contract return {}", receipt.contract_return())).with_source(e))?;
+        abi.decode_output(function_name, &data)
     }
 
     /// # 预调用合约（不会上链）
@@ -369,12 +1184,203 @@ impl LatticeClient {
             .set_owner(owner)
             .set_linker(contract_address)
             .set_code(code)
-            .set_payload(payload.unwrap_or("0x"))
+            .set_payload(payload.unwrap_or(PREFIX_OF_HEX))
             .build();
 
         self.http_client.pre_call_contract(chain_id, transaction)
     }
 
+    /// # 等待多笔交易的回执，每个轮询周期由固定数量的线程并发查询所有未上链的交易
+    ///
+    /// ## 入参
+    /// + `chain_id: u64`: 链ID
+    /// + `hashes: &[&str]`: 交易哈希
+    /// + `timeout: Duration`: 超时时长
+    ///
+    /// ## 出参
+    /// + `Result<Vec<Receipt>, Error>`: 与交易哈希一一对应的回执，超时时返回`Err`，其`source`为`WaitReceiptsTimeout`
+    pub fn wait_for_receipts(&self, chain_id: u64, hashes: &[&str], timeout: Duration) -> Result<Vec<Receipt>, Error> {
+        let deadline = Instant::now() + timeout;
+        let mut receipts: Vec<Option<Receipt>> = vec![None; hashes.len()];
+        loop {
+            let pending: Vec<usize> = (0..hashes.len()).filter(|&i| receipts[i].is_none()).collect();
+            let next = AtomicUsize::new(0);
+            let results = Mutex::new(Vec::with_capacity(pending.len()));
+            thread::scope(|scope| {
+                for _ in 0..RECEIPT_QUERY_CONCURRENCY.min(pending.len()) {
+                    scope.spawn(|| {
+                        while let Some(&i) = pending.get(next.fetch_add(1, Ordering::SeqCst)) {
+                            let result = self.http_client.get_receipt(chain_id, hashes[i]);
+                            results.lock().unwrap().push((i, result));
+                        }
+                    });
+                }
+            });
+            for (i, result) in results.into_inner().unwrap() {
+                match result {
+                    Ok(receipt) => receipts[i] = Some(receipt),
+                    Err(err) => debug!("交易{}的回执未查询到，{}", hashes[i], err),
+                }
+            }
+            if receipts.iter().all(Option::is_some) {
+                return Ok(receipts.into_iter().flatten().collect());
+            }
+            let now = Instant::now();
+            if now >= deadline {
+                let timeout = WaitReceiptsTimeout { receipts };
+                return Err(Error::new(&format!("等待交易回执超时，{}", timeout)).with_source(timeout));
+            }
+            thread::sleep(Duration::from_millis(RECEIPT_POLL_INTERVAL_MILLIS).min(deadline - now));
+        }
+    }
+
+    /// # 订阅合约事件，对每个新的账户区块查询回执，按照合约地址和事件选择器筛选并解码事件
+    ///
+    /// websocket连接断开后会自动重连，客户端关闭后停止订阅，需要在tokio运行时中调用
+    ///
+    /// ## 入参
+    /// + `ws_config: &ConnectingNodeConfig`: 订阅的节点，使用其中的websocket端口
+    /// + `chain_id: u64`: 链ID
+    /// + `address: &str`: 合约地址
+    /// + `abi: &str`: 合约abi
+    /// + `event_name: &str`: 事件名，示例：`ProtocolAdded`
+    ///
+    /// ## 出参
+    /// + `Result<impl Stream<Item = Result<DecodedEvent, Error>>, Error>`: abi不合法或事件不存在时返回`Err`
+    pub fn subscribe_events(
+        &self,
+        ws_config: &ConnectingNodeConfig,
+        chain_id: u64,
+        address: &str,
+        abi: &str,
+        event_name: &str,
+    ) -> Result<impl Stream<Item = Result<DecodedEvent, Error>>, Error> {
+        let filter = EventFilter::new(address, abi, event_name)?;
+        let notifications = self.subscribe(ws_config, &JsonRpcBody::new_ws_transaction_block());
+        Ok(decoded_events(notifications, self.http_client.clone(), chain_id, filter))
+    }
+
+    /// # 订阅守护区块，按高度递增产生，不重复
+    ///
+    /// websocket连接断开后会自动重连，重连前后重复收到的区块会被丢弃，重连期间错过区块时产生`Err`，客户端关闭后停止订阅，需要在tokio运行时中调用
+    ///
+    /// ## 入参
+    /// + `ws_config: &ConnectingNodeConfig`: 订阅的节点，使用其中的websocket端口
+    ///
+    /// ## 出参
+    /// + `impl Stream<Item = Result<DBlock, Error>>`: 按高度递增产生的守护区块，高度跳跃时产生`Err`说明缺失的区块
+    pub fn subscribe_daemon_blocks(
+        &self,
+        ws_config: &ConnectingNodeConfig,
+    ) -> impl Stream<Item = Result<DBlock, Error>> {
+        let notifications = self.subscribe(ws_config, &JsonRpcBody::new_ws_daemon_block());
+        deduplicated_daemon_blocks(notifications)
+    }
+
+    /// # 查询回执的确认数，即回执所在的守护区块之后又产生了多少个守护区块
+    ///
+    /// ## 入参
+    /// + `chain_id: u64`: 链ID
+    /// + `receipt: &Receipt`: 回执
+    ///
+    /// ## 出参
+    /// + `Result<u64, Error>`: `最新的守护区块高度 - 回执所在的守护区块高度`
+    pub fn confirmations(&self, chain_id: u64, receipt: &Receipt) -> Result<u64, Error> {
+        let latest = self.http_client.get_latest_daemon_block(chain_id)?;
+        Ok(latest.height.saturating_sub(receipt.dblock_height()))
+    }
+
+    /// # 等待交易上链并被至少`confirmations`个守护区块确认
+    ///
+    /// ## 入参
+    /// + `chain_id: u64`: 链ID
+    /// + `hash: &str`: 交易哈希
+    /// + `confirmations: u64`: 需要的确认数
+    /// + `timeout: Duration`: 超时时长，包括等待回执的时长
+    ///
+    /// ## 出参
+    /// + `Result<Receipt, Error>`: 达到确认数后返回回执，超时时返回`Err`
+    pub fn wait_for_confirmations(&self, chain_id: u64, hash: &str, confirmations: u64, timeout: Duration) -> Result<Receipt, Error> {
+        let deadline = Instant::now() + timeout;
+        let receipt = self.wait_for_receipts(chain_id, &[hash], timeout)?.remove(0);
+        loop {
+            let current = match self.confirmations(chain_id, &receipt) {
+                Ok(current) if current >= confirmations => return Ok(receipt),
+                Ok(current) => current,
+                Err(err) => {
+                    debug!("查询最新的守护区块失败，{}", err);
+                    0
+                }
+            };
+            let now = Instant::now();
+            if now >= deadline {
+                return Err(Error::new(&format!("等待交易{}的确认数超时，需要{}，当前{}", hash, confirmations, current)));
+            }
+            thread::sleep(Duration::from_millis(RECEIPT_POLL_INTERVAL_MILLIS).min(deadline - now));
+        }
+    }
+
+    /// # 导出账户在高度区间内的交易回执，每查询到一笔回执就写出一行，不会在内存中缓存全部回执
+    ///
+    /// 每行包括交易哈希`hash`、账户区块高度`height`、是否执行成功`success`、消耗的焦耳`joule_used`和交易的时间戳`timestamp`
+    ///
+    /// ## 入参
+    /// + `chain_id: u64`: 链ID
+    /// + `address: &str`: 账户地址
+    /// + `from: u64`: 起始的账户区块高度，包括
+    /// + `to: u64`: 结束的账户区块高度，包括
+    /// + `writer: W`: 输出，例如文件
+    /// + `format: ExportFormat`: 输出的格式
+    ///
+    /// ## 出参
+    /// + `Result<usize, Error>`: 导出的回执数量，查询区块、回执或者写出失败时返回`Err`，已写出的行保留在输出中
+    pub fn export_receipts<W: Write>(
+        &self,
+        chain_id: u64,
+        address: &str,
+        from: u64,
+        to: u64,
+        mut writer: W,
+        format: ExportFormat,
+    ) -> Result<usize, Error> {
+        let write_error = |e: std::io::Error| Error::new(&format!("写出回执失败，{}", e)).with_source(e);
+        if format == ExportFormat::Csv {
+            writeln!(writer, "hash,height,success,joule_used,timestamp").map_err(write_error)?;
+        }
+
+        let addr = Address::new(address);
+        let mut count = 0;
+        for height in from..=to {
+            let block = self.http_client.get_tblock_by_height(chain_id, &addr, height)?;
+            let receipt = self.http_client.get_receipt(chain_id, &block.hash)?;
+            match format {
+                ExportFormat::Jsonl => {
+                    let row = serde_json::json!({
+                        "hash": block.hash,
+                        "height": block.height,
+                        "success": receipt.is_success(),
+                        "joule_used": receipt.joule_used(),
+                        "timestamp": block.timestamp,
+                    });
+                    writeln!(writer, "{}", row)
+                }
+                ExportFormat::Csv => writeln!(
+                    writer,
+                    "{},{},{},{},{}",
+                    block.hash,
+                    block.height,
+                    receipt.is_success(),
+                    receipt.joule_used(),
+                    block.timestamp
+                ),
+            }
+            .map_err(write_error)?;
+            count += 1;
+        }
+        writer.flush().map_err(write_error)?;
+        Ok(count)
+    }
+
     /// # 签名交易并发送交易
     ///
     /// ## 入参
@@ -384,27 +1390,124 @@ impl LatticeClient {
     /// ## 出参
     /// + `Result<String, Error>`
     pub fn sign_and_send_tx(
-        self,
+        &self,
+        credentials: Credentials,
+        chain_id: u64,
+        tx: Transaction,
+    ) -> Result<String, Error> {
+        self.sign_and_send_tx_with_curve(credentials, chain_id, tx, self.chain_config.curve)
+    }
+
+    /// # 签名交易，可选项设置了pow的难度时先计算pow
+    ///
+    /// ## 入参
+    /// + `chain_id: u64`: 链ID
+    /// + `tx: &mut Transaction`: 待签名的交易
+    /// + `key_pair: &KeyPair`: 密钥对
+    fn sign_transaction(&self, chain_id: u64, tx: &mut Transaction, key_pair: &KeyPair) {
+        match self.options.pow_difficulty {
+            Some(difficulty) => tx.sign_with_pow(chain_id, key_pair, difficulty),
+            None => tx.sign_with_key_pair(chain_id, key_pair),
+        };
+    }
+
+    /// # 使用指定的椭圆曲线签名交易并发送交易，用于通过同一个客户端向曲线与链配置不同的链发送交易
+    ///
+    /// ## 入参
+    /// + `credentials: Credentials`: 身份凭证
+    /// + `chain_id: u64`: 链ID
+    /// + `tx: Transaction`: 交易
+    /// + `curve: Curve`: 签名使用的椭圆曲线，替代链配置中的曲线
+    ///
+    /// ## 出参
+    /// + `Result<String, Error>`: 私钥对该曲线不合法时返回`Err`
+    pub fn sign_and_send_tx_with_curve(
+        &self,
         credentials: Credentials,
         chain_id: u64,
         mut tx: Transaction,
+        curve: Curve,
     ) -> Result<String, Error> {
-        let sk = HexString::new(&credentials.get_sk()).decode();
-        let (_, signature) = tx.sign(chain_id, &sk, self.chain_config.curve);
-        tx.sign = signature;
+        let sk = HexString::new(&credentials.get_sk()?).decode();
+        let key_pair = KeyPair::try_from_secret_key(&sk, curve)?;
+        self.sign_transaction(chain_id, &mut tx, &key_pair);
 
         self.http_client.send_raw_tx(chain_id, tx)
     }
 }
 
+impl Drop for LatticeClient {
+    /// 与`shutdown`相同：停止websocket订阅任务并清空缓存
+    fn drop(&mut self) {
+        self.close();
+    }
+}
+
 #[cfg(test)]
 mod test {
+    use std::sync::mpsc;
     use std::thread;
 
+    use serde_json::json;
+
     use abi::abi::Abi;
 
+    use crate::mock::mock_node;
+    use crate::mock_node::mock_lattice_client;
+
     use super::*;
 
+    #[test]
+    fn test_connecting_node_config_from_url() {
+        let config = ConnectingNodeConfig::from_url("http://node.example.com:13800").unwrap();
+        assert_eq!("node.example.com", config.ip);
+        assert_eq!(13800, config.http_port);
+        assert_eq!(0, config.websocket_port);
+
+        let config = ConnectingNodeConfig::from_url("192.168.1.20:13800?ws=13801").unwrap();
+        assert_eq!("192.168.1.20", config.ip);
+        assert_eq!(13800, config.http_port);
+        assert_eq!(13801, config.websocket_port);
+
+        assert_eq!(443, ConnectingNodeConfig::from_url("https://node.example.com").unwrap().http_port);
+        assert!(ConnectingNodeConfig::from_url("ftp://node.example.com:13800").is_err());
+        assert!(ConnectingNodeConfig::from_url("192.168.1.20:138000").is_err());
+        assert!(ConnectingNodeConfig::from_url("192.168.1.20:13800?ws=abc").is_err());
+    }
+
+    #[test]
+    fn test_connect() {
+        const ADDRESS: &str = "zltc_Z1pnS94bP4hQSYLs4aP4UwBP9pH8bEvhi";
+
+        let new_lattice = |http_client: &HttpClient| {
+            LatticeClient::new(
+                ChainConfig { curve: Curve::Sm2p256v1, token_less: true },
+                ConnectingNodeConfig { ip: http_client.ip.clone(), http_port: http_client.port, websocket_port: 0 },
+                None,
+                None,
+                None,
+            )
+        };
+        let blocks = Arc::new(Mutex::new(0));
+        let counter = Arc::clone(&blocks);
+        let http_client = mock_node(move |method, _| match method {
+            "latc_getCurrentTBDB" => {
+                *counter.lock().unwrap() += 1;
+                json!(LatestBlock { height: 5, hash: format!("0x{:064x}", 5), daemon_hash: format!("0x{:064x}", 1) })
+            }
+            method => panic!("unexpected method {}", method),
+        });
+
+        assert!(new_lattice(&http_client).connect(CHAIN_ID, &[ADDRESS]).is_ok());
+        assert_eq!(*blocks.lock().unwrap(), 1);
+
+        let closed = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let unreachable = HttpClient::new("127.0.0.1", closed.local_addr().unwrap().port());
+        drop(closed);
+        let err = new_lattice(&unreachable).connect(CHAIN_ID, &[]).err().unwrap();
+        assert!(err.to_string().contains("无法连接节点"), "{}", err);
+    }
+
     const COUNTER_ABI: &str = r#"[
         {
             "inputs": [],
@@ -503,6 +1606,342 @@ mod test {
         }
     }
 
+    #[test]
+    fn test_transfer_detailed() {
+        let (sender, receiver) = mpsc::channel();
+        let http_client = mock_node(move |method, params| match method {
+            "latc_getCurrentTBDB" | "latc_getPendingTBDB" => json!(LatestBlock {
+                height: 5,
+                hash: format!("0x{:064x}", 5),
+                daemon_hash: format!("0x{:064x}", 1),
+            }),
+            "wallet_sendRawTBlock" => {
+                sender.send(params[0].clone()).unwrap();
+                json!(format!("0x{:064x}", 6))
+            }
+            method => panic!("unexpected method {}", method),
+        });
+        let lattice = LatticeClient::new(
+            ChainConfig {
+                curve: Curve::Sm2p256v1,
+                token_less: true,
+            },
+            ConnectingNodeConfig {
+                ip: http_client.ip.clone(),
+                http_port: http_client.port,
+                websocket_port: 0,
+            },
+            None,
+            None,
+            None,
+        );
+        let setup = Setup::new();
+
+        let result = lattice
+            .transfer_detailed(setup.credentials, CHAIN_ID, "0x01", None, None)
+            .unwrap();
+        let raw_tx = receiver.recv().unwrap();
+        assert_eq!(result.hash, format!("0x{:064x}", 6));
+        assert_eq!(result.height, 6);
+        assert_eq!(raw_tx["number"], json!(result.height));
+        assert_eq!(raw_tx["timestamp"], json!(result.timestamp));
+    }
+
+    #[test]
+    fn test_sign_many() {
+        let setup = Setup::new();
+        let owner = setup.credentials.account_address.clone();
+        let txs = (1..=3)
+            .map(|height| {
+                TransferBuilder::builder()
+                    .set_current_block(LatestBlock {
+                        height,
+                        hash: ZERO_HASH_STRING.to_string(),
+                        daemon_hash: ZERO_HASH_STRING.to_string(),
+                    })
+                    .set_owner(&owner)
+                    .set_linker(ZERO_ZLTC_ADDRESS)
+                    .set_payload(PREFIX_OF_HEX)
+                    .build()
+            })
+            .collect::<Vec<Transaction>>();
+
+        let signed = setup.lattice.sign_many(setup.credentials.clone(), CHAIN_ID, txs).unwrap();
+        assert_eq!(3, signed.len());
+        let key_pair = KeyPair::from_secret_key(&HexString::new(&setup.credentials.sk).decode(), Curve::Sm2p256v1);
+        for (i, tx) in signed.into_iter().enumerate() {
+            assert_eq!(i as u64 + 2, tx.height);
+            let digest = tx.clone().signing_hash(CHAIN_ID, Curve::Sm2p256v1);
+            assert!(key_pair.verify(&digest, &tx.sign));
+        }
+
+        let other = TransferBuilder::builder().set_owner(ZERO_ZLTC_ADDRESS).build();
+        assert!(setup.lattice.sign_many(setup.credentials, CHAIN_ID, vec![other]).is_err());
+    }
+
+    #[test]
+    fn test_sign_and_send_tx_with_curve() {
+        let (sender, receiver) = mpsc::channel();
+        let http_client = mock_node(move |method, params| match method {
+            "wallet_sendRawTBlock" => {
+                sender.send(params[0].clone()).unwrap();
+                json!(format!("0x{:064x}", 1))
+            }
+            method => panic!("unexpected method {}", method),
+        });
+        let lattice = LatticeClient::new(
+            ChainConfig { curve: Curve::Sm2p256v1, token_less: true },
+            ConnectingNodeConfig { ip: http_client.ip.clone(), http_port: http_client.port, websocket_port: 0 },
+            None,
+            None,
+            None,
+        );
+        let credentials = Setup::new().credentials;
+        let sk = HexString::new(&credentials.sk).decode();
+        let new_tx = || {
+            TransferBuilder::builder()
+                .set_current_block(LatestBlock {
+                    height: 1,
+                    hash: ZERO_HASH_STRING.to_string(),
+                    daemon_hash: ZERO_HASH_STRING.to_string(),
+                })
+                .set_owner(&credentials.account_address)
+                .set_linker(ZERO_ZLTC_ADDRESS)
+                .set_payload(PREFIX_OF_HEX)
+                .build()
+        };
+
+        for curve in [Curve::Sm2p256v1, Curve::Secp256k1] {
+            let tx = new_tx();
+            if curve == Curve::Sm2p256v1 {
+                lattice.sign_and_send_tx(credentials.clone(), CHAIN_ID, tx.clone()).unwrap();
+            } else {
+                lattice.sign_and_send_tx_with_curve(credentials.clone(), CHAIN_ID, tx.clone(), curve).unwrap();
+            }
+            let raw_tx = receiver.recv().unwrap();
+            let signature = raw_tx["sign"].as_str().unwrap();
+            let digest = tx.clone().signing_hash(CHAIN_ID, curve);
+            assert!(KeyPair::from_secret_key(&sk, curve).verify(&digest, signature), "{:?}", curve);
+        }
+
+        let invalid = Credentials { sk: format!("0x{}", "ff".repeat(32)), ..credentials.clone() };
+        assert!(lattice.sign_and_send_tx_with_curve(invalid, CHAIN_ID, new_tx(), Curve::Secp256k1).is_err());
+    }
+
+    #[test]
+    fn test_export_receipts() {
+        const ADDRESS: &str = "zltc_Z1pnS94bP4hQSYLs4aP4UwBP9pH8bEvhi";
+
+        let http_client = mock_node(|method, params| match method {
+            "latc_getTBlockByNumber" => {
+                let height = params[1].as_u64().unwrap();
+                json!({
+                    "number": height,
+                    "hash": format!("0x{:064x}", height),
+                    "parentHash": format!("0x{:064x}", height - 1),
+                    "daemonHash": ZERO_HASH_STRING,
+                    "timestamp": 1719804630 + height
+                })
+            }
+            "latc_getReceipt" => {
+                let hash = params[0].as_str().unwrap();
+                json!({
+                    "contractAddress": ZERO_ZLTC_ADDRESS,
+                    "contractRet": "0x",
+                    "dblockHash": ZERO_HASH_STRING,
+                    "dblockNumber": 10,
+                    "jouleUsed": if hash.ends_with('1') { 21 } else { 0 },
+                    "receiptIndex": 0,
+                    "success": hash.ends_with('1'),
+                    "tblockHash": hash,
+                    "version": 3
+                })
+            }
+            method => panic!("unexpected method {}", method),
+        });
+        let lattice = LatticeClient::new(
+            ChainConfig { curve: Curve::Sm2p256v1, token_less: true },
+            ConnectingNodeConfig { ip: http_client.ip.clone(), http_port: http_client.port, websocket_port: 0 },
+            None,
+            None,
+            None,
+        );
+
+        let mut csv = Vec::new();
+        assert_eq!(2, lattice.export_receipts(CHAIN_ID, ADDRESS, 1, 2, &mut csv, ExportFormat::Csv).unwrap());
+        assert_eq!(String::from_utf8(csv).unwrap(), format!(
+            "hash,height,success,joule_used,timestamp\n0x{:064x},1,true,21,1719804631\n0x{:064x},2,false,0,1719804632\n",
+            1, 2
+        ));
+
+        let mut jsonl = Vec::new();
+        assert_eq!(2, lattice.export_receipts(CHAIN_ID, ADDRESS, 1, 2, &mut jsonl, ExportFormat::Jsonl).unwrap());
+        let rows: Vec<serde_json::Value> = String::from_utf8(jsonl).unwrap().lines().map(|line| serde_json::from_str(line).unwrap()).collect();
+        assert_eq!(rows[1], json!({
+            "hash": format!("0x{:064x}", 2),
+            "height": 2,
+            "success": false,
+            "joule_used": 0,
+            "timestamp": 1719804632
+        }));
+        assert_eq!(2, rows.len());
+    }
+
+    #[test]
+    fn test_shutdown_stops_subscriptions() {
+        use futures_util::StreamExt;
+
+        let lattice = Setup::new().lattice;
+        // 没有监听的端口，订阅任务会一直重连
+        let ws_config = ConnectingNodeConfig {
+            ip: String::from("127.0.0.1"),
+            http_port: 0,
+            websocket_port: 1,
+        };
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let guard = runtime.enter();
+        let event_abi = r#"[{"anonymous": false, "inputs": [{"indexed": true, "internalType": "uint64", "name": "protocolUri", "type": "uint64"}], "name": "ProtocolAdded", "type": "event"}]"#;
+        let mut events = Box::pin(lattice.subscribe_events(&ws_config, 1, "zltc_QLbz7JHiBTspUvTPzLHy5biDS9mu53mmv", event_abi, "ProtocolAdded").unwrap());
+        let mut blocks = Box::pin(lattice.subscribe_daemon_blocks(&ws_config));
+        let handles: Vec<AbortHandle> = lattice.subscriptions.lock().unwrap().clone();
+        drop(guard);
+        assert_eq!(2, handles.len());
+        assert!(handles.iter().all(|handle| !handle.is_finished()));
+
+        lattice.shutdown();
+        // 每个订阅任务停止后，对应的流都会结束
+        let timeout = Duration::from_secs(5);
+        assert!(runtime.block_on(async { tokio::time::timeout(timeout, events.next()).await }).unwrap().is_none());
+        assert!(runtime.block_on(async { tokio::time::timeout(timeout, blocks.next()).await }).unwrap().is_none());
+        // 任务先释放订阅流的发送端，之后才被标记为结束
+        let deadline = Instant::now() + timeout;
+        while !handles.iter().all(|handle| handle.is_finished()) {
+            assert!(Instant::now() < deadline, "订阅任务没有结束");
+            thread::sleep(Duration::from_millis(10));
+        }
+    }
+
+    #[test]
+    fn test_wait_for_receipts() {
+        use std::collections::HashMap;
+
+        use crate::mock_node::MockNode;
+
+        const FIRST: &str = "0x01";
+        const SECOND: &str = "0x02";
+        const NEVER: &str = "0x03";
+
+        // FIRST在第1次查询时上链，SECOND在第2次查询时上链，NEVER不上链
+        let polls = Arc::new(Mutex::new(HashMap::<String, usize>::new()));
+        let node = Arc::new(MockNode::new());
+        let counter = Arc::clone(&polls);
+        node.register("latc_getReceipt", move |params| {
+            let hash = params[0].as_str().unwrap().to_string();
+            let mut polls = counter.lock().unwrap();
+            let count = polls.entry(hash.clone()).or_default();
+            *count += 1;
+            match (hash.as_str(), *count) {
+                (FIRST, _) | (SECOND, 2..) => json!({
+                    "contractAddress": ZERO_ZLTC_ADDRESS,
+                    "contractRet": "0x",
+                    "dblockHash": ZERO_HASH_STRING,
+                    "dblockNumber": 1,
+                    "jouleUsed": 0,
+                    "receiptIndex": 0,
+                    "success": true,
+                    "tblockHash": hash,
+                    "version": 3
+                }),
+                _ => serde_json::Value::Null,
+            }
+        });
+        let lattice = mock_lattice_client(node, None);
+
+        let receipts = lattice.wait_for_receipts(CHAIN_ID, &[SECOND, FIRST], Duration::from_secs(5)).unwrap();
+        let hashes: Vec<_> = receipts.iter().map(|receipt| serde_json::to_value(receipt).unwrap()["tblockHash"].clone()).collect();
+        assert_eq!(hashes, vec![json!(SECOND), json!(FIRST)]);
+        assert_eq!(polls.lock().unwrap()[FIRST], 1);
+        assert_eq!(polls.lock().unwrap()[SECOND], 2);
+
+        let err = lattice.wait_for_receipts(CHAIN_ID, &[FIRST, NEVER], Duration::from_millis(300)).unwrap_err();
+        let timeout = std::error::Error::source(&err).unwrap().downcast_ref::<WaitReceiptsTimeout>().unwrap();
+        assert_eq!(timeout.pending(), 1);
+        assert!(timeout.receipts[0].is_some());
+        assert!(timeout.receipts[1].is_none());
+    }
+
+    #[test]
+    fn test_wait_for_receipts_bounded_concurrency() {
+        use std::sync::atomic::AtomicUsize;
+
+        use crate::constants::RECEIPT_QUERY_CONCURRENCY;
+        use crate::mock_node::MockNode;
+
+        let in_flight = Arc::new(AtomicUsize::new(0));
+        let max_in_flight = Arc::new(AtomicUsize::new(0));
+        let node = Arc::new(MockNode::new());
+        let (current, max) = (Arc::clone(&in_flight), Arc::clone(&max_in_flight));
+        node.register("latc_getReceipt", move |params| {
+            max.fetch_max(current.fetch_add(1, Ordering::SeqCst) + 1, Ordering::SeqCst);
+            thread::sleep(Duration::from_millis(10));
+            current.fetch_sub(1, Ordering::SeqCst);
+            json!({
+                "contractAddress": ZERO_ZLTC_ADDRESS,
+                "contractRet": "0x",
+                "dblockHash": ZERO_HASH_STRING,
+                "dblockNumber": 1,
+                "jouleUsed": 0,
+                "receiptIndex": 0,
+                "success": true,
+                "tblockHash": params[0],
+                "version": 3
+            })
+        });
+        let lattice = mock_lattice_client(node, None);
+
+        let hashes: Vec<String> = (0..RECEIPT_QUERY_CONCURRENCY * 4).map(|i| format!("0x{:02x}", i)).collect();
+        let hashes: Vec<&str> = hashes.iter().map(String::as_str).collect();
+        let receipts = lattice.wait_for_receipts(CHAIN_ID, &hashes, Duration::from_secs(5)).unwrap();
+        assert_eq!(receipts.len(), hashes.len());
+        assert!(max_in_flight.load(Ordering::SeqCst) <= RECEIPT_QUERY_CONCURRENCY);
+    }
+
+    #[test]
+    fn test_wait_for_confirmations() {
+        use crate::mock_node::MockNode;
+
+        const HASH: &str = "0x01";
+
+        // 回执在守护区块10，每次查询最新的守护区块高度加1
+        let height = Arc::new(Mutex::new(10u64));
+        let node = Arc::new(MockNode::new());
+        node.register_result("latc_getReceipt", json!({
+            "contractAddress": ZERO_ZLTC_ADDRESS,
+            "contractRet": "0x",
+            "dblockHash": ZERO_HASH_STRING,
+            "dblockNumber": 10,
+            "jouleUsed": 0,
+            "receiptIndex": 0,
+            "success": true,
+            "tblockHash": HASH,
+            "version": 3
+        }));
+        let counter = Arc::clone(&height);
+        node.register("latc_getCurrentDBlock", move |_| {
+            let mut height = counter.lock().unwrap();
+            *height += 1;
+            json!({"hash": ZERO_HASH_STRING, "parentHash": ZERO_HASH_STRING, "number": *height, "timestamp": 0, "version": 3})
+        });
+        let lattice = mock_lattice_client(node, None);
+
+        let receipt = lattice.wait_for_confirmations(CHAIN_ID, HASH, 3, Duration::from_secs(5)).unwrap();
+        assert_eq!(receipt.dblock_height(), 10);
+        assert_eq!(*height.lock().unwrap(), 13);
+        assert_eq!(lattice.confirmations(CHAIN_ID, &receipt).unwrap(), 4);
+
+        assert!(lattice.wait_for_confirmations(CHAIN_ID, HASH, 100, Duration::from_millis(300)).is_err());
+    }
+
     #[test]
     fn test_deploy_counter_contract() {
         let setup = Setup::new();
@@ -542,6 +1981,24 @@ mod test {
         }
     }
 
+    #[test]
+    fn test_new_cancel_transaction() {
+        let owner = "zltc_Z1pnS94bP4hQSYLs4aP4UwBP9pH8bEvhi";
+        let parent_hash = "0x616bf03baa685df9fddeff4701f170b30176e54120df726142a534f8f2b51873";
+        let transaction = LatticeClient::new_cancel_transaction(
+            owner,
+            LatestBlock {
+                height: 2,
+                hash: parent_hash.to_string(),
+                daemon_hash: ZERO_HASH_STRING.to_string(),
+            },
+        );
+        assert_eq!(transaction.height, 3);
+        assert_eq!(transaction.parent_hash, parent_hash);
+        assert_eq!(transaction.linker, Some(owner.to_string()));
+        assert_eq!(transaction.amount, None);
+    }
+
     #[test]
     fn test_decrypt_file_key_from_credentials() {
         let file_key = r#"{"uuid":"123f1bf5-5599-45c4-8566-9a6440ba359f","address":"zltc_Z1pnS94bP4hQSYLs4aP4UwBP9pH8bEvhi","cipher":{"aes":{"cipher":"aes-128-ctr","cipherText":"8f6de52c0be43ae438feddea4c210772da23b9333242b7416446eae889b594e0","iv":"1ad693b4d8089da0492b9c8c49bc60d3"},"kdf":{"kdf":"scrypt","kdfParams":{"DKLen":32,"n":262144,"p":1,"r":8,"salt":"309210a97fbf705eed7bf3485c16d6922a21591297b52c0c59b4f7495863e300"}},"cipherText":"8f6de52c0be43ae438feddea4c210772da23b9333242b7416446eae889b594e0","mac":"335fab3901f8f5c4408b7d6a310ec29cf5bd3792deb696f1b10282e823241c96"},"isGM":true}"#;
@@ -551,9 +2008,60 @@ mod test {
             passphrase: Some(String::from("Root1234")),
             file_key: Some(file_key.to_string()),
         };
-        let sk = credentials.get_sk();
+        let sk = credentials.get_sk().unwrap();
         let expect =
             "0x23d5b2a2eb0a9c8b86d62cbc3955cfd1fb26ec576ecc379f402d0f5d2b27a7bb".to_string();
         assert_eq!(expect, sk)
     }
+
+    #[test]
+    fn test_decrypt_file_key_with_leading_zero_secret() {
+        let expect = "0x00d5b2a2eb0a9c8b86d62cbc3955cfd1fb26ec576ecc379f402d0f5d2b27a7bb";
+        let secret_key = HexString::new(expect).decode();
+        let file_key = FileKey::from_secret_key(&secret_key, b"Root1234", Curve::Sm2p256v1);
+        let credentials = Credentials {
+            account_address: String::from(""),
+            sk: String::from(""),
+            passphrase: Some(String::from("Root1234")),
+            file_key: Some(serde_json::to_string(&file_key).unwrap()),
+        };
+        assert_eq!(expect, credentials.get_sk().unwrap());
+    }
+
+    #[test]
+    fn test_get_sk_prefix_normalization() {
+        let expect = "0x23d5b2a2eb0a9c8b86d62cbc3955cfd1fb26ec576ecc379f402d0f5d2b27a7bb";
+        let new_credentials = |sk: &str| Credentials {
+            account_address: String::from("zltc_Z1pnS94bP4hQSYLs4aP4UwBP9pH8bEvhi"),
+            sk: sk.to_string(),
+            passphrase: None,
+            file_key: None,
+        };
+
+        assert_eq!(expect, new_credentials(expect).get_sk().unwrap());
+        assert_eq!(expect, new_credentials(expect.trim_start_matches("0x")).get_sk().unwrap());
+        assert!(new_credentials("0x23d5b2a2eb0a9c8b86d62cbc3955cfd1fb26ec576ecc379f402d0f5d2b27a7bz").get_sk().is_err());
+        assert!(new_credentials("0x23d5b2a2").get_sk().is_err());
+        assert!(new_credentials("").get_sk().is_err());
+    }
+
+    #[test]
+    fn test_credentials_from_wif() {
+        let address = "zltc_Z1pnS94bP4hQSYLs4aP4UwBP9pH8bEvhi";
+        let sk = HexString::new("0x23d5b2a2eb0a9c8b86d62cbc3955cfd1fb26ec576ecc379f402d0f5d2b27a7bb").decode();
+        let uncompressed = base58check::encode(&[0x80], &sk, base58check::DEFAULT_CHECKSUM_LENGTH);
+        let compressed = base58check::encode(&[0xef], &[sk.as_slice(), &[0x01]].concat(), base58check::DEFAULT_CHECKSUM_LENGTH);
+
+        for wif in [uncompressed, compressed] {
+            let credentials = Credentials::from_wif(address, &wif).unwrap();
+            assert_eq!(address, credentials.account_address);
+            assert_eq!(sk, HexString::new(&credentials.get_sk().unwrap()).decode());
+        }
+
+        let bad_version = base58check::encode(&[0x00], &sk, base58check::DEFAULT_CHECKSUM_LENGTH);
+        assert!(Credentials::from_wif(address, &bad_version).is_err());
+        let bad_length = base58check::encode(&[0x80], &sk[..31], base58check::DEFAULT_CHECKSUM_LENGTH);
+        assert!(Credentials::from_wif(address, &bad_length).is_err());
+        assert!(Credentials::from_wif(address, "not-a-wif").is_err());
+    }
 }
@@ -5,5 +5,12 @@ pub mod client;
 mod constants;
 mod builder;
 pub mod contract;
+pub mod event;
+pub mod daemon_block;
+pub mod storage;
 mod account_lock;
 mod account_cache;
+#[cfg(test)]
+mod mock;
+#[cfg(any(test, feature = "mock-node"))]
+pub mod mock_node;
@@ -0,0 +1,79 @@
+use std::collections::HashMap;
+use std::io::{Read, Write};
+use std::net::TcpListener;
+use std::thread;
+
+use serde_json::Value;
+
+use crate::client::{HttpClient, HttpRequest};
+use crate::mock_node::MockNode;
+
+/// # 启动一个通过http访问的模拟节点，由`handler`根据json-rpc请求的方法名和参数返回结果
+///
+/// 用于需要真实http连接的测试，例如异步客户端和响应体的处理；其它测试直接使用`MockNode`作为传输层
+///
+/// ## 入参
+/// + `handler: F`: 入参为方法名和参数，出参为json-rpc的result
+///
+/// ## 出参
+/// + `HttpClient`: 连接模拟节点的http客户端
+pub(crate) fn mock_node<F>(handler: F) -> HttpClient
+where
+    F: Fn(&str, &Value) -> Value + Send + Sync + 'static,
+{
+    let node = MockNode::new();
+    node.register_fallback(handler);
+    serve(node)
+}
+
+/// # 在本地的随机端口上通过http提供模拟节点
+///
+/// ## 入参
+/// + `node: MockNode`: 处理json-rpc请求的模拟节点
+///
+/// ## 出参
+/// + `HttpClient`: 连接模拟节点的http客户端
+fn serve(node: MockNode) -> HttpClient {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            let mut stream = stream.unwrap();
+            let mut buffer = Vec::new();
+            let mut chunk = [0u8; 1024];
+            // 读取请求头和请求体
+            let body = loop {
+                let n = stream.read(&mut chunk).unwrap();
+                if n == 0 {
+                    break String::new();
+                }
+                buffer.extend_from_slice(&chunk[..n]);
+                let request = String::from_utf8_lossy(&buffer).to_string();
+                if let Some(index) = request.find("\r\n\r\n") {
+                    let content_length = request[..index]
+                        .lines()
+                        .find_map(|line| {
+                            let (k, v) = line.split_once(':')?;
+                            k.eq_ignore_ascii_case("content-length")
+                                .then(|| v.trim().parse::<usize>().unwrap())
+                        })
+                        .unwrap_or(0);
+                    if buffer.len() >= index + 4 + content_length {
+                        break request[index + 4..].to_string();
+                    }
+                }
+            };
+            if body.is_empty() {
+                continue;
+            }
+            let response = node.send(&body, HashMap::new()).unwrap();
+            let message = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                response.len(),
+                response
+            );
+            stream.write_all(message.as_bytes()).unwrap();
+        }
+    });
+    HttpClient::new("127.0.0.1", port)
+}
@@ -0,0 +1,608 @@
+//! # 内存中的模拟节点
+//!
+//! `MockNode`实现了`HttpRequest`，通过`HttpClient::set_transport`接入后，json-rpc请求在进程内处理，不需要真实的节点。
+//!
+//! 内置了以下方法的默认应答：
+//! + `latc_getCurrentTBDB`/`latc_getPendingTBDB`: 账户最近一次发送的交易，没有交易时为创世区块
+//! + `latc_getCurrentDBlock`: 守护区块
+//! + `wallet_sendRawTBlock`: 记录交易并按发送顺序返回交易哈希，重复发送同一笔交易时返回第一次的哈希
+//! + `latc_getReceipt`: 已发送的交易返回成功的回执，否则返回空
+//!
+//! 注册固定数据或处理函数可以覆盖默认应答，也可以应答其它方法：
+//!
+//! ```ignore
+//! let node = Arc::new(MockNode::new());
+//! node.register_result("wallet_preExecuteContract", json!({ ... }));
+//! node.register("latc_getReceipt", |params| json!({ "tblockHash": params[0] }));
+//!
+//! let mut http_client = HttpClient::new("127.0.0.1", 0);
+//! http_client.set_transport(node.clone());
+//!
+//! // 或者直接创建连接到模拟节点的客户端
+//! let lattice = mock_lattice_client(node.clone(), None);
+//! ```
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use serde_json::{json, Value};
+
+use crypto::transaction::RawTransaction;
+use model::block::{DBlock, LatestBlock};
+use model::constants::ZERO_HASH_STRING;
+use model::{Curve, Error};
+
+use crate::client::{HttpClient, HttpRequest};
+use crate::constants::JSON_RPC_VERSION;
+use crate::lattice::{ChainConfig, ConnectingNodeConfig, LatticeClient, Options};
+
+/// json-rpc的错误码，节点不支持请求的方法
+pub const JSON_RPC_METHOD_NOT_FOUND: i32 = -32601;
+/// 模拟节点的守护区块哈希
+pub const MOCK_DAEMON_HASH: &str = "0xe8df1f1e250cd0eac75eee3f8733e26e9422ef5ea88650ab54498cd8e4928144";
+
+/// 处理json-rpc请求的参数，返回json-rpc的result
+type Handler = Arc<dyn Fn(&Value) -> Value + Send + Sync>;
+/// 处理json-rpc请求的方法名和参数，返回json-rpc的result
+type FallbackHandler = Arc<dyn Fn(&str, &Value) -> Value + Send + Sync>;
+
+/// 内存中的模拟节点
+#[derive(Default)]
+pub struct MockNode {
+    /// 注册的处理函数，优先于默认应答
+    handlers: Mutex<HashMap<String, Handler>>,
+    /// 处理所有方法的函数，优先于默认应答，按方法名注册的处理函数优先于它
+    fallback: Mutex<Option<FallbackHandler>>,
+    /// 按发送顺序记录的交易，以及对应的交易哈希
+    transactions: Mutex<Vec<(String, RawTransaction)>>,
+}
+
+impl MockNode {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// # 注册方法的处理函数，覆盖默认应答
+    ///
+    /// ## 入参
+    /// + `method: &str`: json-rpc的方法名
+    /// + `handler: F`: 入参为json-rpc的params，出参为result，返回`Value::Null`时客户端得到结果为空的错误
+    pub fn register<F>(&self, method: &str, handler: F)
+    where
+        F: Fn(&Value) -> Value + Send + Sync + 'static,
+    {
+        self.handlers.lock().unwrap().insert(method.to_string(), Arc::new(handler));
+    }
+
+    /// # 注册处理所有方法的函数，覆盖默认应答，按方法名注册的处理函数仍然优先
+    ///
+    /// ## 入参
+    /// + `handler: F`: 入参为json-rpc的方法名和params，出参为result
+    pub fn register_fallback<F>(&self, handler: F)
+    where
+        F: Fn(&str, &Value) -> Value + Send + Sync + 'static,
+    {
+        *self.fallback.lock().unwrap() = Some(Arc::new(handler));
+    }
+
+    /// # 注册方法的固定应答
+    ///
+    /// ## 入参
+    /// + `method: &str`: json-rpc的方法名
+    /// + `result: Value`: json-rpc的result
+    pub fn register_result(&self, method: &str, result: Value) {
+        self.register(method, move |_| result.clone());
+    }
+
+    /// # 已发送的交易，按发送顺序排列
+    ///
+    /// ## 出参
+    /// + `Vec<(String, RawTransaction)>`: 交易哈希和交易
+    pub fn transactions(&self) -> Vec<(String, RawTransaction)> {
+        self.transactions.lock().unwrap().clone()
+    }
+
+    /// # 处理json-rpc请求
+    ///
+    /// ## 入参
+    /// + `method: &str`: 方法名
+    /// + `params: &Value`: 参数
+    ///
+    /// ## 出参
+    /// + `Option<Value>`: 不支持的方法返回None
+    fn handle(&self, method: &str, params: &Value) -> Option<Value> {
+        let handler = self.handlers.lock().unwrap().get(method).cloned();
+        if let Some(handler) = handler {
+            return Some(handler(params));
+        }
+        let fallback = self.fallback.lock().unwrap().clone();
+        if let Some(fallback) = fallback {
+            return Some(fallback(method, params));
+        }
+        let result = match method {
+            "latc_getCurrentTBDB" | "latc_getPendingTBDB" => json!(self.latest_block(&params[0])),
+            "latc_getCurrentDBlock" => json!(DBlock {
+                hash: MOCK_DAEMON_HASH.to_string(),
+                parent_hash: ZERO_HASH_STRING.to_string(),
+                height: 1,
+                timestamp: 0,
+                version: 3,
+            }),
+            "wallet_sendRawTBlock" => {
+                let tx: RawTransaction = serde_json::from_value(params[0].clone()).ok()?;
+                let mut transactions = self.transactions.lock().unwrap();
+                let sent = transactions.iter().find(|(_, sent)| sent.owner == tx.owner && sent.sign == tx.sign);
+                if let Some((hash, _)) = sent {
+                    return Some(json!(hash));
+                }
+                let hash = format!("0x{:064x}", transactions.len() + 1);
+                transactions.push((hash.clone(), tx));
+                json!(hash)
+            }
+            "latc_getReceipt" => self.receipt(&params[0]),
+            _ => return None,
+        };
+        Some(result)
+    }
+
+    /// # 账户最近一次发送的交易，没有交易时为创世区块
+    fn latest_block(&self, address: &Value) -> LatestBlock {
+        self.transactions
+            .lock()
+            .unwrap()
+            .iter()
+            .rev()
+            .find(|(_, tx)| address == tx.owner.as_str())
+            .map(|(hash, tx)| LatestBlock {
+                height: tx.height,
+                hash: hash.clone(),
+                daemon_hash: MOCK_DAEMON_HASH.to_string(),
+            })
+            .unwrap_or_else(|| LatestBlock {
+                height: 0,
+                hash: ZERO_HASH_STRING.to_string(),
+                daemon_hash: MOCK_DAEMON_HASH.to_string(),
+            })
+    }
+
+    /// # 已发送的交易返回成功的回执，否则返回空
+    fn receipt(&self, hash: &Value) -> Value {
+        let transactions = self.transactions.lock().unwrap();
+        match transactions.iter().position(|(h, _)| hash == h.as_str()) {
+            Some(index) => json!({
+                "contractAddress": transactions[index].1.linker,
+                "contractRet": "0x",
+                "dblockHash": MOCK_DAEMON_HASH,
+                "dblockNumber": 1,
+                "jouleUsed": 0,
+                "receiptIndex": index,
+                "success": true,
+                "tblockHash": hash,
+                "version": 3
+            }),
+            None => Value::Null,
+        }
+    }
+}
+
+impl HttpRequest for MockNode {
+    fn send(&self, message: &str, _headers: HashMap<String, String>) -> Result<String, Error> {
+        let request: Value = serde_json::from_str(message)?;
+        let method = request["method"].as_str().unwrap_or_default();
+        let response = match self.handle(method, &request["params"]) {
+            Some(result) => json!({"jsonRpc": JSON_RPC_VERSION, "id": request["id"], "result": result}),
+            None => json!({
+                "jsonRpc": JSON_RPC_VERSION,
+                "id": request["id"],
+                "error": {"code": JSON_RPC_METHOD_NOT_FOUND, "message": format!("the method {} does not exist", method)}
+            }),
+        };
+        Ok(response.to_string())
+    }
+}
+
+/// # 创建通过模拟节点发送请求的客户端，链配置为secp256k1、无通证
+///
+/// ## 入参
+/// + `transport: Arc<dyn HttpRequest + Send + Sync>`: 模拟节点，或者包装了模拟节点的传输层
+/// + `options: Option<Options>`: 可选项，为None时使用默认值
+///
+/// ## 出参
+/// + `LatticeClient`
+pub fn mock_lattice_client(transport: Arc<dyn HttpRequest + Send + Sync>, options: Option<Options>) -> LatticeClient {
+    let mut http_client = HttpClient::new("127.0.0.1", 0);
+    http_client.set_transport(transport);
+    LatticeClient::new_with_http_client(
+        ChainConfig {
+            curve: Curve::Secp256k1,
+            token_less: true,
+        },
+        ConnectingNodeConfig {
+            ip: http_client.ip.clone(),
+            http_port: http_client.port,
+            websocket_port: 0,
+        },
+        http_client,
+        options,
+        None,
+        None,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use alloy_dyn_abi::DynSolValue;
+    use alloy_primitives::I256;
+
+    use crypto::transaction::TxType;
+    use model::common::{hash_eq, is_zero_hash, Address};
+    use model::convert::decompress_payload;
+    use model::{ErrorKind, HexString};
+
+    use crate::lattice::{Credentials, OptionsBuilder};
+
+    use super::*;
+
+    const CHAIN_ID: u64 = 1;
+    const ACCOUNT_ADDRESS: &str = "zltc_cWAvRSgCKgfyp5Rz5TH8srmrZsH5fVYpg";
+
+    fn new_lattice(node: Arc<MockNode>) -> LatticeClient {
+        mock_lattice_client(node, None)
+    }
+
+    fn new_credentials() -> Credentials {
+        Credentials {
+            sk: String::from("0xd2c784688ab85d689e358a7b030c9f26b8ee45e66e89d8842fa88da3b9637955"),
+            account_address: String::from(ACCOUNT_ADDRESS),
+            passphrase: None,
+            file_key: None,
+        }
+    }
+
+    #[test]
+    fn test_transfer_and_get_receipt() {
+        let node = Arc::new(MockNode::new());
+        let lattice = new_lattice(node.clone());
+        let credentials = new_credentials();
+
+        let result = lattice
+            .transfer_detailed(credentials.clone(), CHAIN_ID, "0x01", None, None)
+            .unwrap();
+        assert_eq!(result.height, 1);
+        let transactions = node.transactions();
+        assert_eq!(transactions.len(), 1);
+        let (hash, tx) = &transactions[0];
+        assert_eq!(hash, &result.hash);
+        assert_eq!(tx.owner, ACCOUNT_ADDRESS);
+        assert!(is_zero_hash(&tx.parent_hash));
+        assert!(hash_eq(&tx.daemon_hash, MOCK_DAEMON_HASH));
+        assert_eq!(TxType::from_name(&tx.ty), Some(TxType::Send));
+
+        let receipt = lattice.http_client.get_receipt(CHAIN_ID, &result.hash).unwrap();
+        assert_eq!(serde_json::to_value(&receipt).unwrap()["tblockHash"], json!(result.hash));
+        assert!(lattice.http_client.get_receipt(CHAIN_ID, ZERO_HASH_STRING).is_err());
+
+        let block = lattice
+            .http_client
+            .get_latest_block(CHAIN_ID, &Address::new(ACCOUNT_ADDRESS))
+            .unwrap();
+        assert_eq!(block.height, 1);
+        assert_eq!(block.hash, result.hash);
+    }
+
+    #[test]
+    fn test_transfer_invalid_secret_key() {
+        let node = Arc::new(MockNode::new());
+        let lattice = new_lattice(node.clone());
+        // 私钥格式正确但超出了曲线的阶
+        let credentials = Credentials {
+            sk: format!("0x{}", "ff".repeat(32)),
+            account_address: String::from(ACCOUNT_ADDRESS),
+            passphrase: None,
+            file_key: None,
+        };
+
+        assert!(lattice.transfer(credentials, CHAIN_ID, "0x01", None, None).is_err());
+        assert!(node.transactions().is_empty());
+    }
+
+    #[test]
+    fn test_registered_fixtures() {
+        let node = Arc::new(MockNode::new());
+        node.register("latc_getCurrentTBDB", |params| {
+            json!({
+                "currentTBlockNumber": 7,
+                "currentTBlockHash": params[0],
+                "currentDBlockHash": MOCK_DAEMON_HASH
+            })
+        });
+        let lattice = new_lattice(node.clone());
+
+        let block = lattice
+            .http_client
+            .get_latest_block(CHAIN_ID, &Address::new(ACCOUNT_ADDRESS))
+            .unwrap();
+        assert_eq!(block.height, 7);
+        assert_eq!(block.hash, ACCOUNT_ADDRESS);
+
+        // 没有注册的方法返回METHOD_NOT_FOUND
+        let request = json!({"jsonRpc": JSON_RPC_VERSION, "id": 1, "method": "latc_unknown", "params": []});
+        let response: Value = serde_json::from_str(&node.send(&request.to_string(), HashMap::new()).unwrap()).unwrap();
+        assert_eq!(response["error"]["code"], JSON_RPC_METHOD_NOT_FOUND);
+    }
+
+    #[test]
+    fn test_cancel_pending() {
+        const PARENT_HASH: &str = "0x616bf03baa685df9fddeff4701f170b30176e54120df726142a534f8f2b51873";
+        let node = Arc::new(MockNode::new());
+        // 账户已上链的最新区块高度为2，交易池中还有高度为3和4的交易
+        node.register_result(
+            "latc_getCurrentTBDB",
+            json!({"currentTBlockNumber": 2, "currentTBlockHash": PARENT_HASH, "currentDBlockHash": MOCK_DAEMON_HASH}),
+        );
+        node.register_result(
+            "latc_getPendingTBDB",
+            json!({"currentTBlockNumber": 4, "currentTBlockHash": format!("0x{:064x}", 4), "currentDBlockHash": MOCK_DAEMON_HASH}),
+        );
+        let lattice = new_lattice(node.clone());
+
+        let err = lattice.cancel_pending(new_credentials(), CHAIN_ID, 0).unwrap_err();
+        assert!(err.to_string().contains("不能取消"), "{}", err);
+        assert!(lattice.cancel_pending(new_credentials(), CHAIN_ID, 5).is_err());
+        let err = lattice.cancel_pending(new_credentials(), CHAIN_ID, 4).unwrap_err();
+        assert!(err.to_string().contains("只能取消最早的pending交易"), "{}", err);
+        assert!(node.transactions().is_empty());
+
+        let hash = lattice.cancel_pending(new_credentials(), CHAIN_ID, 3).unwrap();
+        let transactions = node.transactions();
+        let (sent_hash, tx) = &transactions[0];
+        assert_eq!(sent_hash, &hash);
+        assert_eq!(tx.height, 3);
+        assert!(hash_eq(&tx.parent_hash, PARENT_HASH));
+        assert_eq!(tx.linker, ACCOUNT_ADDRESS);
+    }
+
+    #[test]
+    fn test_transfer_compressed() {
+        let node = Arc::new(MockNode::new());
+        let lattice = new_lattice(node.clone());
+        let data = "memo ".repeat(200).into_bytes();
+
+        let result = lattice.transfer_compressed(new_credentials(), CHAIN_ID, &data, None, None).unwrap();
+        let transactions = node.transactions();
+        let (hash, tx) = &transactions[0];
+        assert_eq!(hash, &result.hash);
+        let payload = HexString::new(&tx.payload).decode();
+        assert!(payload.len() < data.len());
+        assert_eq!(data, decompress_payload(&payload).unwrap());
+    }
+
+    #[test]
+    fn test_idempotent_transfer() {
+        let node = Arc::new(MockNode::new());
+        let options = OptionsBuilder::builder().set_idempotency_window_seconds(60).build();
+        let lattice = mock_lattice_client(node.clone(), Some(options));
+        let credentials = new_credentials();
+
+        let first = lattice.transfer_idempotent(credentials.clone(), CHAIN_ID, "order-1", "0x01", None, None).unwrap();
+        let retried = lattice.transfer_idempotent(credentials.clone(), CHAIN_ID, "order-1", "0x01", None, None).unwrap();
+        assert_eq!(first, retried);
+        assert_eq!(node.transactions().len(), 1);
+
+        // 账户缓存的高度已经前进，内容相同但key不同的转账照常发送
+        let other = lattice.transfer_idempotent(credentials.clone(), CHAIN_ID, "order-2", "0x01", None, None).unwrap();
+        assert_ne!(first, other);
+        assert_eq!(node.transactions().len(), 2);
+
+        // 不使用幂等key时不去重
+        lattice.transfer(credentials, CHAIN_ID, "0x01", None, None).unwrap();
+        assert_eq!(node.transactions().len(), 3);
+    }
+
+    /// 按顺序模拟发送交易时的网络故障，之后的请求正常转发给模拟节点
+    enum Failure {
+        /// 连接失败，节点没有收到交易
+        Refused,
+        /// 节点收到了交易，但响应在返回途中丢失
+        ResponseLost,
+        /// 节点返回json-rpc错误，拒绝了交易
+        Rejected,
+    }
+
+    struct FlakyTransport {
+        node: Arc<MockNode>,
+        failures: Mutex<Vec<Failure>>,
+    }
+
+    impl HttpRequest for FlakyTransport {
+        fn send(&self, message: &str, headers: HashMap<String, String>) -> Result<String, Error> {
+            if !message.contains("wallet_sendRawTBlock") {
+                return self.node.send(message, headers);
+            }
+            let failure = {
+                let mut failures = self.failures.lock().unwrap();
+                (!failures.is_empty()).then(|| failures.remove(0))
+            };
+            match failure {
+                None => self.node.send(message, headers),
+                Some(Failure::Refused) => Err(Error::new("连接被拒绝")),
+                Some(Failure::ResponseLost) => {
+                    self.node.send(message, headers)?;
+                    Err(Error::new("读取响应超时"))
+                }
+                Some(Failure::Rejected) => Ok(json!({
+                    "jsonRpc": JSON_RPC_VERSION,
+                    "id": 1,
+                    "error": {"code": -32000, "message": "invalid transaction"}
+                })
+                .to_string()),
+            }
+        }
+    }
+
+    fn new_flaky_lattice(node: Arc<MockNode>, failures: Vec<Failure>) -> LatticeClient {
+        let options = OptionsBuilder::builder().set_idempotency_window_seconds(60).build();
+        let transport = Arc::new(FlakyTransport { node, failures: Mutex::new(failures) });
+        mock_lattice_client(transport, Some(options))
+    }
+
+    #[test]
+    fn test_idempotent_transfer_response_lost() {
+        let node = Arc::new(MockNode::new());
+        let lattice = new_flaky_lattice(node.clone(), vec![Failure::ResponseLost]);
+        let credentials = new_credentials();
+
+        assert!(lattice.transfer_idempotent(credentials.clone(), CHAIN_ID, "order-1", "0x01", None, None).is_err());
+        // 重试时重新发送同一笔交易，节点只记录一次
+        let retried = lattice.transfer_idempotent(credentials, CHAIN_ID, "order-1", "0x01", None, None).unwrap();
+        let transactions = node.transactions();
+        assert_eq!(transactions.len(), 1);
+        assert_eq!(transactions[0].0, retried.hash);
+        assert_eq!(transactions[0].1.height, retried.height);
+    }
+
+    #[test]
+    fn test_idempotent_transfer_connection_refused() {
+        let node = Arc::new(MockNode::new());
+        let lattice = new_flaky_lattice(node.clone(), vec![Failure::Refused, Failure::Refused]);
+        let credentials = new_credentials();
+
+        assert!(lattice.transfer_idempotent(credentials.clone(), CHAIN_ID, "order-1", "0x01", None, None).is_err());
+        // 节点没有收到交易，重试失败时不能返回Ok
+        assert!(lattice.transfer_idempotent(credentials.clone(), CHAIN_ID, "order-1", "0x01", None, None).is_err());
+        assert!(node.transactions().is_empty());
+
+        let retried = lattice.transfer_idempotent(credentials.clone(), CHAIN_ID, "order-1", "0x01", None, None).unwrap();
+        let transactions = node.transactions();
+        assert_eq!(transactions.len(), 1);
+        assert_eq!(transactions[0].0, retried.hash);
+        assert_eq!(retried, lattice.transfer_idempotent(credentials, CHAIN_ID, "order-1", "0x01", None, None).unwrap());
+        assert_eq!(node.transactions().len(), 1);
+    }
+
+    #[test]
+    fn test_idempotent_transfer_rejected() {
+        let node = Arc::new(MockNode::new());
+        let lattice = new_flaky_lattice(node.clone(), vec![Failure::Rejected]);
+        let credentials = new_credentials();
+
+        let err = lattice.transfer_idempotent(credentials.clone(), CHAIN_ID, "order-1", "0x01", None, None).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::JsonRpc);
+        // 节点拒绝了交易，同一个key可以重新签名发送
+        lattice.transfer_idempotent(credentials, CHAIN_ID, "order-1", "0x01", None, None).unwrap();
+        assert_eq!(node.transactions().len(), 1);
+    }
+
+    #[test]
+    fn test_idempotency_key_requires_window() {
+        let node = Arc::new(MockNode::new());
+        let lattice = new_lattice(node.clone());
+        assert!(lattice.transfer_idempotent(new_credentials(), CHAIN_ID, "order-1", "0x01", None, None).is_err());
+        assert!(node.transactions().is_empty());
+    }
+
+    #[test]
+    fn test_token_less_rejects_fees() {
+        let node = Arc::new(MockNode::new());
+        let lattice = new_lattice(node.clone());
+        let credentials = new_credentials();
+
+        assert!(lattice.transfer(credentials.clone(), CHAIN_ID, "0x01", Some(1), None).is_err());
+        assert!(lattice.transfer(credentials.clone(), CHAIN_ID, "0x01", None, Some(1)).is_err());
+        assert!(node.transactions().is_empty());
+
+        lattice.transfer(credentials, CHAIN_ID, "0x01", Some(0), None).unwrap();
+        assert_eq!(node.transactions().len(), 1);
+    }
+
+    #[test]
+    fn test_transfer_with_pow() {
+        let node = Arc::new(MockNode::new());
+        let options = OptionsBuilder::builder().set_pow_difficulty(Some(4)).build();
+        let lattice = mock_lattice_client(node.clone(), Some(options));
+        let credentials = new_credentials();
+
+        lattice.transfer(credentials, CHAIN_ID, "0x01", None, None).unwrap();
+        let transactions = node.transactions();
+        let (_, tx) = &transactions[0];
+        assert!(tx.proof_of_work.starts_with("0x"));
+        assert_ne!(tx.proof_of_work, "0x");
+    }
+
+    #[test]
+    fn test_deploy_contract_predicted_address() {
+        // 部署者ACCOUNT_ADDRESS在高度1部署合约的回执中的合约地址
+        const CONTRACT_ADDRESS: &str = "zltc_i97KEBEq6EW3yqRhcpyQA24xuHSn7s7SH";
+        let node = Arc::new(MockNode::new());
+        node.register("latc_getReceipt", |params| {
+            json!({
+                "contractAddress": CONTRACT_ADDRESS,
+                "contractRet": "0x",
+                "dblockHash": MOCK_DAEMON_HASH,
+                "dblockNumber": 1,
+                "jouleUsed": 0,
+                "receiptIndex": 0,
+                "success": true,
+                "tblockHash": params[0],
+                "version": 3
+            })
+        });
+        let lattice = new_lattice(node);
+        let credentials = new_credentials();
+
+        let deploy = lattice
+            .deploy_contract_detailed(credentials.clone(), CHAIN_ID, "0x6080", None, None, None)
+            .unwrap();
+        assert_eq!(deploy.height, 1);
+        assert_eq!(deploy.predicted_address, CONTRACT_ADDRESS);
+        let receipt = lattice.confirm_deployment(CHAIN_ID, &deploy, Duration::from_secs(1)).unwrap();
+        assert_eq!(receipt.contract_address(), CONTRACT_ADDRESS);
+
+        let deploy = lattice
+            .deploy_contract_detailed(credentials, CHAIN_ID, "0x6080", None, None, None)
+            .unwrap();
+        assert_eq!(deploy.height, 2);
+        assert!(lattice.confirm_deployment(CHAIN_ID, &deploy, Duration::from_secs(1)).is_err());
+    }
+
+    #[test]
+    fn test_call_contract_fn() {
+        const COUNTER_ABI: &str = r#"[{"inputs":[],"name":"getCount","outputs":[{"internalType":"int256","name":"","type":"int256"}],"stateMutability":"view","type":"function"},{"inputs":[],"name":"incrementCounter","outputs":[],"stateMutability":"nonpayable","type":"function"}]"#;
+        const CONTRACT_ADDRESS: &str = "zltc_i97KEBEq6EW3yqRhcpyQA24xuHSn7s7SH";
+        let node = Arc::new(MockNode::new());
+        node.register("wallet_preExecuteContract", |params| {
+            json!({
+                "contractAddress": params[0]["linker"],
+                "contractRet": format!("0x{:064x}", 5),
+                "dblockHash": MOCK_DAEMON_HASH,
+                "dblockNumber": 1,
+                "jouleUsed": 0,
+                "receiptIndex": 0,
+                "success": true,
+                "tblockHash": ZERO_HASH_STRING,
+                "version": 3
+            })
+        });
+        let lattice = new_lattice(node.clone());
+        let credentials = new_credentials();
+
+        let hash = lattice
+            .call_contract_fn(credentials.clone(), CHAIN_ID, CONTRACT_ADDRESS, COUNTER_ABI, "incrementCounter", vec![])
+            .unwrap();
+        let transactions = node.transactions();
+        assert_eq!(transactions.len(), 1);
+        assert_eq!(transactions[0].0, hash);
+        assert_eq!(transactions[0].1.linker, CONTRACT_ADDRESS);
+        assert_eq!(transactions[0].1.code, "0x5b34b966");
+        assert!(lattice
+            .call_contract_fn(credentials, CHAIN_ID, CONTRACT_ADDRESS, COUNTER_ABI, "resetCounter", vec![])
+            .is_err());
+
+        let outputs = lattice
+            .pre_call_fn(CHAIN_ID, ACCOUNT_ADDRESS, CONTRACT_ADDRESS, COUNTER_ABI, "getCount", vec![])
+            .unwrap();
+        assert_eq!(outputs, vec![DynSolValue::Int(I256::try_from(5).unwrap(), 256)]);
+    }
+}
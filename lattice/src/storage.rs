@@ -0,0 +1,68 @@
+use alloy_primitives::{keccak256, U256};
+
+use crypto::hash::sm3;
+use model::Curve;
+
+/// # 计算映射中键对应的存储槽：`hash(key ‖ base)`
+///
+/// ## 入参
+/// + `key: &[u8]`: 键，值类型（地址、整数等）为32字节的abi编码，`string`和`bytes`为原始字节
+/// + `base: U256`: 映射变量所在的存储槽
+/// + `curve: Curve`: 链的曲线，国密链的合约使用sm3，其它使用keccak256
+///
+/// ## 出参
+/// + `U256`: 键对应的值所在的存储槽
+pub fn mapping_slot(key: &[u8], base: U256, curve: Curve) -> U256 {
+    storage_hash(&[key, &base.to_be_bytes::<32>()].concat(), curve)
+}
+
+/// # 计算动态数组中元素的存储槽：`hash(base) + index * element_words`
+///
+/// ## 入参
+/// + `base: U256`: 数组变量所在的存储槽，该槽保存数组的长度
+/// + `index: U256`: 元素的下标
+/// + `element_words: u64`: 每个元素占用的存储槽数，小于32字节且被紧凑存储的元素需自行计算槽内的偏移
+/// + `curve: Curve`: 链的曲线，国密链的合约使用sm3，其它使用keccak256
+///
+/// ## 出参
+/// + `U256`: 元素的第一个存储槽
+pub fn array_element_slot(base: U256, index: U256, element_words: u64, curve: Curve) -> U256 {
+    storage_hash(&base.to_be_bytes::<32>(), curve)
+        .wrapping_add(index.wrapping_mul(U256::from(element_words)))
+}
+
+/// # 合约计算存储槽使用的哈希
+fn storage_hash(data: &[u8], curve: Curve) -> U256 {
+    let digest = match curve {
+        Curve::Sm2p256v1 => sm3(data),
+        _ => keccak256(data).0,
+    };
+    U256::from_be_bytes(digest)
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy_primitives::hex;
+
+    use super::*;
+
+    #[test]
+    fn test_mapping_slot() {
+        // 以太坊中 mapping(address => uint256) 位于槽0时，地址0x5f2b...9920对应的槽
+        let mut key = [0u8; 32];
+        key[12..].copy_from_slice(&hex::decode("5f2be9a02b43f748ee460bf36eed24fafa109920").unwrap());
+        let expected = keccak256([key, [0u8; 32]].concat());
+        assert_eq!(U256::from_be_bytes(expected.0), mapping_slot(&key, U256::ZERO, Curve::Secp256k1));
+
+        let expected = sm3(&[key, [0u8; 32]].concat());
+        assert_eq!(U256::from_be_bytes(expected), mapping_slot(&key, U256::ZERO, Curve::Sm2p256v1));
+    }
+
+    #[test]
+    fn test_array_element_slot() {
+        let base = U256::from(3);
+        let first = array_element_slot(base, U256::ZERO, 1, Curve::Secp256k1);
+        assert_eq!(U256::from_be_bytes(keccak256(base.to_be_bytes::<32>()).0), first);
+        assert_eq!(first + U256::from(4), array_element_slot(base, U256::from(2), 2, Curve::Secp256k1));
+    }
+}
@@ -15,12 +15,22 @@ pub struct LatestBlock {
 }
 
 /// 账户区块
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct TBlock {
+    /// 账户区块高度
     #[serde(rename = "number")]
-    height: u64,
-    parent_hash: String,
-    daemon_hash: String,
+    pub height: u64,
+    /// 账户区块哈希，即交易哈希
+    pub hash: String,
+    /// 上一个账户区块的哈希
+    #[serde(rename = "parentHash")]
+    pub parent_hash: String,
+    /// 守护区块哈希
+    #[serde(rename = "daemonHash")]
+    pub daemon_hash: String,
+    /// 交易的时间戳
+    #[serde(default)]
+    pub timestamp: u64,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -1,14 +1,14 @@
+use rand::{CryptoRng, RngCore};
 use sha256::digest;
 
-use crate::constants::ADDRESS_TITLE;
+use crate::constants::{ADDRESS_CHECKSUM_LENGTH, ADDRESS_TITLE, ADDRESS_VERSION, ETHEREUM_ADDRESS_LENGTH, PREFIX_OF_HEX, ZERO_HASH_STRING};
+use crate::Error;
 
 /// hex字符串结构体
 pub struct HexString {
     pub hex_string: String,
 }
 
-const HEX_PREFIX: &str = "0x";
-
 impl HexString {
     /// # 接收一个hex字符串来初始化一个hex对象
     pub fn new(hex: &str) -> Self {
@@ -18,12 +18,17 @@ impl HexString {
     /// # 接收一个`&[u8]`字符串来初始化一个hex对象
     pub fn from(bs: &[u8]) -> Self {
         let hex_string = hex::encode(bs);
-        HexString { hex_string: format!("{}{}", HEX_PREFIX, hex_string) }
+        HexString { hex_string: format!("{}{}", PREFIX_OF_HEX, hex_string) }
+    }
+
+    /// # 生成指定字节长度的随机hex字符串，用于盐、初始化向量、请求ID等
+    pub fn random(byte_len: usize) -> Self {
+        Self::from(&random_bytes(byte_len))
     }
 
     /// # 获取没有前缀0x的hex string
     pub fn clean_hex_string(&self) -> String {
-        if let Some(res) = self.hex_string.strip_prefix(HEX_PREFIX) {
+        if let Some(res) = self.hex_string.strip_prefix(PREFIX_OF_HEX) {
             res.to_string()
         } else {
             self.hex_string.clone()
@@ -36,9 +41,78 @@ impl HexString {
     }
 }
 
+/// # 比较两个哈希是否相等，忽略`0x`前缀和大小写
+///
+/// ## 入参
+/// + `a: &str`: 示例：0xABC...
+/// + `b: &str`: 示例：abc...
+///
+/// ## 出参
+/// + `bool`
+pub fn hash_eq(a: &str, b: &str) -> bool {
+    strip_hex_prefix(a).eq_ignore_ascii_case(strip_hex_prefix(b))
+}
+
+/// # 去掉`0x`或`0X`前缀
+fn strip_hex_prefix(hex: &str) -> &str {
+    hex.strip_prefix(PREFIX_OF_HEX).or_else(|| hex.strip_prefix("0X")).unwrap_or(hex)
+}
+
+/// # 判断是否为零哈希，忽略`0x`前缀和大小写
+///
+/// ## 入参
+/// + `hash: &str`
+///
+/// ## 出参
+/// + `bool`
+pub fn is_zero_hash(hash: &str) -> bool {
+    hash_eq(hash, ZERO_HASH_STRING)
+}
+
+/// # 使用密码学安全的随机数生成器生成随机字节
+///
+/// ## 入参
+/// + `n: usize`: 字节长度
+///
+/// ## 出参
+/// + `Vec<u8>`
+pub fn random_bytes(n: usize) -> Vec<u8> {
+    random_bytes_with_rng(n, &mut rand::thread_rng())
+}
+
+/// # 使用指定的随机数生成器生成随机字节，测试中可以传入种子固定的随机数生成器
+///
+/// ## 入参
+/// + `n: usize`: 字节长度
+/// + `rng: &mut R`: 密码学安全的随机数生成器
+///
+/// ## 出参
+/// + `Vec<u8>`
+pub fn random_bytes_with_rng<R: RngCore + CryptoRng>(n: usize, rng: &mut R) -> Vec<u8> {
+    let mut bytes = vec![0u8; n];
+    rng.fill_bytes(&mut bytes);
+    bytes
+}
+
+/// 地址配置，用于前缀或版本字节不同于`zltc_`/`01`的网络
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AddressConfig {
+    /// 地址前缀，示例：`zltc_`
+    pub title: String,
+    /// 版本字节，示例：`0x01`
+    pub version: u8,
+}
+
+impl Default for AddressConfig {
+    fn default() -> Self {
+        AddressConfig { title: ADDRESS_TITLE.to_string(), version: ADDRESS_VERSION }
+    }
+}
+
 /// 地址结构体
 pub struct Address {
     pub addr: String,
+    config: AddressConfig,
 }
 
 impl Address {
@@ -51,7 +125,23 @@ impl Address {
     /// ## 出参
     /// + `Address`
     pub fn new(addr: &str) -> Self {
-        Address { addr: addr.to_string() }
+        Self::new_with_config(addr, AddressConfig::default())
+    }
+
+    /// # 使用指定的地址配置初始化一个地址对象
+    /// ## 入参
+    /// + `addr: &str`: 可接收一个Lattice地址或ethereum地址
+    /// + `config: AddressConfig`: 地址的前缀和版本字节
+    ///
+    /// ## 出参
+    /// + `Address`
+    pub fn new_with_config(addr: &str, config: AddressConfig) -> Self {
+        Address { addr: addr.to_string(), config }
+    }
+
+    /// 地址的前缀和版本字节
+    pub fn config(&self) -> &AddressConfig {
+        &self.config
     }
 
     /// # Lattice地址转为以太坊地址
@@ -61,19 +151,66 @@ impl Address {
     /// ## 出参
     /// + `String`: 示例：`0x5f2be9a02b43f748ee460bf36eed24fafa109920`
     pub fn to_ethereum_address(&self) -> String {
-        if self.addr.starts_with(ADDRESS_TITLE) {
-            let addr = &self.addr[5..];
+        if let Some(addr) = self.addr.strip_prefix(self.config.title.as_str()) {
             let decoded = bs58::decode(addr).into_vec().unwrap();
             let len = decoded.len() - 4;
 
             let data = &decoded[1..len];
 
-            format!("{}{}", HEX_PREFIX, hex::encode(data))
+            format!("{}{}", PREFIX_OF_HEX, hex::encode(data))
         } else {
             self.addr.clone()
         }
     }
 
+    /// # Lattice地址转为以太坊地址，校验前缀、长度、版本字节和校验和
+    /// ## 入参
+    /// + `&self`: Lattice地址，示例：`zltc_Z1pnS94bP4hQSYLs4aP4UwBP9pH8bEvhi`
+    ///
+    /// ## 出参
+    /// + `Result<String, Error>`: 示例：`0x5f2be9a02b43f748ee460bf36eed24fafa109920`，地址不合法时返回`Err`
+    pub fn try_to_ethereum_address(&self) -> Result<String, Error> {
+        let encoded = self
+            .addr
+            .strip_prefix(self.config.title.as_str())
+            .ok_or_else(|| Error::new(&format!("address {} does not start with {}", self.addr, self.config.title)))?;
+        let decoded = bs58::decode(encoded)
+            .into_vec()
+            .map_err(|e| Error::new(&format!("invalid base58 address {}", self.addr)).with_source(e))?;
+        if decoded.len() != 1 + ETHEREUM_ADDRESS_LENGTH + ADDRESS_CHECKSUM_LENGTH {
+            return Err(Error::new(&format!("invalid length of address {}", self.addr)));
+        }
+        let (data, checksum) = decoded.split_at(1 + ETHEREUM_ADDRESS_LENGTH);
+        if data[0] != self.config.version {
+            return Err(Error::new(&format!("invalid version of address {}", self.addr)));
+        }
+        if address_checksum(data) != checksum {
+            return Err(Error::new(&format!("invalid checksum of address {}", self.addr)));
+        }
+        Ok(format!("{}{}", PREFIX_OF_HEX, hex::encode(&data[1..])))
+    }
+
+    /// # 以太坊地址转为Lattice地址，校验hex编码和长度
+    /// ## 入参
+    /// + `&self`: 以太坊地址，示例：`0x5f2be9a02b43f748ee460bf36eed24fafa109920`
+    ///
+    /// ## 出参
+    /// + `Result<String, Error>`: Lattice地址，示例：`zltc_Z1pnS94bP4hQSYLs4aP4UwBP9pH8bEvhi`，地址不合法时返回`Err`
+    pub fn try_to_zltc_address(&self) -> Result<String, Error> {
+        let hex_string = self
+            .addr
+            .strip_prefix(PREFIX_OF_HEX)
+            .ok_or_else(|| Error::new(&format!("address {} does not start with {}", self.addr, PREFIX_OF_HEX)))?;
+        let eth = hex::decode(hex_string)
+            .map_err(|e| Error::new(&format!("invalid hex address {}", self.addr)).with_source(e))?;
+        if eth.len() != ETHEREUM_ADDRESS_LENGTH {
+            return Err(Error::new(&format!("invalid length of address {}", self.addr)));
+        }
+        let data = [&[self.config.version], eth.as_slice()].concat();
+        let encoded = bs58::encode([data.as_slice(), &address_checksum(&data)].concat()).into_string();
+        Ok(format!("{}{}", self.config.title, encoded))
+    }
+
     /// # 以太坊地址转为Lattice地址
     /// ## 入参
     /// + `&self`: 以太坊地址，示例：`0x5f2be9a02b43f748ee460bf36eed24fafa109920`
@@ -81,24 +218,67 @@ impl Address {
     /// ## 出参
     /// + `String`: Lattice地址，示例：`zltc_Z1pnS94bP4hQSYLs4aP4UwBP9pH8bEvhi`
     pub fn to_zltc_address(&self) -> String {
-        if self.addr.starts_with(HEX_PREFIX) {
+        if self.addr.starts_with(PREFIX_OF_HEX) {
             let eth = HexString::new(&self.addr).decode();
-            let prefix = hex::decode("01").unwrap();
+            let prefix = [self.config.version];
             let hash = [&prefix, eth.as_slice()].concat();
-            let d1 = hex::decode(digest(&hash)).unwrap();
-            let d2 = hex::decode(digest(&d1)).unwrap();
-            let d3 = [&prefix, eth.as_slice(), &d2[0..4]].concat();
+            let d3 = [hash.as_slice(), &address_checksum(&hash)].concat();
             let encoded = bs58::encode(d3).into_string();
-            format!("{}{}", ADDRESS_TITLE, encoded)
+            format!("{}{}", self.config.title, encoded)
         } else {
             self.addr.clone()
         }
     }
 }
 
+/// # 地址的校验和，为两次SHA-256哈希的前`ADDRESS_CHECKSUM_LENGTH`个字节
+fn address_checksum(data: &[u8]) -> Vec<u8> {
+    let d1 = hex::decode(digest(data)).unwrap();
+    let d2 = hex::decode(digest(&d1)).unwrap();
+    d2[..ADDRESS_CHECKSUM_LENGTH].to_vec()
+}
+
+/// # 批量转换地址，Lattice地址转为以太坊地址，以太坊地址转为Lattice地址
+///
+/// ## 入参
+/// + `addrs: &[&str]`: Lattice地址或以太坊地址，可以混合
+///
+/// ## 出参
+/// + `Vec<Result<String, Error>>`: 与入参一一对应的转换结果，不合法的地址对应`Err`，不影响其它地址
+pub fn convert_addresses(addrs: &[&str]) -> Vec<Result<String, Error>> {
+    addrs
+        .iter()
+        .map(|addr| {
+            let address = Address::new(addr);
+            if addr.starts_with(PREFIX_OF_HEX) {
+                address.try_to_zltc_address()
+            } else {
+                address.try_to_ethereum_address()
+            }
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::common::{Address, HexString};
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    use crate::common::{convert_addresses, hash_eq, is_zero_hash, random_bytes_with_rng, Address, AddressConfig, HexString};
+    use crate::constants::ZERO_HASH_STRING;
+
+    #[test]
+    fn test_hash_eq() {
+        let hash = "0x616BF03BAA685DF9FDDEFF4701F170B30176E54120DF726142A534F8F2B51873";
+        assert!(hash_eq(hash, "616bf03baa685df9fddeff4701f170b30176e54120df726142a534f8f2b51873"));
+        assert!(hash_eq(hash, "0X616bf03baa685df9fddeff4701f170b30176e54120df726142a534f8f2b51873"));
+        assert!(!hash_eq(hash, ZERO_HASH_STRING));
+
+        assert!(is_zero_hash(ZERO_HASH_STRING));
+        assert!(is_zero_hash(ZERO_HASH_STRING.trim_start_matches("0x")));
+        assert!(is_zero_hash(&ZERO_HASH_STRING.replacen("0x", "0X", 1)));
+        assert!(!is_zero_hash(hash));
+    }
 
     #[test]
     fn test_new_hex_string() {
@@ -115,6 +295,17 @@ mod tests {
         assert_eq!(expected, hex_string)
     }
 
+    #[test]
+    fn test_random_hex_string() {
+        let first = HexString::random(16);
+        assert_eq!(32, first.clean_hex_string().len());
+        assert!(first.hex_string.starts_with("0x"));
+        assert_ne!(first.hex_string, HexString::random(16).hex_string);
+
+        let seeded = random_bytes_with_rng(16, &mut StdRng::seed_from_u64(42));
+        assert_eq!(seeded, random_bytes_with_rng(16, &mut StdRng::seed_from_u64(42)));
+    }
+
     #[test]
     fn zltc_address_to_ethereum_address() {
         let a = Address::new("zltc_Z1pnS94bP4hQSYLs4aP4UwBP9pH8bEvhi");
@@ -128,4 +319,29 @@ mod tests {
         let zltc_address = a.to_zltc_address();
         assert_eq!("zltc_Z1pnS94bP4hQSYLs4aP4UwBP9pH8bEvhi", zltc_address)
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn ethereum_address_with_custom_config() {
+        let config = AddressConfig { title: String::from("fork_"), version: 0x02 };
+        let eth = "0x5f2be9a02b43f748ee460bf36eed24fafa109920";
+        let address = Address::new_with_config(eth, config.clone()).to_zltc_address();
+        assert!(address.starts_with("fork_"));
+        assert_ne!(address, Address::new(eth).to_zltc_address().replacen("zltc_", "fork_", 1));
+        assert_eq!(eth, Address::new_with_config(&address, config).to_ethereum_address());
+    }
+
+    #[test]
+    fn test_convert_addresses() {
+        let results = convert_addresses(&[
+            "zltc_Z1pnS94bP4hQSYLs4aP4UwBP9pH8bEvhi",
+            "0x5f2be9a02b43f748ee460bf36eed24fafa109920",
+            "zltc_Z1pnS94bP4hQSYLs4aP4UwBP9pH8bEvhj",
+            "0x5f2be9a02b43f748ee460bf36eed24fafa1099",
+        ]);
+        assert_eq!(4, results.len());
+        assert_eq!("0x5f2be9a02b43f748ee460bf36eed24fafa109920", results[0].as_ref().unwrap());
+        assert_eq!("zltc_Z1pnS94bP4hQSYLs4aP4UwBP9pH8bEvhi", results[1].as_ref().unwrap());
+        assert!(results[2].is_err());
+        assert!(results[3].is_err());
+    }
+}
@@ -1,5 +1,18 @@
+use crate::common::Address;
+use crate::HexString;
+
+/// 地址的前缀，例如`zltc_QLbz7JHiBTspS962RLKV8GndWFwjA5K66`中的`zltc_`
 pub const ADDRESS_TITLE: &str = "zltc_";
 
+/// 地址的版本字节，base58编码前拼接在以太坊地址之前
+pub const ADDRESS_VERSION: u8 = 0x01;
+
+/// 以太坊地址的字节长度
+pub const ETHEREUM_ADDRESS_LENGTH: usize = 20;
+
+/// 地址的校验和字节长度
+pub const ADDRESS_CHECKSUM_LENGTH: usize = 4;
+
 /// 零地址，由以太坊的零地址`0x0000000000000000000000000000000000000000`转换的
 pub const ZERO_ZLTC_ADDRESS: &str = "zltc_QLbz7JHiBTspS962RLKV8GndWFwjA5K66";
 
@@ -18,4 +31,43 @@ pub const PUBLIC_KEY_LENGTH: usize = 64;
 /// 私钥的字节长度
 pub const PRIVATE_KEY_LENGTH: usize = 32;
 
-pub const PREFIX_OF_HEX: &str = "0x";
\ No newline at end of file
+/// Secp256k1签名的字节长度，由32字节的r+32字节的s+1字节的recovery id组成
+pub const SECP256K1_SIGNATURE_LENGTH: usize = 65;
+
+/// Sm2p256v1签名的字节长度，由32字节的r+32字节的s+1字节的分隔符+32字节的e组成
+pub const SM2P256V1_SIGNATURE_LENGTH: usize = 97;
+
+/// Ed25519签名的字节长度，由32字节的R+32字节的S组成
+pub const ED25519_SIGNATURE_LENGTH: usize = 64;
+
+/// hex字符串的前缀，空的payload、合约返回值等也用它表示
+pub const PREFIX_OF_HEX: &str = "0x";
+
+/// # 零地址
+///
+/// ## 出参
+/// + `Address`: `ZERO_ZLTC_ADDRESS`对应的地址
+pub fn zero_address() -> Address {
+    Address::new(ZERO_ZLTC_ADDRESS)
+}
+
+/// # 零哈希，用于创世交易的父哈希、预执行合约时的占位区块等
+///
+/// ## 出参
+/// + `HexString`: `ZERO_HASH_STRING`对应的hex字符串
+pub fn zero_hash() -> HexString {
+    HexString::new(ZERO_HASH_STRING)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_zero_values() {
+        assert_eq!("0x0000000000000000000000000000000000000000", zero_address().to_ethereum_address());
+        assert_eq!(ZERO_ZLTC_ADDRESS, zero_address().to_zltc_address());
+        assert_eq!(vec![0u8; 32], zero_hash().decode());
+        assert!(zero_hash().hex_string.starts_with(PREFIX_OF_HEX));
+    }
+}
\ No newline at end of file
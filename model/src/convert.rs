@@ -1,6 +1,17 @@
+use miniz_oxide::deflate::compress_to_vec;
+use miniz_oxide::inflate::decompress_to_vec_with_limit;
 use num_bigint::BigUint;
 
-use crate::HexString;
+use crate::{Error, HexString};
+
+/// 压缩payload的方案前缀：deflate
+pub const PAYLOAD_SCHEME_DEFLATE: u8 = 0x01;
+
+/// 解压payload时允许的最大输出字节数，防止解压炸弹
+pub const MAX_DECOMPRESSED_PAYLOAD_SIZE: usize = 4 * 1024 * 1024;
+
+/// 压缩payload时使用的deflate压缩等级
+const PAYLOAD_COMPRESSION_LEVEL: u8 = 6;
 
 pub trait IntoBigUint {
     fn into_big_uint(self) -> BigUint;
@@ -20,6 +31,7 @@ macro_rules! impl_into_big_uint {
 
 impl_into_big_uint! {u8 u16 u32 u64 u128 usize}
 
+/// 将可选的数字转为大端序（big-endian）的byte数组，None时返回空数组
 pub fn option_number_to_vec<T>(num: Option<T>) -> Vec<u8>
     where
         T: IntoBigUint + Copy
@@ -32,6 +44,7 @@ pub fn option_number_to_vec<T>(num: Option<T>) -> Vec<u8>
     }
 }
 
+/// 将数字转为大端序（big-endian）的byte数组，高位字节在前，例如`1024u64`转为`[0x04, 0x00]`
 pub fn number_to_vec<T>(num: T) -> Vec<u8>
     where
         T: IntoBigUint + Copy
@@ -39,6 +52,23 @@ pub fn number_to_vec<T>(num: T) -> Vec<u8>
     num.into_big_uint().to_bytes_be()
 }
 
+/// 将大端序（big-endian）的byte数组转为数字，是`number_to_vec`的逆操作，空数组转为0
+///
+/// ## 入参
+/// + `bytes: &[u8]`: 大端序的byte数组，例如`[0x04, 0x00]`
+///
+/// ## 出参
+/// + `Result<T, Error>`: 数字超出目标类型的范围时返回错误
+pub fn number_from_vec<T>(bytes: &[u8]) -> Result<T, Error>
+    where
+        T: TryFrom<BigUint>
+{
+    let num = BigUint::from_bytes_be(bytes);
+    T::try_from(num).map_err(|_| {
+        Error::new(&format!("number 0x{} overflows {}", hex::encode(bytes), std::any::type_name::<T>()))
+    })
+}
+
 /// 将字符串转为byte数组，然后再扩展其长度为32的倍数，之后每32个字节转为一个hex字符串，返回一个字符串数组
 pub fn string_to_bytes32_array(data: &str) -> Vec<String> {
     let mut bytes = data.to_string().into_bytes();
@@ -55,9 +85,40 @@ pub fn string_to_bytes32_array(data: &str) -> Vec<String> {
     hex_string_array
 }
 
+/// 使用deflate压缩payload，并在首字节写入方案前缀`PAYLOAD_SCHEME_DEFLATE`
+///
+/// ## 入参
+/// + `data: &[u8]`: 原始数据
+///
+/// ## 出参
+/// + `Vec<u8>`: 方案前缀 + 压缩后的数据
+pub fn compress_payload(data: &[u8]) -> Vec<u8> {
+    let mut compressed = vec![PAYLOAD_SCHEME_DEFLATE];
+    compressed.extend(compress_to_vec(data, PAYLOAD_COMPRESSION_LEVEL));
+    compressed
+}
+
+/// 解压`compress_payload`压缩的payload，是`compress_payload`的逆操作
+///
+/// ## 入参
+/// + `data: &[u8]`: 方案前缀 + 压缩后的数据
+///
+/// ## 出参
+/// + `Result<Vec<u8>, Error>`: 方案前缀不合法、数据损坏或解压后超过`MAX_DECOMPRESSED_PAYLOAD_SIZE`时返回错误
+pub fn decompress_payload(data: &[u8]) -> Result<Vec<u8>, Error> {
+    match data.split_first() {
+        Some((&PAYLOAD_SCHEME_DEFLATE, compressed)) => {
+            decompress_to_vec_with_limit(compressed, MAX_DECOMPRESSED_PAYLOAD_SIZE)
+                .map_err(|err| Error::new(&format!("failed to decompress payload: {}", err)))
+        }
+        Some((scheme, _)) => Err(Error::new(&format!("unsupported payload compression scheme 0x{:02x}", scheme))),
+        None => Err(Error::new("empty compressed payload")),
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::convert::option_number_to_vec;
+    use crate::convert::{compress_payload, decompress_payload, number_from_vec, number_to_vec, option_number_to_vec};
 
     #[test]
     fn convert() {
@@ -82,4 +143,31 @@ mod tests {
         println!("vec_some_u128: {:?}", vec_some_u128);
         println!("vec_none: {:?}", vec_none);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn number_from_big_endian_vec() {
+        assert_eq!(number_from_vec::<u64>(&[0x04, 0x00]).unwrap(), 1024u64);
+        assert_eq!(number_from_vec::<u64>(&[]).unwrap(), 0u64);
+        assert_eq!(number_from_vec::<u128>(&number_to_vec(123456789012345678901234567890u128)).unwrap(), 123456789012345678901234567890u128);
+        assert!(number_from_vec::<u8>(&[0x01, 0x00]).is_err());
+    }
+
+    #[test]
+    fn compress_payload_round_trip() {
+        let data = b"writeTraceability payload with some memo data";
+        let compressed = compress_payload(data);
+        assert_eq!(compressed[0], super::PAYLOAD_SCHEME_DEFLATE);
+        assert_eq!(decompress_payload(&compressed).unwrap(), data.to_vec());
+
+        assert!(decompress_payload(&[]).is_err());
+        assert!(decompress_payload(&[0x02, 0x00]).is_err());
+    }
+
+    #[test]
+    fn compress_payload_shrinks_compressible_input() {
+        let data = vec![0x61u8; 4096];
+        let compressed = compress_payload(&data);
+        assert!(compressed.len() < data.len() / 10);
+        assert_eq!(decompress_payload(&compressed).unwrap(), data);
+    }
+}
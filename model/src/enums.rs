@@ -1,8 +1,60 @@
 /// 椭圆曲线
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Curve {
     /// 国际算法，NIST
     Secp256k1,
     /// 国密算法，SMC
     Sm2p256v1,
-}
\ No newline at end of file
+    /// 实验性的Edwards曲线签名算法，需要开启`ed25519`特性
+    #[cfg(feature = "ed25519")]
+    Ed25519,
+}
+
+impl Curve {
+    /// # 曲线计算交易和消息摘要使用的哈希算法
+    ///
+    /// ## 出参
+    /// + `&'static str`: 示例：`SM3`
+    pub fn hash_name(&self) -> &'static str {
+        match self {
+            Curve::Secp256k1 => "SHA-256",
+            Curve::Sm2p256v1 => "SM3",
+            #[cfg(feature = "ed25519")]
+            Curve::Ed25519 => "SHA-256",
+        }
+    }
+
+    /// # 曲线使用的签名算法
+    ///
+    /// ## 出参
+    /// + `&'static str`: 示例：`ECDSA`
+    pub fn signature_scheme(&self) -> &'static str {
+        match self {
+            Curve::Secp256k1 => "ECDSA",
+            Curve::Sm2p256v1 => "SM2",
+            #[cfg(feature = "ed25519")]
+            Curve::Ed25519 => "EdDSA",
+        }
+    }
+
+    /// # 是否为国密算法，与节点配置中的`isGM`对应
+    pub fn is_gm(&self) -> bool {
+        matches!(self, Curve::Sm2p256v1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_curve_descriptors() {
+        assert_eq!("SHA-256", Curve::Secp256k1.hash_name());
+        assert_eq!("ECDSA", Curve::Secp256k1.signature_scheme());
+        assert!(!Curve::Secp256k1.is_gm());
+
+        assert_eq!("SM3", Curve::Sm2p256v1.hash_name());
+        assert_eq!("SM2", Curve::Sm2p256v1.signature_scheme());
+        assert!(Curve::Sm2p256v1.is_gm());
+    }
+}
@@ -15,7 +15,7 @@ macro_rules! create_error {
         }
 
         impl $name {
-            pub fn message_en(&self) -> &str {
+            pub fn message_en(&self) -> &'static str {
                 match self {
                     $(
                         $name::$variant => $message_en,
@@ -23,7 +23,7 @@ macro_rules! create_error {
                 }
             }
 
-            pub fn message_cn(&self) -> &str {
+            pub fn message_cn(&self) -> &'static str {
                 match self {
                     $(
                         $name::$variant => $message_cn,
@@ -46,24 +46,124 @@ create_error!(LatticeError,
     ReceiptNotFound => ("Receipt not found, contract is not execute or tx is not on-chain", "收据信息不存在，合约未被执行或者交易未被上链")
 );
 
+create_error!(JsonRpcErrorKind,
+    ParseError => ("Parse error", "请求解析失败"),
+    InvalidRequest => ("Invalid request", "无效的请求"),
+    MethodNotFound => ("Method not found", "方法不存在"),
+    InvalidParams => ("Invalid params", "无效的参数"),
+    InternalError => ("Internal error", "节点内部错误")
+);
+
+impl JsonRpcErrorKind {
+    /// # 根据json-rpc的错误码获取错误类型
+    ///
+    /// ## 入参
+    /// + `code: i32`: 错误码
+    ///
+    /// ## 出参
+    /// + `Option<JsonRpcErrorKind>`: 不是json-rpc规范中的错误码时返回None
+    pub fn from_code(code: i32) -> Option<Self> {
+        match code {
+            -32700 => Some(JsonRpcErrorKind::ParseError),
+            -32600 => Some(JsonRpcErrorKind::InvalidRequest),
+            -32601 => Some(JsonRpcErrorKind::MethodNotFound),
+            -32602 => Some(JsonRpcErrorKind::InvalidParams),
+            -32603 => Some(JsonRpcErrorKind::InternalError),
+            _ => None,
+        }
+    }
+}
+
+/// 错误的来源
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorKind {
+    /// 客户端产生的错误，包括参数校验、网络和响应解析失败等，请求可能到达了节点，也可能没有
+    Client,
+    /// 节点返回的json-rpc错误，说明节点收到并拒绝了请求
+    JsonRpc,
+}
+
 #[derive(Debug)]
 pub struct Error {
+    kind: ErrorKind,
     code: i32,
     message: String,
+    source: Option<Box<dyn std::error::Error + Send + Sync + 'static>>,
 }
 
 impl Error {
     pub fn new(message: &str) -> Self {
         Error {
+            kind: ErrorKind::Client,
             code: -1,
             message: message.to_string(),
+            source: None,
         }
     }
 
     pub fn custom(code: i32, message: String) -> Self {
         Error {
+            kind: ErrorKind::Client,
             code,
             message,
+            source: None,
+        }
+    }
+
+    /// 节点返回的json-rpc错误，沿用节点的错误码和错误信息
+    pub fn json_rpc(code: i32, message: String) -> Self {
+        Error {
+            kind: ErrorKind::JsonRpc,
+            code,
+            message,
+            source: None,
+        }
+    }
+
+    /// 附带底层的错误，可通过`std::error::Error::source`获取，便于定位真正的原因
+    pub fn with_source<E>(mut self, source: E) -> Self
+    where
+        E: std::error::Error + Send + Sync + 'static,
+    {
+        self.source = Some(Box::new(source));
+        self
+    }
+
+    /// 错误的来源
+    pub fn kind(&self) -> ErrorKind {
+        self.kind
+    }
+
+    /// 错误码，json-rpc的错误沿用节点返回的错误码
+    pub fn code(&self) -> i32 {
+        self.code
+    }
+
+    /// 原始的错误信息
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// # 英文的错误信息
+    ///
+    /// json-rpc规范中的错误码使用对照表中的信息，并在其后附上原始的错误信息；
+    /// 其它错误码没有对照的翻译，原样返回原始的错误信息
+    pub fn message_en(&self) -> String {
+        self.localized(JsonRpcErrorKind::message_en)
+    }
+
+    /// # 中文的错误信息
+    ///
+    /// json-rpc规范中的错误码使用对照表中的信息，并在其后附上原始的错误信息；
+    /// 其它错误码没有对照的翻译，原样返回原始的错误信息，节点返回的信息通常为英文
+    pub fn message_cn(&self) -> String {
+        self.localized(JsonRpcErrorKind::message_cn)
+    }
+
+    fn localized(&self, translate: fn(&JsonRpcErrorKind) -> &'static str) -> String {
+        match JsonRpcErrorKind::from_code(self.code) {
+            Some(kind) => format!("{}: {}", translate(&kind), self.message),
+            None => self.message.clone(),
         }
     }
 }
@@ -74,16 +174,43 @@ impl fmt::Display for Error {
     }
 }
 
-impl std::error::Error for Error {}
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        self.source.as_ref().map(|source| source.as_ref() as &(dyn std::error::Error + 'static))
+    }
+}
 
 impl From<serde_json::Error> for Error {
     fn from(err: serde_json::Error) -> Self {
-        Error::new(err.to_string().as_str())
+        Error::new(err.to_string().as_str()).with_source(err)
     }
 }
 
 impl From<reqwest::Error> for Error {
     fn from(err: reqwest::Error) -> Self {
-        Error::new(err.to_string().as_str())
+        Error::new(err.to_string().as_str()).with_source(err)
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_localized_message() {
+        let err = Error::json_rpc(-32601, String::from("the method latc_foo does not exist"));
+        assert_eq!(err.kind(), ErrorKind::JsonRpc);
+        assert_eq!(err.message(), "the method latc_foo does not exist");
+        assert_eq!(err.message_en(), "Method not found: the method latc_foo does not exist");
+        assert_eq!(err.message_cn(), "方法不存在: the method latc_foo does not exist");
+
+        let err = Error::json_rpc(-32000, String::from("nonce too low"));
+        assert_eq!(err.message_en(), "nonce too low");
+        assert_eq!(err.message_cn(), "nonce too low");
+
+        let err = Error::new("结果为空");
+        assert_eq!(err.kind(), ErrorKind::Client);
+        assert_eq!(err.message_en(), "结果为空");
+        assert_eq!(err.message_cn(), "结果为空");
+    }
+}
@@ -2,6 +2,8 @@ pub use common::HexString;
 pub use enums::Curve;
 pub use errors::Error;
 pub use errors::LatticeError;
+pub use errors::JsonRpcErrorKind;
+pub use errors::ErrorKind;
 
 pub mod block;
 pub mod receipt;
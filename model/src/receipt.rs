@@ -1,4 +1,10 @@
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{Error, HexString};
+
+/// `Error(string)`的函数选择器，合约`revert("...")`/`require(.., "...")`的返回数据以此开头
+const REVERT_SELECTOR: [u8; 4] = [0x08, 0xc3, 0x79, 0xa0];
 
 /// 回执
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -11,7 +17,7 @@ pub struct Receipt {
     daemon_block_hash: String,
     #[serde(rename = "dblockNumber")]
     dblock_height: u64,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     events: Option<Vec<Event>>,
     #[serde(rename = "jouleUsed")]
     joule_used: u64,
@@ -26,12 +32,114 @@ pub struct Receipt {
     version: u16,
 }
 
+impl Receipt {
+    /// 合约地址
+    pub fn contract_address(&self) -> &str {
+        &self.contract_address
+    }
+
+    /// 合约的返回数据，hex字符串
+    pub fn contract_return(&self) -> &str {
+        &self.contract_return
+    }
+
+    /// 交易所在的守护区块的高度
+    pub fn dblock_height(&self) -> u64 {
+        self.dblock_height
+    }
+
+    /// 消耗的焦耳
+    pub fn joule_used(&self) -> u64 {
+        self.joule_used
+    }
+
+    /// 交易是否执行成功
+    pub fn is_success(&self) -> bool {
+        self.success
+    }
+
+    /// 交易哈希
+    pub fn tblock_hash(&self) -> &str {
+        &self.tblock_hash
+    }
+
+    /// 合约产生的事件，没有事件时为空
+    pub fn events(&self) -> &[Event] {
+        self.events.as_deref().unwrap_or_default()
+    }
+
+    /// # 失败原因
+    ///
+    /// ## 出参
+    /// + `Option<String>`: 执行成功时为None；返回数据为`Error(string)`时为其中的字符串，否则为返回数据的hex字符串
+    pub fn revert_reason(&self) -> Option<String> {
+        self.revert_reason_with(|_| None)
+    }
+
+    /// # 失败原因，先用调用方提供的函数解码合约的自定义错误
+    ///
+    /// 回执不依赖abi，解码自定义错误需要合约的abi，可以使用`abi::Abi::revert_reason`
+    ///
+    /// ## 入参
+    /// + `decode_error: F`: 解码返回数据的函数，无法解码时返回None
+    ///
+    /// ## 出参
+    /// + `Option<String>`: 执行成功时为None；`decode_error`能解码时为其结果，否则同`revert_reason`
+    pub fn revert_reason_with<F>(&self, decode_error: F) -> Option<String>
+    where
+        F: FnOnce(&[u8]) -> Option<String>,
+    {
+        if self.success {
+            return None;
+        }
+        let data = hex::decode(HexString::new(&self.contract_return).clean_hex_string()).unwrap_or_default();
+        decode_error(&data)
+            .or_else(|| decode_revert_string(&data))
+            .or_else(|| Some(self.contract_return.clone()))
+    }
+
+    /// # 确保交易执行成功，便于`receipt.ensure_success()?`链式调用
+    ///
+    /// ## 出参
+    /// + `Result<&Receipt, Error>`: 执行失败时返回带有失败原因的错误
+    pub fn ensure_success(&self) -> Result<&Receipt, Error> {
+        match self.revert_reason() {
+            None => Ok(self),
+            Some(reason) => Err(Error::new(&format!("交易{}执行失败, {}", self.tblock_hash, reason))),
+        }
+    }
+}
+
+/// # 解码`Error(string)`的返回数据
+///
+/// ## 入参
+/// + `data: &[u8]`: 合约返回数据，布局为`selector ‖ offset ‖ length ‖ bytes`
+///
+/// ## 出参
+/// + `Option<String>`: 不是`Error(string)`或数据不完整时返回None
+fn decode_revert_string(data: &[u8]) -> Option<String> {
+    let data = data.strip_prefix(&REVERT_SELECTOR)?;
+    let word = |offset: usize| -> Option<usize> {
+        let word = data.get(offset..offset.checked_add(32)?)?;
+        if word[..24].iter().any(|b| *b != 0) {
+            return None;
+        }
+        Some(u64::from_be_bytes(word[24..].try_into().ok()?) as usize)
+    };
+    let offset = word(0)?;
+    let len = word(offset)?;
+    let start = offset.checked_add(32)?;
+    let bytes = data.get(start..start.checked_add(len)?)?;
+    String::from_utf8(bytes.to_vec()).ok()
+}
+
 /// 事件
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Event {
     address: String, // address of the contract that generated the event
     topics: Vec<String>,// list of topics provided by the contract
-    data: Vec<u8>, // supplied by the contract, usually ABI-encoded
+    #[serde(default, skip_serializing_if = "Value::is_null")]
+    data: Value, // supplied by the contract, kept as sent by the node, the bytes are decoded from `dataHex`
     #[serde(rename = "logIndex")]
     index: u32, // index of the log in the block
     #[serde(rename = "dblockNumber")]
@@ -39,4 +147,126 @@ pub struct Event {
     removed: bool,
     #[serde(rename = "dataHex")]
     data_hex: String,
-}
\ No newline at end of file
+}
+
+impl Event {
+    /// 产生事件的合约地址
+    pub fn address(&self) -> &str {
+        &self.address
+    }
+
+    /// 事件的主题，非匿名事件的第一个主题为事件选择器
+    pub fn topics(&self) -> &[String] {
+        &self.topics
+    }
+
+    /// # 事件的数据，通常为abi编码
+    ///
+    /// ## 出参
+    /// + `Result<Vec<u8>, Error>`: 从`dataHex`解码的数据，`dataHex`不是合法的hex字符串时返回`Err`
+    pub fn data(&self) -> Result<Vec<u8>, Error> {
+        hex::decode(HexString::new(&self.data_hex).clean_hex_string())
+            .map_err(|e| Error::new(&format!("invalid event dataHex {}", self.data_hex)).with_source(e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::Value;
+
+    use crate::HexString;
+
+    use super::*;
+
+    /// 手写的回执样例，不是从节点抓取的，字段名与节点的回执一致
+    const RECEIPT_JSON: &str = include_str!("../testdata/receipt.json");
+
+    #[test]
+    fn test_deserialize_receipt() {
+        let receipt: Receipt = serde_json::from_str(RECEIPT_JSON).unwrap();
+        assert_eq!(receipt.contract_address, "zltc_QLbz7JHiBTspUvTPzLHy5biDS9mu53mmv");
+        assert_eq!(receipt.dblock_height, 1024);
+        assert_eq!(receipt.joule_used, 21000);
+        assert_eq!(receipt.receipt_index, 0);
+        assert!(receipt.success);
+        assert_eq!(receipt.confirm_time, Some(1719804632));
+        assert_eq!(receipt.version, 3);
+
+        assert_eq!(receipt.events().len(), 1);
+        assert_eq!(receipt.events()[0].address(), receipt.contract_address());
+        let events = receipt.events.unwrap();
+        assert_eq!(events.len(), 1);
+        let event = &events[0];
+        assert_eq!(event.topics.len(), 2);
+        assert_eq!(event.index, 0);
+        assert_eq!(event.daemon_block_height, 1024);
+        assert!(!event.removed);
+        assert_eq!(HexString::from(&event.data().unwrap()).hex_string, event.data_hex);
+    }
+
+    #[test]
+    fn test_receipt_round_trip() {
+        let receipt: Receipt = serde_json::from_str(RECEIPT_JSON).unwrap();
+        let expected: Value = serde_json::from_str(RECEIPT_JSON).unwrap();
+        let actual = serde_json::to_value(&receipt).unwrap();
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn test_receipt_events_absent_or_empty() {
+        let mut value: Value = serde_json::from_str(RECEIPT_JSON).unwrap();
+
+        value["events"] = Value::Array(vec![]);
+        let receipt: Receipt = serde_json::from_value(value.clone()).unwrap();
+        assert_eq!(receipt.events.as_ref().map(Vec::len), Some(0));
+        assert_eq!(serde_json::to_value(&receipt).unwrap()["events"], Value::Array(vec![]));
+
+        value.as_object_mut().unwrap().remove("events");
+        let receipt: Receipt = serde_json::from_value(value).unwrap();
+        assert!(receipt.events.is_none());
+        assert!(serde_json::to_value(&receipt).unwrap().get("events").is_none());
+    }
+
+    #[test]
+    fn test_ensure_success() {
+        let receipt: Receipt = serde_json::from_str(RECEIPT_JSON).unwrap();
+        assert_eq!(receipt.contract_address(), "zltc_QLbz7JHiBTspUvTPzLHy5biDS9mu53mmv");
+        assert_eq!(receipt.joule_used(), 21000);
+        assert!(receipt.revert_reason().is_none());
+        assert_eq!(receipt.ensure_success().unwrap().joule_used(), 21000);
+    }
+
+    #[test]
+    fn test_ensure_success_failed() {
+        let mut value: Value = serde_json::from_str(RECEIPT_JSON).unwrap();
+        value["success"] = Value::Bool(false);
+        // Error("insufficient balance")
+        value["contractRet"] = Value::String(String::from("0x08c379a0\
+            0000000000000000000000000000000000000000000000000000000000000020\
+            0000000000000000000000000000000000000000000000000000000000000014\
+            696e73756666696369656e742062616c616e6365000000000000000000000000"));
+        let receipt: Receipt = serde_json::from_value(value.clone()).unwrap();
+        assert!(!receipt.is_success());
+        assert_eq!(receipt.revert_reason().as_deref(), Some("insufficient balance"));
+        let err = receipt.ensure_success().unwrap_err();
+        assert!(err.to_string().contains("insufficient balance"));
+
+        value["contractRet"] = Value::String(String::from("0x82b42900"));
+        let receipt: Receipt = serde_json::from_value(value).unwrap();
+        assert_eq!(receipt.revert_reason().as_deref(), Some("0x82b42900"));
+        assert!(receipt.ensure_success().is_err());
+    }
+
+    #[test]
+    fn test_decode_revert_string_overflowing_offset() {
+        let data = hex::decode("08c379a0\
+            000000000000000000000000000000000000000000000000ffffffffffffffff\
+            0000000000000000000000000000000000000000000000000000000000000014").unwrap();
+        assert!(decode_revert_string(&data).is_none());
+
+        let data = hex::decode("08c379a0\
+            0000000000000000000000000000000000000000000000000000000000000020\
+            000000000000000000000000000000000000000000000000ffffffffffffffe0").unwrap();
+        assert!(decode_revert_string(&data).is_none());
+    }
+}
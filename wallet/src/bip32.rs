@@ -58,8 +58,14 @@ impl ExtendedPrivateKey {
     where
         Path: IntoDerivationPath,
     {
+        let key: &[u8] = match curve {
+            // SLIP-0010：Ed25519使用独立的主密钥种子
+            #[cfg(feature = "ed25519")]
+            Curve::Ed25519 => b"ed25519 seed",
+            _ => b"Bitcoin seed",
+        };
         let mut hmac =
-            HmacSha512::new_from_slice(b"Bitcoin seed").expect("seed is always correct; qed");
+            HmacSha512::new_from_slice(key).expect("seed is always correct; qed");
         hmac.update(seed);
 
         let result = hmac.finalize().into_bytes();
@@ -110,6 +116,9 @@ impl ExtendedPrivateKey {
                     let pk_bytes = CURVE_SM2P256V1.point_to_bytes(&pk, true).unwrap();
                     hmac.update(&pk_bytes);
                 }
+                // SLIP-0010：Ed25519只支持硬化派生
+                #[cfg(feature = "ed25519")]
+                Curve::Ed25519 => return Err(Error::InvalidChildNumber),
             }
         } else {
             hmac.update(&[0]);
@@ -137,6 +146,11 @@ impl ExtendedPrivateKey {
                 // 对私钥进行加法微调
                 sk = (secret_key + &self.secret_key) % CURVE_SM2P256V1.get_n();
             }
+            // SLIP-0010：Ed25519的子私钥直接取哈希的左32字节
+            #[cfg(feature = "ed25519")]
+            Curve::Ed25519 => {
+                sk = BigUint::from_bytes_be(secret_key);
+            }
         }
 
         Ok(ExtendedPrivateKey {
@@ -150,15 +164,16 @@ impl ExtendedPrivateKey {
     type Err = Error;
 
     fn from_str(xprv: &str) -> Result<ExtendedPrivateKey, Error> {
-        let data = xprv.from_base58().map_err(|_| Error::InvalidExtendedPrivKey)?;
+        let (_, data) = crypto::base58check::decode(xprv, 4, crypto::base58check::DEFAULT_CHECKSUM_LENGTH)
+            .map_err(|_| Error::InvalidExtendedPrivKey)?;
 
-        if data.len() != 82 {
+        if data.len() != 74 {
             return Err(Error::InvalidExtendedPrivKey);
         }
 
         Ok(ExtendedPrivateKey {
-            chain_code: Protected::from(&data[13..45]),
-            secret_key: BigUint::from_bytes_be(&data[56..78]),
+            chain_code: Protected::from(&data[9..41]),
+            secret_key: BigUint::from_bytes_be(&data[42..74]),
         })
     }
 }*/
@@ -28,6 +28,41 @@ impl ChildNumber {
     pub fn non_hardened_from_u32(index: u32) -> Self {
         ChildNumber(index)
     }
+
+    /// # 硬化的子序号，例如`44'`
+    ///
+    /// ## 入参
+    /// + `index: u32`: 序号，必须小于2^31
+    ///
+    /// ## 出参
+    /// + `Result<ChildNumber, Error>`: 序号超出范围时返回`Error::InvalidChildNumber`
+    pub fn hardened(index: u32) -> Result<Self, Error> {
+        Self::check_index(index).map(Self::hardened_from_u32)
+    }
+
+    /// # 普通(非硬化)的子序号，例如`0`
+    ///
+    /// ## 入参
+    /// + `index: u32`: 序号，必须小于2^31
+    ///
+    /// ## 出参
+    /// + `Result<ChildNumber, Error>`: 序号超出范围时返回`Error::InvalidChildNumber`
+    pub fn normal(index: u32) -> Result<Self, Error> {
+        Self::check_index(index).map(Self::non_hardened_from_u32)
+    }
+
+    /// 去掉硬化标识位后的序号
+    pub fn index(&self) -> u32 {
+        self.0 & !HARDENED_BIT
+    }
+
+    fn check_index(index: u32) -> Result<u32, Error> {
+        if index & HARDENED_BIT == 0 {
+            Ok(index)
+        } else {
+            Err(Error::InvalidChildNumber)
+        }
+    }
 }
 
 impl FromStr for ChildNumber {
@@ -73,6 +108,12 @@ impl FromStr for DerivationPath {
     }
 }
 
+impl From<Vec<ChildNumber>> for DerivationPath {
+    fn from(path: Vec<ChildNumber>) -> Self {
+        DerivationPath { path }
+    }
+}
+
 impl DerivationPath {
     pub fn as_ref(&self) -> &[ChildNumber] {
         &self.path
@@ -119,4 +160,28 @@ mod tests {
             }
         );
     }
+
+    #[test]
+    fn child_number_constructors() {
+        let child = ChildNumber::hardened(44).unwrap();
+        assert!(child.is_hardened());
+        assert_eq!(child.index(), 44);
+        assert_eq!(child, "44'".parse().unwrap());
+
+        let child = ChildNumber::normal(0).unwrap();
+        assert!(child.is_normal());
+        assert_eq!(child.index(), 0);
+
+        assert_eq!(ChildNumber::hardened(HARDENED_BIT), Err(Error::InvalidChildNumber));
+        assert_eq!(ChildNumber::normal(u32::MAX), Err(Error::InvalidChildNumber));
+        assert!(ChildNumber::normal(HARDENED_BIT - 1).is_ok());
+
+        let path = DerivationPath::from(vec![
+            ChildNumber::hardened(44).unwrap(),
+            ChildNumber::hardened(60).unwrap(),
+            ChildNumber::hardened(0).unwrap(),
+            ChildNumber::normal(0).unwrap(),
+        ]);
+        assert_eq!(path, "m/44'/60'/0'/0".parse().unwrap());
+    }
 }
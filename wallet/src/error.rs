@@ -1,3 +1,5 @@
+use std::fmt;
+
 #[derive(Clone, PartialEq, Eq, Debug)]
 pub enum Error {
     Secp256k1(secp256k1::Error),
@@ -5,4 +7,47 @@ pub enum Error {
     InvalidChildNumber,
     InvalidDerivationPath,
     InvalidExtendedPrivateKey,
-}
\ No newline at end of file
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Secp256k1(e) => write!(f, "secp256k1 error: {}", e),
+            Error::Sm2p256v1 => write!(f, "sm2p256v1 error"),
+            Error::InvalidChildNumber => write!(f, "invalid child number"),
+            Error::InvalidDerivationPath => write!(f, "invalid derivation path"),
+            Error::InvalidExtendedPrivateKey => write!(f, "invalid extended private key"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Secp256k1(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<Error> for model::Error {
+    fn from(err: Error) -> Self {
+        model::Error::new(&format!("wallet: {}", err)).with_source(err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_into_model_error() {
+        let err: model::Error = Error::InvalidChildNumber.into();
+        assert_eq!(err.message(), "wallet: invalid child number");
+        let source = std::error::Error::source(&err).unwrap();
+        assert_eq!(source.downcast_ref::<Error>(), Some(&Error::InvalidChildNumber));
+
+        let err: model::Error = Error::Secp256k1(secp256k1::Error::InvalidSecretKey).into();
+        assert!(err.message().starts_with("wallet: secp256k1 error"));
+    }
+}
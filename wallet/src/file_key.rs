@@ -1,13 +1,14 @@
-use rand::random;
 use scrypt::{Params, Scrypt};
 use scrypt::password_hash::{PasswordHasher, SaltString};
 use serde::{Deserialize, Serialize};
+use tiny_keccak::{Hasher, Keccak};
 use uuid::Uuid;
 
 use crypto::aes;
 use crypto::aes::AesMode;
 use crypto::hash::hash_message;
 use crypto::sign::KeyPair;
+use model::common::random_bytes;
 use model::Curve;
 use model::Error;
 use model::HexString;
@@ -29,6 +30,26 @@ pub struct Cipher {
     pub cipher_text: String,
     /// Message Authentication Code（消息认证码）
     pub mac: String,
+    /// 消息认证码的算法，缺省时为曲线对应的哈希算法
+    #[serde(rename = "macAlgorithm", default, skip_serializing_if = "MacAlgorithm::is_curve")]
+    pub mac_algorithm: MacAlgorithm,
+}
+
+/// 消息认证码的算法
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum MacAlgorithm {
+    /// 曲线对应的哈希算法，国密为SM3，否则为SHA-256
+    #[default]
+    Curve,
+    /// keccak256，与以太坊的keystore兼容
+    Keccak256,
+}
+
+impl MacAlgorithm {
+    fn is_curve(&self) -> bool {
+        *self == MacAlgorithm::Curve
+    }
 }
 
 /// aes
@@ -85,11 +106,24 @@ impl FileKey {
     /// ## Returns
     /// + FileKey
     pub fn from_secret_key(secret_key: &[u8], password: &[u8], curve: Curve) -> Self {
+        Self::from_secret_key_with_mac(secret_key, password, curve, MacAlgorithm::default())
+    }
+
+    /// # 从私钥得到FileKey，并指定消息认证码的算法
+    /// ## Parameters
+    /// + `secret_key: &[u8]`: 私钥
+    /// + `password: &[u8]`: 身份密码
+    /// + `curve: Curve`: Secp256k1 or Sm2p256v1
+    /// + `mac_algorithm: MacAlgorithm`: 消息认证码的算法，`Keccak256`时与以太坊的keystore兼容
+    ///
+    /// ## Returns
+    /// + FileKey
+    pub fn from_secret_key_with_mac(secret_key: &[u8], password: &[u8], curve: Curve, mac_algorithm: MacAlgorithm) -> Self {
         let key_pair = KeyPair::from_secret_key(secret_key, curve);
         FileKey {
             uuid: Uuid::new_v4().to_string(),
             address: key_pair.address(),
-            cipher: gen_cipher(secret_key, password, curve),
+            cipher: gen_cipher(secret_key, password, curve, mac_algorithm),
             is_gm: matches!(curve, Curve::Sm2p256v1),
         }
     }
@@ -107,7 +141,7 @@ impl FileKey {
 
         let hash_key = hex::decode(&key[32..64]).unwrap();
         let curve = if self.is_gm { Curve::Sm2p256v1 } else { Curve::Secp256k1 };
-        let actual_mac = compute_mac(&hash_key, &self.cipher.cipher_text, curve);
+        let actual_mac = compute_mac(&hash_key, &self.cipher.cipher_text, curve, self.cipher.mac_algorithm);
         if !actual_mac.eq(&self.cipher.mac) {
             return Err(Error::new("根据密码无法解析出私钥，请检查密码"));
         }
@@ -117,7 +151,7 @@ impl FileKey {
         let sk_hex = aes::decrypt_with_mode(AesMode::CTR, &self.cipher.cipher_text, &aes_key, Some(&iv_bytes));
         let secret_bytes = hex::decode(sk_hex).unwrap();
 
-        Ok(KeyPair::from_secret_key(&secret_bytes, curve))
+        KeyPair::try_from_secret_key(&secret_bytes, curve)
     }
 }
 
@@ -126,19 +160,20 @@ impl FileKey {
 /// + `secret_key: &[u8]`: 私钥
 /// + `password: &[u8]`: 密码
 /// + `curve: Curve`:
+/// + `mac_algorithm: MacAlgorithm`: 消息认证码的算法
 ///
 /// ## Returns
 /// + `Cipher`: struct
-fn gen_cipher(secret_key: &[u8], password: &[u8], curve: Curve) -> Cipher {
-    let salt = hex::encode(random::<[u8; 32]>());
-    let iv_bytes = random::<[u8; 16]>(); // 16 equals aes.BlockSize
-    let iv = hex::encode(iv_bytes);
+fn gen_cipher(secret_key: &[u8], password: &[u8], curve: Curve, mac_algorithm: MacAlgorithm) -> Cipher {
+    let salt = HexString::random(32).clean_hex_string();
+    let iv_bytes = random_bytes(16); // 16 equals aes.BlockSize
+    let iv = hex::encode(&iv_bytes);
     let key = scrypt_key(password, &salt);
     let aes_key = hex::decode(&key[0..32]).unwrap();
     let hash_key = hex::decode(&key[32..64]).unwrap();
     // let cipher_text = aes::encrypt(&secret_key, &aes_key, &iv_bytes);
     let cipher_text = aes::encrypt_with_mode(AesMode::CTR, &secret_key, &aes_key, Some(&iv_bytes));
-    let mac = compute_mac(&hash_key, &cipher_text, curve);
+    let mac = compute_mac(&hash_key, &cipher_text, curve, mac_algorithm);
     Cipher {
         aes: Aes {
             cipher: "aes-128-ctr".to_string(),
@@ -156,6 +191,7 @@ fn gen_cipher(secret_key: &[u8], password: &[u8], curve: Curve) -> Cipher {
         },
         cipher_text,
         mac,
+        mac_algorithm,
     }
 }
 
@@ -181,14 +217,24 @@ fn scrypt_key(password: &[u8], salt: &str) -> String {
 /// + `key: &[u8]`:
 /// + `cipher_text: &str`:
 /// + `curve: Curve`:
+/// + `mac_algorithm: MacAlgorithm`: `Curve`时使用曲线对应的哈希算法，`Keccak256`时使用keccak256
 ///
 /// ## Returns
 /// + String
-fn compute_mac(key: &[u8], cipher_text: &str, curve: Curve) -> String {
+fn compute_mac(key: &[u8], cipher_text: &str, curve: Curve, mac_algorithm: MacAlgorithm) -> String {
     let h = HexString { hex_string: String::from(cipher_text) };
     let cipher_bytes = h.decode();
     let data = [key, &cipher_bytes].concat();
-    hash_message(&data, curve)
+    match mac_algorithm {
+        MacAlgorithm::Curve => hash_message(&data, curve),
+        MacAlgorithm::Keccak256 => {
+            let mut hasher = Keccak::v256();
+            let mut output = [0u8; 32];
+            hasher.update(&data);
+            hasher.finalize(&mut output);
+            hex::encode(output)
+        }
+    }
 }
 
 #[cfg(test)]
@@ -196,7 +242,7 @@ mod tests {
     use model::Curve;
     use model::HexString;
 
-    use crate::file_key::FileKey;
+    use crate::file_key::{FileKey, MacAlgorithm};
 
     #[test]
     fn test_gen_file_key() {
@@ -224,4 +270,51 @@ mod tests {
         let key_pair = file_key.decrypt("Root1234").unwrap();
         assert_eq!("23d5b2a2eb0a9c8b86d62cbc3955cfd1fb26ec576ecc379f402d0f5d2b27a7bb", hex::encode(key_pair.secret_key.to_bytes_be()))
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_decrypt_keccak_mac_file_key() {
+        let json = r#"{"uuid":"b2a39030-53de-4147-9410-9c35cba8c2d6","address":"zltc_ZUpkgxSNrZUPAiGGrfhUzC1Uyq9XuFJB9","cipher":{"aes":{"cipher":"aes-128-ctr","iv":"58dbadaa14ae3010b56e856abeaec615"},"kdf":{"kdf":"scrypt","kdfParams":{"DKLen":32,"n":262144,"p":1,"r":8,"salt":"b6a82fb97cfe3446e8f0f2647844c2e889cfda6cd063bcd233be1a473cb53dbc"}},"cipherText":"a70a333669460cb184c0a2c1f6f13e6f6f4b6eb7316ddb5793c002a4ac35e649","mac":"e834fef5c117e2bc87bc72032d11d2abcc95d6da798b89484404f773b6dcdb7e","macAlgorithm":"keccak256"},"isGM":false}"#;
+        let file_key = FileKey::new(json);
+        assert_eq!(MacAlgorithm::Keccak256, file_key.cipher.mac_algorithm);
+        let key_pair = file_key.decrypt("Root1234").unwrap();
+        assert_eq!("23d5b2a2eb0a9c8b86d62cbc3955cfd1fb26ec576ecc379f402d0f5d2b27a7bb", hex::encode(key_pair.secret_key.to_bytes_be()));
+        assert_eq!(file_key.address, key_pair.address());
+
+        // 同一个文件按照曲线的哈希算法校验消息认证码会失败
+        let json = json.replace(r#","macAlgorithm":"keccak256""#, "");
+        let file_key = FileKey::new(&json);
+        assert_eq!(MacAlgorithm::Curve, file_key.cipher.mac_algorithm);
+        assert!(file_key.decrypt("Root1234").is_err());
+    }
+
+    #[test]
+    fn test_keccak_mac_file_key_round_trip() {
+        let secret_key = HexString::new("0x23d5b2a2eb0a9c8b86d62cbc3955cfd1fb26ec576ecc379f402d0f5d2b27a7bb").decode();
+        let file_key = FileKey::from_secret_key_with_mac(secret_key.as_slice(), b"Root1234", Curve::Sm2p256v1, MacAlgorithm::Keccak256);
+        let json = serde_json::to_string(&file_key).unwrap();
+        assert!(json.contains(r#""macAlgorithm":"keccak256""#));
+
+        let key_pair = FileKey::new(&json).decrypt("Root1234").unwrap();
+        assert_eq!(secret_key, key_pair.secret_key.to_bytes_be());
+
+        let json = serde_json::to_string(&FileKey::from_secret_key(secret_key.as_slice(), b"Root1234", Curve::Sm2p256v1)).unwrap();
+        assert!(!json.contains("macAlgorithm"));
+    }
+
+    #[test]
+    fn test_secp256k1_file_key_round_trip() {
+        let secret_key = HexString::new("0x23d5b2a2eb0a9c8b86d62cbc3955cfd1fb26ec576ecc379f402d0f5d2b27a7bb").decode();
+        let file_key = FileKey::from_secret_key(secret_key.as_slice(), b"Root1234", Curve::Secp256k1);
+        assert!(!file_key.is_gm);
+
+        let json = serde_json::to_string(&file_key).unwrap();
+        let file_key = FileKey::new(&json);
+        assert!(!file_key.is_gm);
+
+        let key_pair = file_key.decrypt("Root1234").unwrap();
+        assert!(matches!(key_pair.curve, Curve::Secp256k1));
+        assert_eq!(secret_key, key_pair.secret_key.to_bytes_be());
+        assert_eq!(file_key.address, key_pair.address());
+        assert!(file_key.decrypt("Root12345").is_err());
+    }
+}
@@ -1,6 +1,6 @@
 mod bip32;
 pub mod bip39;
 pub mod bip44;
-mod error;
+pub mod error;
 pub mod file_key;
 pub mod wallet;
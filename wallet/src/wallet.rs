@@ -1 +1,175 @@
-// contains `new account`, `import account`, `sign tx`
\ No newline at end of file
+use std::str::FromStr;
+
+use crypto::sign::KeyPair;
+use crypto::Transaction;
+use model::constants::PREFIX_OF_HEX;
+use model::{Curve, Error};
+
+use crate::bip32::ExtendedPrivateKey;
+use crate::file_key::FileKey;
+
+/// 可以签名的账户，统一了密钥对、地址和曲线，可以从私钥、助记词或者FileKey创建
+#[derive(Debug)]
+pub struct Wallet {
+    /// 密钥对
+    pub keypair: KeyPair,
+    /// 账户地址，`zltc_`开头
+    pub address: String,
+    /// 椭圆曲线，Secp256k1 or Sm2p256v1
+    pub curve: Curve,
+}
+
+impl Wallet {
+    /// # 从密钥对创建钱包
+    ///
+    /// ## 入参
+    /// + `keypair: KeyPair`: 密钥对
+    ///
+    /// ## 出参
+    /// + `Wallet`
+    pub fn from_keypair(keypair: KeyPair) -> Self {
+        Wallet {
+            address: keypair.address(),
+            curve: keypair.curve,
+            keypair,
+        }
+    }
+
+    /// # 从私钥创建钱包
+    ///
+    /// ## 入参
+    /// + `secret_key: &str`: 私钥的hex字符串，可带0x前缀
+    /// + `curve: Curve`: 椭圆曲线，Secp256k1 or Sm2p256v1
+    ///
+    /// ## 出参
+    /// + `Result<Wallet, Error>`: 私钥不是hex字符串或者不合法时返回`Err`
+    pub fn from_private_key(secret_key: &str, curve: Curve) -> Result<Self, Error> {
+        let bytes = hex::decode(secret_key.strip_prefix(PREFIX_OF_HEX).unwrap_or(secret_key))
+            .map_err(|e| Error::new("secret key is not a hex string").with_source(e))?;
+        KeyPair::try_from_secret_key(&bytes, curve).map(Self::from_keypair)
+    }
+
+    /// # 从BIP39助记词按BIP44路径派生私钥创建钱包
+    ///
+    /// ## 入参
+    /// + `words: &str`: 助记词
+    /// + `passphrase: &str`: 生成种子的密码，没有时传空字符串
+    /// + `path: &str`: 派生路径，例如`m/44'/60'/0'/0/0`
+    /// + `curve: Curve`: 椭圆曲线，Secp256k1 or Sm2p256v1
+    ///
+    /// ## 出参
+    /// + `Result<Wallet, Error>`: 助记词或者路径不合法时返回`Err`
+    pub fn from_mnemonic(words: &str, passphrase: &str, path: &str, curve: Curve) -> Result<Self, Error> {
+        let mnemonic = ::bip39::Mnemonic::from_str(words)
+            .map_err(|e| Error::new(&format!("invalid mnemonic, {}", e)))?;
+        let seed = mnemonic.to_seed(passphrase);
+        let extended = ExtendedPrivateKey::derive(&seed, path, curve)?;
+        KeyPair::try_from_secret_key(&extended.secret(), curve).map(Self::from_keypair)
+    }
+
+    /// # 解密FileKey创建钱包
+    ///
+    /// ## 入参
+    /// + `file_key_json: &str`: FileKey的json字符串
+    /// + `password: &str`: 身份密码
+    ///
+    /// ## 出参
+    /// + `Result<Wallet, Error>`: json不合法或者密码错误时返回`Err`
+    pub fn from_keystore(file_key_json: &str, password: &str) -> Result<Self, Error> {
+        let file_key: FileKey = serde_json::from_str(file_key_json)
+            .map_err(|e| Error::new("invalid file key json").with_source(e))?;
+        file_key.decrypt(password).map(Self::from_keypair)
+    }
+
+    /// # 账户地址
+    pub fn address(&self) -> &str {
+        &self.address
+    }
+
+    /// # 签名交易，签名写入交易的`sign`字段
+    ///
+    /// ## 入参
+    /// + `tx: &mut Transaction`: 待签名的交易
+    /// + `chain_id: u64`: 链ID
+    ///
+    /// ## 出参
+    /// + `String`: 签名
+    pub fn sign_transaction(&self, tx: &mut Transaction, chain_id: u64) -> String {
+        let (_, signature) = tx.sign_with_key_pair(chain_id, &self.keypair);
+        signature
+    }
+
+    /// # 使用身份密码加密私钥，导出FileKey的json字符串
+    ///
+    /// ## 入参
+    /// + `password: &str`: 身份密码
+    ///
+    /// ## 出参
+    /// + `Result<String, Error>`
+    pub fn to_keystore(&self, password: &str) -> Result<String, Error> {
+        let mut secret_key = [0u8; 32];
+        let bytes = self.keypair.secret_key.to_bytes_be();
+        secret_key[32 - bytes.len()..].copy_from_slice(&bytes);
+        let file_key = FileKey::from_secret_key(&secret_key, password.as_bytes(), self.curve);
+        serde_json::to_string(&file_key).map_err(|e| Error::new("serialize file key failed").with_source(e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crypto::transaction::TxType;
+    use model::constants::{ZERO_HASH_STRING, ZERO_ZLTC_ADDRESS};
+
+    use super::*;
+
+    const WORDS: &str = "potato front rug inquiry old author dose little still apart below develop";
+    const PATH: &str = "m/44'/60'/0'/0/0";
+
+    fn new_tx(owner: &str) -> Transaction {
+        let mut tx = Transaction::empty_tx();
+        tx.height = 1;
+        tx.tx_type = TxType::Send;
+        tx.parent_hash = ZERO_HASH_STRING.to_string();
+        tx.daemon_hash = ZERO_HASH_STRING.to_string();
+        tx.owner = owner.to_string();
+        tx.linker = Some(ZERO_ZLTC_ADDRESS.to_string());
+        tx.timestamp = 1719804632;
+        tx
+    }
+
+    #[test]
+    fn test_wallet_constructors() {
+        for (curve, sk) in [
+            (Curve::Secp256k1, "0xdbd91293f324e5e49f040188720c6c9ae7e6cc2b4c5274120ee25808e8f4b6a7"),
+            (Curve::Sm2p256v1, "0x24f5d48f3804af48d7d0f3f02b25bdf7b3f936d8c2c7b04eca415fa83cc02758"),
+        ] {
+            let from_sk = Wallet::from_private_key(sk, curve).unwrap();
+            let from_mnemonic = Wallet::from_mnemonic(WORDS, "Root1234", PATH, curve).unwrap();
+            let from_keystore = Wallet::from_keystore(&from_sk.to_keystore("Root1234").unwrap(), "Root1234").unwrap();
+
+            for wallet in [&from_sk, &from_mnemonic, &from_keystore] {
+                assert_eq!(wallet.curve, curve);
+                assert_eq!(wallet.address(), from_sk.address());
+
+                let mut tx = new_tx(wallet.address());
+                let signature = wallet.sign_transaction(&mut tx, 1);
+                assert_eq!(tx.sign, signature);
+                let hash = tx.signing_hash(1, curve);
+                assert!(wallet.keypair.verify(&hash, &signature));
+            }
+        }
+    }
+
+    #[test]
+    fn test_wallet_invalid_inputs() {
+        assert!(Wallet::from_private_key("0xzz", Curve::Secp256k1).is_err());
+        assert!(Wallet::from_private_key("0x0102", Curve::Secp256k1).is_err());
+        assert!(Wallet::from_mnemonic("potato front rug", "", PATH, Curve::Secp256k1).is_err());
+        assert!(Wallet::from_mnemonic(WORDS, "", "44'/60'", Curve::Secp256k1).is_err());
+        assert!(Wallet::from_keystore("{}", "Root1234").is_err());
+
+        let wallet = Wallet::from_private_key("0x23d5b2a2eb0a9c8b86d62cbc3955cfd1fb26ec576ecc379f402d0f5d2b27a7bb", Curve::Sm2p256v1).unwrap();
+        assert_eq!(wallet.address(), "zltc_Z1pnS94bP4hQSYLs4aP4UwBP9pH8bEvhi");
+        assert!(Wallet::from_keystore(&wallet.to_keystore("Root1234").unwrap(), "wrong").is_err());
+    }
+}